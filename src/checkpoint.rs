@@ -0,0 +1,272 @@
+use crate::error::ParseError;
+use crate::input::{Compression, LogInput};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, Metadata};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bytes at the start of a file hashed to recognise it after rotation or truncation
+const FINGERPRINT_LEN: usize = 1024;
+
+/// How far a source has been read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Device and inode of the file (0 where the platform has none)
+    pub device: u64,
+    pub inode: u64,
+    /// Member inside an archive, for sources read from tar files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<PathBuf>,
+    /// Byte offset into the decoded content after the last line processed
+    pub offset: u64,
+    /// Lines before `offset`, so resumed reads keep their line numbers
+    #[serde(default)]
+    pub lines: usize,
+    /// FNV-1a hash of the first `fingerprint_len` bytes of the file
+    pub fingerprint: String,
+    pub fingerprint_len: usize,
+    pub updated: DateTime<Utc>,
+}
+
+/// Identity of a file as recorded when reading started
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileState {
+    device: u64,
+    inode: u64,
+    /// Up to `FINGERPRINT_LEN` bytes from the start of the file
+    head: Vec<u8>,
+    /// File size, for uncompressed files whose offsets are byte positions on disk
+    plain_len: Option<u64>,
+}
+
+impl FileState {
+    /// Inspect the file behind an input; `None` for stdin
+    pub fn of(input: &LogInput) -> std::io::Result<Option<Self>> {
+        let Some(path) = input.path() else {
+            return Ok(None);
+        };
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        if !metadata.is_file() {
+            return Ok(None);
+        }
+        
+        let mut head = Vec::with_capacity(FINGERPRINT_LEN);
+        (&mut file).take(FINGERPRINT_LEN as u64).read_to_end(&mut head)?;
+        let (device, inode) = file_identity(&metadata);
+        let is_plain = input.member().is_none()
+            && Compression::detect(&head) == Compression::None;
+        
+        Ok(Some(Self {
+            device,
+            inode,
+            head,
+            plain_len: is_plain.then_some(metadata.len()),
+        }))
+    }
+    
+    fn is_same_file(&self, checkpoint: &Checkpoint) -> bool {
+        (self.device, self.inode) == (checkpoint.device, checkpoint.inode) && self.inode != 0
+    }
+}
+
+/// Read positions of every source, persisted as JSON between runs
+#[derive(Debug, Clone, Default)]
+pub struct CheckpointStore {
+    path: PathBuf,
+    entries: BTreeMap<String, Checkpoint>,
+}
+
+impl CheckpointStore {
+    /// Load the store at `path`; a missing file is an empty store
+    pub fn load(path: &Path) -> Result<Self, ParseError> {
+        let entries = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|e| ParseError::ConfigurationError {
+                parameter: "checkpoint_file".to_string(),
+                error_message: format!("Corrupt checkpoint file {}: {}", path.display(), e),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(ParseError::IoError {
+                    operation: format!("reading checkpoint file {}", path.display()),
+                    error_message: e.to_string(),
+                })
+            }
+        };
+        Ok(Self { path: path.to_path_buf(), entries })
+    }
+    
+    /// Saved checkpoint for a source
+    pub fn get(&self, source: &str) -> Option<&Checkpoint> {
+        self.entries.get(source)
+    }
+    
+    /// Offset to resume `source` from. Follows a file renamed by rotation (same
+    /// inode under another name) and starts over when the file was replaced,
+    /// truncated or rewritten.
+    pub fn resume_offset(&self, source: &str, state: &FileState, member: Option<&Path>) -> u64 {
+        self.resume_point(source, state, member).0
+    }
+    
+    /// Offset to resume `source` from and the number of lines before it
+    pub fn resume_point(&self, source: &str, state: &FileState, member: Option<&Path>) -> (u64, usize) {
+        let saved = self.entries.get(source)
+            .filter(|checkpoint| state.inode == 0 || state.is_same_file(checkpoint))
+            .or_else(|| self.entries.values().find(|checkpoint| {
+                state.is_same_file(checkpoint) && checkpoint.member.as_deref() == member
+            }));
+        
+        let Some(checkpoint) = saved else {
+            return (0, 0);
+        };
+        // The file may have grown past the bytes hashed last time
+        let head_matches = state.head.get(..checkpoint.fingerprint_len)
+            .is_some_and(|head| fingerprint(head) == checkpoint.fingerprint);
+        let truncated = state.plain_len.is_some_and(|len| len < checkpoint.offset);
+        
+        if head_matches && !truncated {
+            (checkpoint.offset, checkpoint.lines)
+        } else {
+            (0, 0)
+        }
+    }
+    
+    /// Record how far `source` has been read; saved by `save`. Record a run's
+    /// sources after resolving all of their resume offsets, so a rotated file can
+    /// still be found under its old name.
+    pub fn record(&mut self, source: &str, state: &FileState, member: Option<&Path>, offset: u64, lines: usize) {
+        // A rotated file keeps its entry under the new name only
+        self.entries.retain(|name, checkpoint| {
+            name == source || !state.is_same_file(checkpoint) || checkpoint.member.as_deref() != member
+        });
+        self.entries.insert(source.to_string(), Checkpoint {
+            device: state.device,
+            inode: state.inode,
+            member: member.map(Path::to_path_buf),
+            offset,
+            lines,
+            fingerprint: fingerprint(&state.head),
+            fingerprint_len: state.head.len(),
+            updated: Utc::now(),
+        });
+    }
+    
+    /// Write the store atomically (temporary file, then rename)
+    pub fn save(&self) -> Result<(), ParseError> {
+        let io_error = |e: std::io::Error| ParseError::IoError {
+            operation: format!("writing checkpoint file {}", self.path.display()),
+            error_message: e.to_string(),
+        };
+        
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let temp = self.path.with_extension("json.tmp");
+        let contents = serde_json::to_string_pretty(&self.entries).map_err(|e| io_error(e.into()))?;
+        std::fs::write(&temp, contents).map_err(io_error)?;
+        std::fs::rename(&temp, &self.path).map_err(io_error)
+    }
+}
+
+/// Default checkpoint file: `$XDG_STATE_HOME/tango/checkpoints.json` or `~/.local/state/tango/checkpoints.json`
+pub fn default_checkpoint_path() -> PathBuf {
+    std::env::var_os("XDG_STATE_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("tango")
+        .join("checkpoints.json")
+}
+
+/// 64-bit FNV-1a, stable across builds and platforms
+fn fingerprint(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(unix)]
+fn file_identity(metadata: &Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &Metadata) -> (u64, u64) {
+    (0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tango-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+    
+    fn state(path: &Path) -> FileState {
+        FileState::of(&LogInput::file(path)).unwrap().unwrap()
+    }
+    
+    fn append(path: &Path, text: &str) {
+        std::fs::OpenOptions::new().append(true).create(true).open(path).unwrap()
+            .write_all(text.as_bytes()).unwrap();
+    }
+    
+    #[test]
+    fn test_resume_after_append_and_restart_after_truncation() {
+        let log = temp_path("app.log");
+        std::fs::write(&log, "one\ntwo\n").unwrap();
+        let source = log.to_string_lossy().to_string();
+        
+        let mut store = CheckpointStore::load(&temp_path("append.json")).unwrap();
+        assert_eq!(store.resume_offset(&source, &state(&log), None), 0);
+        store.record(&source, &state(&log), None, 8, 2);
+        store.save().unwrap();
+        
+        // Growing past the fingerprinted head keeps the checkpoint
+        append(&log, &"three\n".repeat(300));
+        let store = CheckpointStore::load(&temp_path("append.json")).unwrap();
+        assert_eq!(store.resume_point(&source, &state(&log), None), (8, 2));
+        
+        // copytruncate-style rotation
+        std::fs::File::create(&log).unwrap();
+        append(&log, "x\n");
+        assert_eq!(store.resume_offset(&source, &state(&log), None), 0);
+        
+        // Rewritten in place with different content
+        std::fs::write(&log, "ONE\nTWO\nTHREE\n").unwrap();
+        assert_eq!(store.resume_offset(&source, &state(&log), None), 0);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_rotated_file_is_followed_by_inode() {
+        let log = temp_path("web.log");
+        let rotated = temp_path("web.log.1");
+        std::fs::write(&log, "a\nb\n").unwrap();
+        let source = log.to_string_lossy().to_string();
+        let rotated_source = rotated.to_string_lossy().to_string();
+        
+        let mut store = CheckpointStore::load(&temp_path("rotate.json")).unwrap();
+        store.record(&source, &state(&log), None, 2, 2);
+        
+        std::fs::rename(&log, &rotated).unwrap();
+        std::fs::write(&log, "fresh\n").unwrap();
+        
+        assert_eq!(store.resume_offset(&rotated_source, &state(&rotated), None), 2);
+        assert_eq!(store.resume_offset(&source, &state(&log), None), 0);
+        
+        store.record(&rotated_source, &state(&rotated), None, 4, 4);
+        store.record(&source, &state(&log), None, 6, 2);
+        assert_eq!(store.get(&rotated_source).map(|c| c.offset), Some(4));
+        assert_eq!(store.get(&source).map(|c| c.offset), Some(6));
+    }
+}
@@ -0,0 +1,400 @@
+use crate::input::{LogInput, MappedFile, MappedLines};
+use crate::format_lock::FormatLocks;
+use crate::multiline::{LineAssembler, LineGroupRef, SourceLine};
+use crate::parse_result::{ParseResult, ParseResultRef};
+use crate::parsers::ParserRegistry;
+use crate::statistics::ParsingStatistics;
+use crate::tango_parser::{TangoConfig, TangoParser};
+use rayon::prelude::*;
+use std::io;
+use std::ops::Range;
+
+/// Bytes of input handed to one worker at a time
+pub const DEFAULT_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Events parsed from one chunk, with lines numbered from the chunk's first line
+struct ParsedChunk<R> {
+    results: Vec<R>,
+    lines: usize,
+    format_locks: FormatLocks,
+    statistics: Option<ParsingStatistics>,
+}
+
+/// Parses large uncompressed files as newline-aligned byte ranges on a rayon
+/// pool of its own, handing events back in file order. Split files, and plain
+/// files read with `parse_input_ref`, are memory-mapped so events can borrow
+/// from the file.
+///
+/// Each chunk is parsed by a fresh parser: format locks and statistics are
+/// merged back into the caller's parser, while learned timestamp formats start
+/// empty (they only decide which format is tried first) and year-less
+/// timestamps are dated from the file's modification time, as a sequential
+/// read dates its first line
+pub struct ChunkedParser {
+    /// Configuration for the per-chunk parsers
+    config: TangoConfig,
+    /// Parsers registered with the parser the chunks are parsed for
+    registry: ParserRegistry,
+    /// Multi-line rules deciding where a chunk may start
+    assembler: LineAssembler,
+    /// Workers the chunks are parsed on
+    pool: rayon::ThreadPool,
+    /// Chunks parsed concurrently
+    jobs: usize,
+    chunk_size: u64,
+}
+
+impl ChunkedParser {
+    /// Chunked parsing with the configuration and multi-line rules of `parser`,
+    /// on `parallel_config.num_threads` workers (0 = one per core)
+    pub fn new(parser: &TangoParser) -> Self {
+        let mut config = parser.get_config().clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(config.parallel_config.num_threads)
+            .build()
+            .expect("failed to start the chunk parsing threads");
+        
+        // Chunks are parsed line by line; the other pipelines are not needed
+        config.enable_streaming = false;
+        config.enable_parallel_processing = false;
+        
+        Self {
+            config,
+            registry: parser.parser_registry().clone(),
+            assembler: parser.line_assembler(),
+            jobs: pool.current_num_threads(),
+            pool,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+    
+    /// Split files into chunks of about `bytes` bytes
+    pub fn with_chunk_size(mut self, bytes: u64) -> Self {
+        self.chunk_size = bytes.max(1);
+        self
+    }
+    
+    /// Parse `input`, passing events to `emit` in file order until it returns false.
+    /// Inputs that cannot be split are parsed on this thread with `parser`
+    pub fn parse_input<F>(&self, parser: &mut TangoParser, input: &LogInput, mut emit: F) -> io::Result<()>
+    where
+        F: FnMut(ParseResult) -> io::Result<bool>,
+    {
+        // Only split files are mapped; owned events gain nothing from borrowing
+        let ranges = match input.plain_file()? {
+            Some(path) => self.split(std::fs::metadata(path)?.len()).map(|ranges| (path, ranges)),
+            None => None,
+        };
+        let Some((path, ranges)) = ranges else {
+            return Self::parse_sequential(parser, input, input.lines()?, &mut emit);
+        };
+        let map = MappedFile::open(path)?;
+        
+        self.parse_waves(
+            parser,
+            &ranges,
+            |range| self.parse_chunk(map.bytes(), input, range, |parser, group| {
+                parser.parse_group_from_input(&group.into_owned(), input)
+            }),
+            |result, lines_before| {
+                result.line_number = result.line_number.map(|line| line + lines_before);
+                result.event.source.line = result.event.source.line.map(|line| line + lines_before);
+            },
+            emit,
+        )
+    }
+    
+    /// `parse_input` handing out events that borrow from the mapped file; other
+    /// inputs are read and parsed as owned events
+    pub fn parse_input_ref<F>(&self, parser: &mut TangoParser, input: &LogInput, mut emit: F) -> io::Result<()>
+    where
+        F: for<'m> FnMut(ParseResultRef<'m>) -> io::Result<bool>,
+    {
+        let Some(path) = input.plain_file()? else {
+            return Self::parse_sequential(parser, input, input.lines()?, &mut |result| emit(result.into()));
+        };
+        let map = MappedFile::open(path)?;
+        let Some(ranges) = self.split(map.len()) else {
+            for group in parser.group_line_refs(map.lines()) {
+                let group = group?;
+                if group.is_blank() {
+                    continue;
+                }
+                if !emit(parser.parse_group_ref_from_input(&group, input))? {
+                    break;
+                }
+            }
+            return Ok(());
+        };
+        
+        self.parse_waves(
+            parser,
+            &ranges,
+            |range| self.parse_chunk(map.bytes(), input, range, |parser, group| {
+                parser.parse_group_ref_from_input(&group, input)
+            }),
+            |result, lines_before| {
+                result.line_number = result.line_number.map(|line| line + lines_before);
+                result.event.source.line = result.event.source.line.map(|line| line + lines_before);
+            },
+            emit,
+        )
+    }
+    
+    /// Parse lines on this thread with `parser`
+    fn parse_sequential<I, L>(
+        parser: &mut TangoParser,
+        input: &LogInput,
+        lines: I,
+        emit: &mut impl FnMut(ParseResult) -> io::Result<bool>,
+    ) -> io::Result<()>
+    where
+        I: Iterator<Item = io::Result<L>>,
+        L: Into<SourceLine>,
+    {
+        for group in parser.group_lines(lines) {
+            let group = group?;
+            if group.is_blank() {
+                continue;
+            }
+            if !emit(parser.parse_group_from_input(&group, input))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Byte ranges to parse concurrently, or None when the input is read sequentially
+    fn split(&self, len: u64) -> Option<Vec<Range<u64>>> {
+        // An explicit year applies to the first line; only a sequential read carries it forward
+        if self.jobs <= 1 || self.config.year.is_some() || len <= self.chunk_size {
+            return None;
+        }
+        // Without event starts every chunk would scan to the end of the input
+        if !self.assembler.finds_event_starts() {
+            return None;
+        }
+        
+        let ranges = (0..len).step_by(self.chunk_size as usize)
+            .map(|start| start..(start + self.chunk_size).min(len))
+            .collect();
+        Some(ranges)
+    }
+    
+    /// Parse `ranges` a wave of `jobs` chunks at a time, emitting each wave's events
+    /// in order; `renumber` adds the lines before a chunk to its events' line numbers
+    fn parse_waves<R: Send>(
+        &self,
+        parser: &mut TangoParser,
+        ranges: &[Range<u64>],
+        parse_chunk: impl Fn(Range<u64>) -> io::Result<ParsedChunk<R>> + Sync,
+        renumber: impl Fn(&mut R, usize),
+        mut emit: impl FnMut(R) -> io::Result<bool>,
+    ) -> io::Result<()> {
+        let mut lines_before = 0;
+        for wave in ranges.chunks(self.jobs) {
+            let chunks = self.pool.install(|| {
+                wave.par_iter()
+                    .map(|range| parse_chunk(range.clone()))
+                    .collect::<io::Result<Vec<_>>>()
+            })?;
+            
+            for chunk in chunks {
+                parser.merge_format_locks(&chunk.format_locks);
+                if let Some(statistics) = &chunk.statistics {
+                    parser.merge_statistics(statistics);
+                }
+                for mut result in chunk.results {
+                    renumber(&mut result, lines_before);
+                    if !emit(result)? {
+                        return Ok(());
+                    }
+                }
+                lines_before += chunk.lines;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Parse the events starting in `range` of the mapped `data`. An event belongs
+    /// to the chunk its first line starts in, so the chunk skips the tail of the
+    /// previous chunk's last event and reads past its end to complete its own
+    fn parse_chunk<'m, R>(
+        &self,
+        data: &'m [u8],
+        input: &LogInput,
+        range: Range<u64>,
+        parse_group: impl Fn(&mut TangoParser, LineGroupRef<'m>) -> R,
+    ) -> io::Result<ParsedChunk<R>> {
+        let mut start = range.start as usize;
+        if start > 0 {
+            // The line holding byte `start - 1` was started by the previous chunk
+            start = memchr::memchr(b'\n', &data[start - 1..]).map_or(data.len(), |end| start + end);
+        }
+        
+        let mut parser = TangoParser::with_config(self.config.clone())
+            .with_parser_registry(self.registry.clone());
+        parser.set_source_modified(input.source(), input.modified());
+        
+        // Each line is paired with the one before, which may be a partial CRI line it continues
+        let mut previous = start.checked_sub(1).and_then(|end| {
+            let begin = memchr::memrchr(b'\n', &data[..end]).map_or(0, |newline| newline + 1);
+            std::str::from_utf8(&data[begin..end]).ok().map(|line| line.trim_end_matches('\r'))
+        });
+        let mut owned = 0;
+        let lines = MappedLines::new(&data[start..], start as u64)
+            .map(|line| {
+                let before = match &line {
+                    Ok(line) => previous.replace(line.text),
+                    Err(_) => previous.take(),
+                };
+                (before, line)
+            })
+            .skip_while(|(before, line)| range.start > 0 && matches!(line, Ok(line) if !self.assembler.starts_event_after(*before, line.text)))
+            .take_while(|(before, line)| !matches!(line, Ok(line) if line.offset >= Some(range.end) && self.assembler.starts_event_after(*before, line.text)))
+            .map(|(_, line)| line.map(|line| {
+                owned += 1;
+                SourceLine { number: Some(owned), ..line }
+            }));
+        
+        let mut results = Vec::new();
+        for group in parser.group_line_refs(lines) {
+            let group = group?;
+            if group.is_blank() {
+                continue;
+            }
+            results.push(parse_group(&mut parser, group));
+        }
+        
+        Ok(ParsedChunk {
+            results,
+            lines: owned,
+            format_locks: parser.format_locks().clone(),
+            statistics: parser.get_statistics().cloned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    
+    fn parse_all(chunked: &ChunkedParser, input: &LogInput) -> Vec<ParseResult> {
+        let mut parser = TangoParser::new();
+        let mut results = Vec::new();
+        chunked.parse_input(&mut parser, input, |result| {
+            results.push(result);
+            Ok(true)
+        }).unwrap();
+        results
+    }
+    
+    #[test]
+    fn test_chunks_match_sequential_parse() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..200 {
+            writeln!(file, r#"{{"level":"info","msg":"request {}"}}"#, i).unwrap();
+            if i % 7 == 0 {
+                writeln!(file, "2024-01-15 10:30:{:02} ERROR failed {}", i % 60, i).unwrap();
+                writeln!(file, "    at com.example.Handler.run(Handler.java:{})", i).unwrap();
+            }
+        }
+        file.flush().unwrap();
+        let input = LogInput::file(file.path());
+        
+        let parser = TangoParser::new();
+        let sequential = parse_all(&ChunkedParser::new(&parser).with_chunk_size(u64::MAX), &input);
+        let mut chunked = ChunkedParser::new(&parser).with_chunk_size(97);
+        chunked.jobs = 3;
+        let chunked = parse_all(&chunked, &input);
+        
+        assert_eq!(sequential.len(), 229);
+        assert_eq!(chunked.len(), sequential.len());
+        for (chunked, sequential) in chunked.iter().zip(&sequential) {
+            assert_eq!(chunked.event.raw, sequential.event.raw);
+            assert_eq!(chunked.event.source.line, sequential.event.source.line);
+            assert_eq!(chunked.event.source.offset, sequential.event.source.offset);
+        }
+        assert!(chunked[1].event.fields.contains_key("stack_trace"));
+    }
+    
+    #[test]
+    fn test_jobs_and_statistics_follow_the_callers_parser() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..120 {
+            writeln!(file, "ts=2024-01-15T10:30:01Z level=info msg=\"done {}\" user=u{}", i, i).unwrap();
+        }
+        file.flush().unwrap();
+        let input = LogInput::file(file.path());
+        
+        // Each chunked parser gets the worker count it is configured with
+        for num_threads in [2, 3] {
+            let mut config = TangoConfig::default();
+            config.parallel_config.num_threads = num_threads;
+            assert_eq!(ChunkedParser::new(&TangoParser::with_config(config)).jobs, num_threads);
+        }
+        
+        let mut config = TangoConfig::default();
+        config.parallel_config.num_threads = 3;
+        let mut parser = TangoParser::with_config(config);
+        let chunked = ChunkedParser::new(&parser).with_chunk_size(500);
+        assert!(chunked.split(std::fs::metadata(file.path()).unwrap().len()).unwrap().len() > 3);
+        chunked.parse_input(&mut parser, &input, |_| Ok(true)).unwrap();
+        
+        let statistics = parser.get_statistics().unwrap();
+        assert_eq!(statistics.total_lines, 120);
+        assert_eq!(statistics.format_distribution.get(&crate::models::FormatType::Logfmt), Some(&120));
+    }
+    
+    #[test]
+    fn test_end_marker_alone_reads_sequentially() {
+        let mut config = TangoConfig::default();
+        config.multiline.end_pattern = Some(";$".to_string());
+        let mut chunked = ChunkedParser::new(&TangoParser::with_config(config)).with_chunk_size(10);
+        chunked.jobs = 2;
+        assert_eq!(chunked.split(100), None);
+        
+        config = TangoConfig::default();
+        config.multiline.start_pattern = Some("^BEGIN".to_string());
+        config.multiline.end_pattern = Some(";$".to_string());
+        let mut chunked = ChunkedParser::new(&TangoParser::with_config(config)).with_chunk_size(10);
+        chunked.jobs = 2;
+        assert_eq!(chunked.split(100).map(|ranges| ranges.len()), Some(10));
+    }
+    
+    #[test]
+    fn test_borrowed_chunks_match_owned_parse() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for i in 0..100 {
+            writeln!(file, r#"{{"ts":"2024-01-15T10:30:00Z","level":"warn","msg":"slow \"{}\"","http":{{"status":{}}}}}"#, i, 200 + i).unwrap();
+            writeln!(file, "ts=2024-01-15T10:30:01Z level=info msg=\"done {}\" user=u{}", i, i).unwrap();
+            if i % 9 == 0 {
+                writeln!(file, "2024-01-15 10:30:02 ERROR failed {}", i).unwrap();
+                writeln!(file, "\tat com.example.Handler.run(Handler.java:{})", i).unwrap();
+            }
+        }
+        file.flush().unwrap();
+        let input = LogInput::file(file.path());
+        
+        let parser = TangoParser::new();
+        let mut chunked = ChunkedParser::new(&parser).with_chunk_size(541);
+        chunked.jobs = 2;
+        let owned = parse_all(&chunked, &input);
+        
+        for chunked in [chunked, ChunkedParser::new(&parser).with_chunk_size(u64::MAX)] {
+            let mut parser = TangoParser::new();
+            let mut borrowed = Vec::new();
+            chunked.parse_input_ref(&mut parser, &input, |result| {
+                borrowed.push(result.into_owned());
+                Ok(true)
+            }).unwrap();
+            
+            assert_eq!(borrowed.len(), owned.len());
+            for (borrowed, owned) in borrowed.iter().zip(&owned) {
+                assert_eq!(borrowed.event, owned.event);
+                assert_eq!(borrowed.line_number, owned.line_number);
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::models::*;
-#[cfg(test)]
 use crate::parse_result::ParseResult;
 use crate::parsers::*;
+use crate::field_mapping::FieldMappings;
 use std::collections::HashMap;
 
 /// Interface for format classification
@@ -301,25 +301,28 @@ pub struct CacheStats {
     pub total_samples: usize,
 }
 
+/// Detected format with its confidence, timestamp format and field mappings
+type Detection = (FormatType, f64, Option<String>, HashMap<String, String>);
+
 /// Multi-stage format classifier with detection heuristics
 #[derive(Clone)]
 pub struct TangoFormatClassifier {
     /// Cache of detected formats per source with performance optimization
     format_cache: FormatCache,
     
-    /// Parser instances for format detection
-    json_parser: JsonParser,
-    logfmt_parser: LogfmtParser,
-    pattern_parser: PatternParser,
+    /// Parsers detection tries, built-in and custom, in trial order
+    registry: ParserRegistry,
+    
+    /// The registry with other field mappings, for sources matching each pattern
+    source_registries: Vec<(glob::Pattern, ParserRegistry)>,
 }
 
 impl TangoFormatClassifier {
     pub fn new() -> Self {
         Self {
             format_cache: FormatCache::new(),
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
+            registry: ParserRegistry::new(),
+            source_registries: Vec::new(),
         }
     }
     
@@ -327,107 +330,144 @@ impl TangoFormatClassifier {
     pub fn with_cache_settings(max_entries: usize, max_age_seconds: i64, min_samples: usize) -> Self {
         Self {
             format_cache: FormatCache::with_settings(max_entries, max_age_seconds, min_samples),
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
+            registry: ParserRegistry::new(),
+            source_registries: Vec::new(),
         }
     }
     
-    /// Multi-stage detection algorithm with metadata extraction
-    /// 1. Check format cache for known source
-    /// 2. Attempt JSON parsing (fast fail on syntax error)
-    /// 3. Analyze key=value density for logfmt detection
-    /// 4. Match timestamp and level patterns using regex
-    /// 5. Default to plain text processing
-    fn detect_format_internal(&self, line: &str) -> (FormatType, f64, Option<String>, HashMap<String, String>) {
-        let trimmed_line = line.trim();
-        let mut field_mappings = HashMap::new();
-        let mut timestamp_format = None;
-        
-        // Stage 1: JSON detection (starts with '{', valid parse)
-        if trimmed_line.starts_with('{') {
-            if self.json_parser.can_parse(line) {
-                let result = self.json_parser.parse(line);
-                if result.success {
-                    // Extract field mappings from JSON parsing
-                    if result.event.timestamp.is_some() {
-                        timestamp_format = Some("ISO8601".to_string());
-                    }
-                    
-                    // Add common JSON field mappings
-                    field_mappings.insert("timestamp_fields".to_string(), "ts,time,timestamp,@timestamp".to_string());
-                    field_mappings.insert("level_fields".to_string(), "level,severity,lvl,log.level".to_string());
-                    field_mappings.insert("message_fields".to_string(), "msg,message,log.message".to_string());
-                    
-                    return (FormatType::Json, result.confidence, timestamp_format, field_mappings);
-                }
-            }
-        }
-        
-        // Stage 2: Pattern detection BEFORE logfmt (syslog lines contain key=value but are not logfmt)
-        // Check pattern parser first if line looks like it could be syslog/structured pattern
-        if self.pattern_parser.can_parse(line) {
-            let result = self.pattern_parser.parse(line);
-            if result.success && result.event.timestamp.is_some() {
-                // Extract timestamp format information
-                if line.contains('T') && (line.contains('Z') || line.contains('+')) {
-                    timestamp_format = Some("ISO8601".to_string());
-                } else if line.contains('[') && line.contains(']') {
-                    timestamp_format = Some("bracketed".to_string());
-                } else {
-                    timestamp_format = Some("space_separated".to_string());
-                }
-                
-                // Add pattern-based field mappings
-                field_mappings.insert("pattern_type".to_string(), "timestamp_level".to_string());
-                
-                return (FormatType::TimestampLevel, result.confidence, timestamp_format, field_mappings);
+    /// Detect with the parsers in `registry`, which includes the built-ins
+    pub fn with_registry(mut self, registry: ParserRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+    
+    /// Replace the registered parsers; per-source field mappings are kept
+    pub fn set_registry(&mut self, registry: ParserRegistry) {
+        self.source_registries = std::mem::take(&mut self.source_registries).into_iter()
+            .map(|(pattern, source_registry)| {
+                let field_mappings = source_registry.field_mappings().clone();
+                (pattern, registry.clone().with_field_mappings(field_mappings))
+            })
+            .collect();
+        self.registry = registry;
+    }
+    
+    /// Read canonical fields with other mappings for sources matching each
+    /// pattern, first match wins
+    pub fn set_source_field_mappings(&mut self, rules: Vec<(glob::Pattern, FieldMappings)>) {
+        self.source_registries = rules.into_iter()
+            .map(|(pattern, field_mappings)| (pattern, self.registry.clone().with_field_mappings(field_mappings)))
+            .collect();
+    }
+    
+    pub fn registry(&self) -> &ParserRegistry {
+        &self.registry
+    }
+    
+    /// Registry lines from `source` are parsed with, holding its field mappings
+    pub fn registry_for(&self, source: &str) -> &ParserRegistry {
+        self.source_registries.iter()
+            .find(|(pattern, _)| pattern.matches(source))
+            .map_or(&self.registry, |(_, registry)| registry)
+    }
+    
+    /// Detection with metadata extraction: the first parser in the registry's
+    /// trial order that detects and parses the line wins, so custom parsers
+    /// above priority 0 come first, then container envelopes and klog, JSON,
+    /// RFC 5424, the built-in profiles, timestamp/level patterns, logfmt, the
+    /// other custom parsers and finally plain text
+    fn detect_format_internal(&self, line: &str, source: &str) -> Detection {
+        let registry = self.registry_for(source);
+        for parser in registry.parsers() {
+            let format_type = parser.format_type();
+            if format_type == FormatType::PlainText || !parser.detects(line) {
+                continue;
             }
-        }
-        
-        // Stage 3: Logfmt detection (key=value density analysis)
-        if self.logfmt_parser.can_parse(line) {
-            let result = self.logfmt_parser.parse(line);
+            let result = parser.parse(line);
             if result.success {
-                // Extract field mappings from logfmt parsing
-                if result.event.timestamp.is_some() {
-                    timestamp_format = Some("logfmt_inferred".to_string());
-                }
-                
-                // Add common logfmt field mappings
-                field_mappings.insert("timestamp_fields".to_string(), "ts,time,timestamp".to_string());
-                field_mappings.insert("level_fields".to_string(), "level,severity,lvl".to_string());
-                field_mappings.insert("message_fields".to_string(), "msg,message".to_string());
-                
-                return (FormatType::Logfmt, result.confidence, timestamp_format, field_mappings);
+                let format_type = self.prefer_source_profile(format_type, line, source);
+                let (timestamp_format, field_mappings) = Self::detection_metadata(&format_type, &result, line, registry);
+                return (format_type, result.confidence, timestamp_format, field_mappings);
             }
         }
         
-        // Stage 4: Pattern detection fallback (for patterns without timestamps)
-        if self.pattern_parser.can_parse(line) {
-            let result = self.pattern_parser.parse(line);
-            if result.success {
-                // Extract timestamp format information
-                if result.event.timestamp.is_some() {
-                    // Determine timestamp format based on the line content
-                    if line.contains('T') && (line.contains('Z') || line.contains('+')) {
-                        timestamp_format = Some("ISO8601".to_string());
-                    } else if line.contains('[') && line.contains(']') {
-                        timestamp_format = Some("bracketed".to_string());
-                    } else {
-                        timestamp_format = Some("space_separated".to_string());
-                    }
-                }
-                
-                // Add pattern-based field mappings
+        // Default to plain text
+        (FormatType::PlainText, 0.1, None, HashMap::new()) // Low confidence for plain text
+    }
+    
+    /// Apache and nginx share the combined layout, so a line Apache takes is
+    /// nginx when the source names it and the nginx profile parses it too
+    fn prefer_source_profile(&self, format_type: FormatType, line: &str, source: &str) -> FormatType {
+        let nginx = FormatType::Profile(ProfileType::Nginx);
+        if format_type == FormatType::Profile(ProfileType::Apache)
+            && source.to_ascii_lowercase().contains("nginx")
+            && self.get_confidence(line, nginx.clone()) > 0.0
+        {
+            return nginx;
+        }
+        format_type
+    }
+    
+    /// Timestamp format and field mappings recorded for a detected format
+    fn detection_metadata(
+        format_type: &FormatType,
+        result: &ParseResult,
+        line: &str,
+        registry: &ParserRegistry,
+    ) -> (Option<String>, HashMap<String, String>) {
+        let mut field_mappings = HashMap::new();
+        let timestamp_format = match format_type {
+            FormatType::DockerJson | FormatType::Cri | FormatType::Klog | FormatType::Rfc5424 => {
+                let (pattern_type, timestamp_format) = match format_type {
+                    FormatType::DockerJson => ("docker", "RFC3339"),
+                    FormatType::Cri => ("cri", "RFC3339"),
+                    FormatType::Klog => ("klog", "klog"),
+                    _ => ("rfc5424", "RFC3339"),
+                };
+                field_mappings.insert("pattern_type".to_string(), pattern_type.to_string());
+                Some(timestamp_format.to_string())
+            }
+            FormatType::Json | FormatType::Logfmt => {
+                // The keys this source's canonical fields are read from
+                field_mappings.extend(registry.field_mappings().describe());
+                let inferred = if *format_type == FormatType::Json { "ISO8601" } else { "logfmt_inferred" };
+                result.event.timestamp.map(|_| inferred.to_string())
+            }
+            FormatType::Profile(profile_type) => {
+                field_mappings.insert("pattern_type".to_string(), format!("{:?}", profile_type).to_lowercase());
+                let timestamp_format = match profile_type {
+                    ProfileType::Syslog => "syslog",
+                    _ => "clf",
+                };
+                Some(timestamp_format.to_string())
+            }
+            FormatType::TimestampLevel | FormatType::Pattern => {
                 field_mappings.insert("pattern_type".to_string(), "timestamp_level".to_string());
-                
-                return (FormatType::TimestampLevel, result.confidence, timestamp_format, field_mappings);
+                let timestamp_format = if line.contains('T') && (line.contains('Z') || line.contains('+')) {
+                    "ISO8601"
+                } else if line.contains('[') && line.contains(']') {
+                    "bracketed"
+                } else {
+                    "space_separated"
+                };
+                Some(timestamp_format.to_string())
             }
-        }
-        
-        // Stage 4: Default to plain text
-        (FormatType::PlainText, 0.1, None, HashMap::new()) // Low confidence for plain text
+            FormatType::Custom(_) | FormatType::PlainText => None,
+        };
+        (timestamp_format, field_mappings)
+    }
+    
+    /// Formats detection tries before falling back to plain text
+    pub fn detectable_formats(&self) -> Vec<FormatType> {
+        self.registry.parsers().iter()
+            .map(|parser| parser.format_type())
+            .filter(|format_type| *format_type != FormatType::PlainText)
+            .collect()
+    }
+    
+    /// Parse a line with a built-in profile; `None` for profile types that need configuration
+    pub fn parse_with_builtin_profile(&self, profile_type: ProfileType, line: &str) -> Option<ParseResult> {
+        self.registry.for_format(FormatType::Profile(profile_type)).map(|parser| parser.parse(line))
     }
     
     /// Get cached format for a source, if available
@@ -457,10 +497,10 @@ impl TangoFormatClassifier {
 }
 
 impl FormatClassifier for TangoFormatClassifier {
-    fn detect_format(&self, line: &str, _source: &str) -> FormatType {
+    fn detect_format(&self, line: &str, source: &str) -> FormatType {
         // For the trait implementation, we can't use caching due to immutable self
         // The cache_format method should be called separately to update the cache
-        let (format_type, _confidence, _timestamp_format, _field_mappings) = self.detect_format_internal(line);
+        let (format_type, _confidence, _timestamp_format, _field_mappings) = self.detect_format_internal(line, source);
         format_type
     }
     
@@ -469,15 +509,11 @@ impl FormatClassifier for TangoFormatClassifier {
         let mut field_mappings = HashMap::new();
         let timestamp_format = match format {
             FormatType::Json => {
-                field_mappings.insert("timestamp_fields".to_string(), "ts,time,timestamp,@timestamp".to_string());
-                field_mappings.insert("level_fields".to_string(), "level,severity,lvl,log.level".to_string());
-                field_mappings.insert("message_fields".to_string(), "msg,message,log.message".to_string());
+                field_mappings.extend(self.registry_for(&source).field_mappings().describe());
                 Some("ISO8601".to_string())
             }
             FormatType::Logfmt => {
-                field_mappings.insert("timestamp_fields".to_string(), "ts,time,timestamp".to_string());
-                field_mappings.insert("level_fields".to_string(), "level,severity,lvl".to_string());
-                field_mappings.insert("message_fields".to_string(), "msg,message".to_string());
+                field_mappings.extend(self.registry_for(&source).field_mappings().describe());
                 Some("logfmt_inferred".to_string())
             }
             FormatType::TimestampLevel => {
@@ -493,40 +529,16 @@ impl FormatClassifier for TangoFormatClassifier {
     fn get_confidence(&self, line: &str, format: FormatType) -> f64 {
         // Get confidence score for a specific format detection
         match format {
-            FormatType::Json => {
-                if self.json_parser.can_parse(line) {
-                    let result = self.json_parser.parse(line);
-                    result.confidence
-                } else {
-                    0.0
-                }
-            }
-            FormatType::Logfmt => {
-                if self.logfmt_parser.can_parse(line) {
-                    let result = self.logfmt_parser.parse(line);
-                    result.confidence
-                } else {
-                    0.0
-                }
-            }
-            FormatType::Pattern => {
-                if self.pattern_parser.can_parse(line) {
-                    let result = self.pattern_parser.parse(line);
-                    result.confidence
-                } else {
-                    0.0
-                }
-            }
-            FormatType::TimestampLevel => {
-                if self.pattern_parser.can_parse(line) {
-                    let result = self.pattern_parser.parse(line);
-                    result.confidence
-                } else {
-                    0.0
-                }
-            }
             FormatType::PlainText => 0.1, // Low confidence for plain text
-            FormatType::Profile(_) => 0.9, // High confidence for user-defined profiles
+            _ => match self.registry.for_format(format.clone()) {
+                Some(parser) if parser.detects(line) => {
+                    let result = parser.parse(line);
+                    if result.success { result.confidence } else { 0.0 }
+                }
+                Some(_) => 0.0,
+                None if matches!(format, FormatType::Profile(_)) => 0.9, // High confidence for user-defined profiles
+                None => 0.0,
+            },
         }
     }
 }
@@ -536,17 +548,23 @@ impl TangoFormatClassifier {
     pub fn detect_format_with_caching(&mut self, line: &str, source: &str) -> FormatType {
         // Check cache first for known source
         if let Some(cached_entry) = self.format_cache.get(source) {
-            return cached_entry.format_type;
+            return cached_entry.format_type.clone();
         }
         
         // Perform detection if not cached
-        let (format_type, confidence, timestamp_format, field_mappings) = self.detect_format_internal(line);
+        let (format_type, confidence, timestamp_format, field_mappings) = self.detect_format_internal(line, source);
         
         // Cache the result
-        self.format_cache.put(source.to_string(), format_type, confidence, timestamp_format, field_mappings);
+        self.format_cache.put(source.to_string(), format_type.clone(), confidence, timestamp_format, field_mappings);
         
         format_type
     }
+    
+    /// Full detection returning the winning format and its confidence
+    pub fn detect_format_with_confidence(&self, line: &str, source: &str) -> (FormatType, f64) {
+        let (format_type, confidence, _timestamp_format, _field_mappings) = self.detect_format_internal(line, source);
+        (format_type, confidence)
+    }
 }
 
 #[cfg(test)]
@@ -574,8 +592,8 @@ mod tests {
     impl FormatClassifier for MockClassifier {
         fn detect_format(&self, line: &str, source: &str) -> FormatType {
             // Check cache first
-            if let Some(&cached_format) = self.cache.get(source) {
-                return cached_format;
+            if let Some(cached_format) = self.cache.get(source) {
+                return cached_format.clone();
             }
             
             // Simple mock detection logic
@@ -584,7 +602,7 @@ mod tests {
             } else if line.contains('=') {
                 FormatType::Logfmt
             } else {
-                self.default_format
+                self.default_format.clone()
             }
         }
         
@@ -620,6 +638,26 @@ mod tests {
         assert!(confidence > 0.9); // Should have high confidence for valid JSON
     }
     
+    #[test]
+    fn test_tango_format_classifier_builtin_profile_detection() {
+        let classifier = TangoFormatClassifier::new();
+        
+        let access_line = r#"203.0.113.9 - - [10/Oct/2024:13:55:36 +0000] "GET /login?next=%2F HTTP/1.1" 302 0 "-" "curl/8.0""#;
+        assert_eq!(classifier.detect_format(access_line, "access.log"), FormatType::Profile(ProfileType::Apache));
+        assert_eq!(classifier.detect_format(access_line, "/var/log/nginx/access.log"), FormatType::Profile(ProfileType::Nginx));
+        assert!(classifier.get_confidence(access_line, FormatType::Profile(ProfileType::Nginx)) > 0.8);
+        
+        let common_line = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#;
+        assert_eq!(classifier.detect_format(common_line, "access.log"), FormatType::Profile(ProfileType::Apache));
+        
+        let syslog_line = "<34>Oct 11 22:14:15 mymachine su: 'su root' failed for lonvick on /dev/pts/8";
+        assert_eq!(classifier.detect_format(syslog_line, "messages"), FormatType::Profile(ProfileType::Syslog));
+        
+        // Pattern-parsed syslog keeps its classification
+        let plain_syslog = "Jun 14 15:16:01 combo sshd(pam_unix)[19939]: authentication failure";
+        assert_eq!(classifier.detect_format(plain_syslog, "messages"), FormatType::TimestampLevel);
+    }
+    
     #[test]
     fn test_tango_format_classifier_logfmt_detection() {
         let classifier = TangoFormatClassifier::new();
@@ -648,6 +686,21 @@ mod tests {
         assert!(confidence > 0.8); // Should have good confidence for valid patterns
     }
     
+    #[test]
+    fn test_tango_format_classifier_rfc5424_detection() {
+        let classifier = TangoFormatClassifier::new();
+        
+        // Structured data must not be mistaken for logfmt pairs
+        let rfc5424_line = r#"<165>1 2025-01-01T12:00:00.003Z web01 nginx 4242 ACCESS [req@32473 method="GET" path="/" status="200"] served"#;
+        let detected_format = classifier.detect_format(rfc5424_line, "syslog");
+        assert_eq!(detected_format, FormatType::Rfc5424);
+        assert!(classifier.get_confidence(rfc5424_line, FormatType::Rfc5424) > 0.9);
+        
+        // RFC 3164 lines are left to the pattern stages
+        let rfc3164_line = "<34>Oct 11 22:14:15 mymachine su: 'su root' failed";
+        assert_ne!(classifier.detect_format(rfc3164_line, "syslog"), FormatType::Rfc5424);
+    }
+    
     #[test]
     fn test_tango_format_classifier_plain_text_fallback() {
         let classifier = TangoFormatClassifier::new();
@@ -677,6 +730,27 @@ mod tests {
         assert_eq!(detected_format, FormatType::Logfmt);
     }
     
+    #[test]
+    fn test_detection_follows_registry_order() {
+        let classifier = TangoFormatClassifier::new();
+        let lines = [
+            r#"{"log":"started\n","stream":"stdout","time":"2025-01-01T12:00:00Z"}"#,
+            r#"{"message": "test=value", "level": "info"}"#,
+            r#"<165>1 2025-01-01T12:00:00.003Z web01 nginx 4242 ACCESS [req@32473 method="GET"] served"#,
+            r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326"#,
+            "<34>Oct 11 22:14:15 mymachine su: 'su root' failed for lonvick on /dev/pts/8",
+            "[2025-12-29T10:21:03Z] [INFO] Application started successfully",
+            "level=info msg=test time=2025-12-29T10:21:03Z user=john action=login",
+            "This is a plain text log message without structure",
+        ];
+        
+        // Detection and the registry's own trial order pick the same parser
+        for line in lines {
+            let (format_type, _) = classifier.registry().parse_first(line);
+            assert_eq!(classifier.detect_format(line, "app.log"), format_type, "{}", line);
+        }
+    }
+    
     #[test]
     fn test_tango_format_classifier_caching() {
         let mut classifier = TangoFormatClassifier::new();
@@ -1153,7 +1227,7 @@ mod tests {
         assert_eq!(detected, FormatType::TimestampLevel);
         
         // Cache the result
-        classifier.cache_format(source.to_string(), detected);
+        classifier.cache_format(source.to_string(), detected.clone());
         
         // Verify confidence
         let confidence = classifier.get_confidence(line, detected);
@@ -1164,6 +1238,27 @@ mod tests {
         assert_eq!(cached_result, FormatType::TimestampLevel);
     }
     
+    #[test]
+    fn test_cached_field_mappings_follow_source() {
+        let mut classifier = TangoFormatClassifier::new();
+        let billing = FieldMappings {
+            level: vec!["sev".to_string(), "log.level".to_string()],
+            ..FieldMappings::default()
+        };
+        classifier.set_source_field_mappings(vec![(glob::Pattern::new("billing-*").unwrap(), billing)]);
+        
+        let line = r#"{"sev":"warn","msg":"charged"}"#;
+        classifier.detect_format_with_caching(line, "billing-1.log");
+        classifier.detect_format_with_caching(line, "app.log");
+        
+        let level_fields = |classifier: &mut TangoFormatClassifier, source: &str| {
+            classifier.get_cached_format(source).unwrap().field_mappings["level_fields"].clone()
+        };
+        assert_eq!(level_fields(&mut classifier, "billing-1.log"), "sev,log.level");
+        assert_eq!(level_fields(&mut classifier, "app.log"), "level,severity,lvl,log.level");
+        assert_eq!(classifier.registry_for("billing-1.log").parse_as(FormatType::Json, line).event.level, Some(LogLevel::Warn));
+    }
+    
     // Property-based tests for format classifier
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
@@ -1267,7 +1362,7 @@ mod tests {
         match &log_line_type {
             LogLineType::Json(_) => {
                 // JSON logs should be detected as JSON if they're valid
-                if classifier.json_parser.can_parse(&log_line) {
+                if JsonParser::new().can_parse(&log_line) {
                     detected_format == FormatType::Json
                 } else {
                     // If JSON parsing fails, it should fall back appropriately
@@ -1287,7 +1382,7 @@ mod tests {
             }
             LogLineType::TimestampLevel(_, _, _) => {
                 // Timestamp+level logs should be detected as such if they have both components
-                if classifier.pattern_parser.can_parse(&log_line) {
+                if PatternParser::new().can_parse(&log_line) {
                     detected_format == FormatType::TimestampLevel
                 } else {
                     // If pattern doesn't match, should fall back
@@ -1337,7 +1432,7 @@ mod tests {
         
         // Confidence should be consistent for the same line and format
         let detected_format = classifier.detect_format(&log_line, "test.log");
-        let confidence1 = classifier.get_confidence(&log_line, detected_format);
+        let confidence1 = classifier.get_confidence(&log_line, detected_format.clone());
         let confidence2 = classifier.get_confidence(&log_line, detected_format);
         
         // Confidence should be the same for identical inputs
@@ -1401,7 +1496,7 @@ mod tests {
         // Verify detection follows expected heuristics
         if starts_with_brace && log_line.starts_with('{') {
             // Should attempt JSON detection first
-            if classifier.json_parser.can_parse(&log_line) {
+            if JsonParser::new().can_parse(&log_line) {
                 detected_format == FormatType::Json
             } else {
                 // If JSON parsing fails, should fall back
@@ -1409,7 +1504,7 @@ mod tests {
             }
         } else if has_equals_signs >= 3 {
             // Should attempt logfmt detection
-            if classifier.logfmt_parser.can_parse(&log_line) {
+            if LogfmtParser::new().can_parse(&log_line) {
                 detected_format == FormatType::Logfmt
             } else {
                 // If logfmt parsing fails, should fall back
@@ -1417,7 +1512,7 @@ mod tests {
             }
         } else if has_timestamp_pattern && has_level_token {
             // Should attempt timestamp+level detection
-            if classifier.pattern_parser.can_parse(&log_line) {
+            if PatternParser::new().can_parse(&log_line) {
                 detected_format == FormatType::TimestampLevel
             } else {
                 // If pattern parsing fails, should fall back
@@ -1613,7 +1708,7 @@ mod tests {
             if *should_cache && expected_format.is_none() {
                 // First detection - establish the expected format
                 let detected_format = classifier.detect_format_with_caching(log_line, &source_name);
-                expected_format = Some(detected_format);
+                expected_format = Some(detected_format.clone());
                 
                 // Verify the format was cached
                 let cached_entry = classifier.get_cached_format(&source_name);
@@ -1627,12 +1722,12 @@ mod tests {
                 }
                 
                 cache_was_used = true;
-            } else if let Some(expected) = expected_format {
+            } else if let Some(expected) = &expected_format {
                 // Subsequent detections should use cached format
                 let detected_format = classifier.detect_format_with_caching(log_line, &source_name);
                 
                 // Should return the cached format
-                if detected_format != expected {
+                if detected_format != *expected {
                     return false;
                 }
                 
@@ -1643,7 +1738,7 @@ mod tests {
                 }
                 
                 let cached_entry = cached_entry.unwrap();
-                if cached_entry.format_type != expected {
+                if cached_entry.format_type != *expected {
                     return false;
                 }
                 
@@ -1785,9 +1880,9 @@ mod tests {
         let limited_changes: Vec<FormatType> = format_changes.into_iter().take(3).collect();
         
         // Test that cache properly handles format changes and invalidation
-        for (i, &format_type) in limited_changes.iter().enumerate() {
+        for (i, format_type) in limited_changes.iter().enumerate() {
             // Cache the format
-            classifier.cache_format(clean_source.clone(), format_type);
+            classifier.cache_format(clean_source.clone(), format_type.clone());
             
             // Verify it's cached
             let cached_entry = classifier.get_cached_format(&clean_source);
@@ -1795,7 +1890,7 @@ mod tests {
                 return false;
             }
             
-            if cached_entry.unwrap().format_type != format_type {
+            if cached_entry.unwrap().format_type != *format_type {
                 return false;
             }
             
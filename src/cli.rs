@@ -13,9 +13,59 @@ pub struct Cli {
     #[arg(long, short = 'j', global = true, default_value = "0")]
     pub parallel: usize,
     
-    /// Memory limit in MB
-    #[arg(long, global = true, default_value = "100")]
-    pub memory_limit: usize,
+    /// Memory limit in MB for buffered events, e.g. the `convert --merge` window (default: 100)
+    #[arg(long, global = true)]
+    pub memory_limit: Option<usize>,
+    
+    /// Config file (default: ./tango.toml, then $XDG_CONFIG_HOME/tango/config.toml)
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    
+    /// Year of the first line for timestamps without a year (default: from file mtime)
+    #[arg(long, global = true)]
+    pub year: Option<i32>,
+    
+    /// IANA timezone for timestamps without an offset, e.g. America/Chicago (default: UTC)
+    #[arg(long, global = true)]
+    pub tz: Option<String>,
+    
+    /// Parse every line as this format instead of detecting it; lines that do not
+    /// match are parse failures (json, logfmt, syslog, rfc5424, apache, nginx, logcat, csv, cri, docker, klog, plain).
+    /// Named apart from `convert --format`, which picks the output format
+    #[arg(long, global = true, conflicts_with = "profile")]
+    pub input_format: Option<String>,
+    
+    /// Parse every line with this profile from the config file; lines that do not
+    /// match are parse failures
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    
+    /// Write lines that fail to parse, or match no format and fall back to plain
+    /// text, to this NDJSON file instead of the output, with their source, line
+    /// number, attempted formats and error
+    #[arg(long, global = true)]
+    pub rejects: Option<PathBuf>,
+    
+    /// Also reject lines parsed with a confidence below this (0.0 - 1.0)
+    #[arg(long, global = true, requires = "rejects")]
+    pub min_confidence: Option<f64>,
+    
+    /// Unit of numeric and epoch-string timestamps: auto (from the magnitude), s, ms, us or ns
+    #[arg(long, global = true)]
+    pub epoch_unit: Option<crate::EpochUnit>,
+    
+    /// Flatten nested JSON objects into dotted field names at most this many
+    /// levels deep; deeper values are kept whole (default: unlimited)
+    #[arg(long, global = true)]
+    pub flatten_depth: Option<usize>,
+    
+    /// Flatten JSON arrays into indexed field names (`items[0].id`)
+    #[arg(long, global = true)]
+    pub index_arrays: bool,
+    
+    /// Rebuild nested objects and arrays from flattened field names in JSON and CSV output
+    #[arg(long, global = true)]
+    pub nest_fields: bool,
 }
 
 #[derive(Subcommand)]
@@ -38,10 +88,13 @@ pub enum Commands {
 
 #[derive(Args)]
 pub struct ParseArgs {
-    /// Log files to parse (supports glob patterns)
-    #[arg(required = true)]
+    /// Log files to parse (supports glob patterns); `-` or no files reads piped stdin
     pub files: Vec<PathBuf>,
     
+    /// Source name for stdin input, used for profile bindings and the format cache
+    #[arg(long)]
+    pub source_name: Option<String>,
+    
     /// Output format
     #[arg(long, short, value_enum, default_value = "table")]
     pub output: OutputFormat,
@@ -62,6 +115,10 @@ pub struct ParseArgs {
     #[arg(long, short)]
     pub grep: Option<String>,
     
+    /// Filter expression, e.g. 'level>=warn and fields.status >= 500'
+    #[arg(long = "where", short = 'w')]
+    pub filter: Option<String>,
+    
     /// Filter by field value (format: field=value)
     #[arg(long, short = 'F')]
     pub field: Option<Vec<String>>,
@@ -93,12 +150,12 @@ pub struct ParseArgs {
 
 #[derive(Args)]
 pub struct TailArgs {
-    /// Log file to tail
+    /// Log files to tail (supports glob patterns; new matching files are picked up)
     #[arg(required = true)]
-    pub file: PathBuf,
+    pub files: Vec<PathBuf>,
     
-    /// Follow file changes (like tail -f)
-    #[arg(long, short)]
+    /// Follow files by name (like tail -F): survives rotation and truncation
+    #[arg(long, short, visible_short_alias = 'F')]
     pub follow: bool,
     
     /// Output format
@@ -113,6 +170,10 @@ pub struct TailArgs {
     #[arg(long, short)]
     pub grep: Option<String>,
     
+    /// Filter expression, e.g. 'level>=warn and fields.status >= 500'
+    #[arg(long = "where", short = 'w')]
+    pub filter: Option<String>,
+    
     /// Highlight matches
     #[arg(long, short = 'H')]
     pub highlight: bool,
@@ -124,10 +185,17 @@ pub struct TailArgs {
 
 #[derive(Args)]
 pub struct StatsArgs {
-    /// Log files to analyze
-    #[arg(required = true)]
+    /// Log files to analyze; `-` or no files reads piped stdin
     pub files: Vec<PathBuf>,
     
+    /// Source name for stdin input, used for profile bindings and the format cache
+    #[arg(long)]
+    pub source_name: Option<String>,
+    
+    /// Filter expression, e.g. 'level>=warn and fields.status >= 500'
+    #[arg(long = "where", short = 'w')]
+    pub filter: Option<String>,
+    
     /// Count entries by field
     #[arg(long)]
     pub count_by: Option<String>,
@@ -159,10 +227,13 @@ pub struct StatsArgs {
 
 #[derive(Args)]
 pub struct SearchArgs {
-    /// Log files to search (supports glob patterns)
-    #[arg(required = true)]
+    /// Log files to search (supports glob patterns); `-` or no files reads piped stdin
     pub files: Vec<PathBuf>,
     
+    /// Source name for stdin input, used for profile bindings and the format cache
+    #[arg(long)]
+    pub source_name: Option<String>,
+    
     /// Pattern to search in message (required)
     #[arg(long, short)]
     pub grep: Option<String>,
@@ -179,6 +250,10 @@ pub struct SearchArgs {
     #[arg(long)]
     pub until: Option<String>,
     
+    /// Filter expression, e.g. 'level>=warn and fields.status >= 500'
+    #[arg(long = "where", short = 'w')]
+    pub filter: Option<String>,
+    
     /// Filter by field value (format: field=value)
     #[arg(long, short = 'F')]
     pub field: Option<Vec<String>>,
@@ -214,10 +289,13 @@ pub struct SearchArgs {
 
 #[derive(Args)]
 pub struct ConvertArgs {
-    /// Log files to convert
-    #[arg(required = true)]
+    /// Log files to convert; `-` or no files reads piped stdin
     pub files: Vec<PathBuf>,
     
+    /// Source name for stdin input, used for profile bindings and the format cache
+    #[arg(long)]
+    pub source_name: Option<String>,
+    
     /// Output format
     #[arg(long, short = 'f', value_enum, default_value = "json")]
     pub format: OutputFormat,
@@ -226,10 +304,26 @@ pub struct ConvertArgs {
     #[arg(long, short = 'o')]
     pub output_file: Option<PathBuf>,
     
+    /// Filter expression, e.g. 'level>=warn and fields.status >= 500'
+    #[arg(long = "where", short = 'w')]
+    pub filter: Option<String>,
+    
     /// Merge files by timestamp
     #[arg(long)]
     pub merge: bool,
     
+    /// How far back in time a line may jump and still be merged in order (with --merge)
+    #[arg(long, default_value = "5s", value_parser = humantime::parse_duration)]
+    pub reorder_window: std::time::Duration,
+    
+    /// Only read lines added since the previous run, then save the new positions
+    #[arg(long)]
+    pub since_checkpoint: bool,
+    
+    /// Checkpoint state file (default: $XDG_STATE_HOME/tango/checkpoints.json)
+    #[arg(long, requires = "since_checkpoint")]
+    pub checkpoint_file: Option<PathBuf>,
+    
     /// Fields to include (comma-separated)
     #[arg(long)]
     pub fields: Option<String>,
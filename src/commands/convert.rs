@@ -1,23 +1,40 @@
 use crate::cli::ConvertArgs;
-use crate::commands::output::OutputFormatter;
-use crate::commands::parse::expand_globs;
-use crate::{TangoParser, CanonicalEvent};
+use crate::commands::output::{OutputFormatter, finish_rejects, open_rejects};
+use crate::commands::parse::{expand_inputs, build_filter};
+use crate::checkpoint::{default_checkpoint_path, CheckpointStore, FileState};
+use crate::input::OffsetLines;
+use crate::merge::merge_by_timestamp;
+use crate::{ChunkedParser, TangoParser, TangoConfig, MergeConfig, LogInput};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write, stdout};
+use std::io::{BufRead, Write, stdout};
 
-pub fn run_convert(args: ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = TangoParser::new();
+type InputLines = OffsetLines<Box<dyn BufRead + Send>>;
+
+pub fn run_convert(args: ConvertArgs, config: &TangoConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = TangoParser::with_config(config.clone());
     let formatter = OutputFormatter::new(args.format)
         .with_fields(args.fields.clone())
-        .with_raw(!args.no_raw);
+        .with_raw(!args.no_raw)
+        .with_nested_fields(config.flatten.nest_output);
     
-    let files = expand_globs(&args.files)?;
+    let inputs = expand_inputs(&args.files, args.source_name.as_deref())?;
+    let filter = build_filter(args.filter.as_deref(), &None, &None, &None, None, &None)?;
     
-    if files.is_empty() {
+    if inputs.is_empty() {
         eprintln!("No files matched the given patterns");
         return Ok(());
     }
     
+    let mut checkpoints = if args.since_checkpoint {
+        let path = args.checkpoint_file.clone().unwrap_or_else(default_checkpoint_path);
+        Some(CheckpointStore::load(&path)?)
+    } else {
+        None
+    };
+    // How far each input was read, committed once the output is flushed
+    let mut read_to: Vec<(&LogInput, Option<FileState>, u64, usize)> = Vec::new();
+    
+    let mut rejects = open_rejects(config, &parser)?;
     let mut output: Box<dyn Write> = if let Some(ref path) = args.output_file {
         Box::new(File::create(path)?)
     } else {
@@ -25,66 +42,133 @@ pub fn run_convert(args: ConvertArgs) -> Result<(), Box<dyn std::error::Error>>
     };
     
     if args.merge {
-        // Collect all events and sort by timestamp
-        let mut all_events: Vec<CanonicalEvent> = Vec::new();
+        // Stream a k-way merge over one reader per input
+        let mut groups = Vec::with_capacity(inputs.len());
+        let mut states = Vec::with_capacity(inputs.len());
+        for input in &inputs {
+            let (lines, state) = open_input(input, checkpoints.as_ref())?;
+            groups.push(parser.group_lines(lines));
+            states.push(state);
+        }
         
-        for file_path in &files {
-            let file = File::open(file_path)?;
-            let reader = BufReader::new(file);
-            let source = file_path.to_string_lossy().to_string();
-            
-            for line in reader.lines() {
-                let line = line?;
-                if line.trim().is_empty() {
+        let merge_config = MergeConfig {
+            reorder_window: chrono::Duration::from_std(args.reorder_window)?,
+            memory_limit_bytes: config.streaming_config.memory_limit_bytes,
+        };
+        let mut merged = merge_by_timestamp(inputs.len(), merge_config, |i| {
+            for group in groups[i].by_ref() {
+                let group = match group {
+                    Ok(group) => group,
+                    Err(e) => return Some(Err(e)),
+                };
+                if group.is_blank() {
                     continue;
                 }
                 
-                let result = parser.parse_line_with_source(&line, &source);
-                all_events.push(result.event);
-            }
-        }
-        
-        // Sort by timestamp
-        all_events.sort_by(|a, b| {
-            match (a.timestamp, b.timestamp) {
-                (Some(ta), Some(tb)) => ta.cmp(&tb),
-                (Some(_), None) => std::cmp::Ordering::Less,
-                (None, Some(_)) => std::cmp::Ordering::Greater,
-                (None, None) => std::cmp::Ordering::Equal,
+                let result = parser.parse_group_from_input(&group, &inputs[i]);
+                match rejects.reject(&result) {
+                    Ok(true) => continue,
+                    Ok(false) => {}
+                    Err(e) => return Some(Err(e)),
+                }
+                if filter.matches(&result.event) {
+                    return Some(Ok(result.event));
+                }
             }
+            None
         });
         
-        // Output merged events
         formatter.print_header(&mut output)?;
-        for event in &all_events {
-            writeln!(output, "{}", formatter.format_event(event))?;
+        let mut total = 0;
+        for event in merged.by_ref() {
+            writeln!(output, "{}", formatter.format_event(&event?))?;
+            total += 1;
         }
         
-        eprintln!("Converted and merged {} events from {} files", all_events.len(), files.len());
+        if merged.spilled_runs() > 0 {
+            eprintln!("Reorder window exceeded the memory limit; spilled {} sorted runs to disk", merged.spilled_runs());
+        }
+        drop(merged);
+        
+        for ((input, state), group) in inputs.iter().zip(states).zip(&groups) {
+            read_to.push((input, state, group.lines().offset(), group.lines().lines_read()));
+        }
+        eprintln!("Converted and merged {} events from {} files", total, inputs.len());
     } else {
-        // Process files sequentially
+        // Process files in order, splitting large ones across workers
+        let chunked = ChunkedParser::new(&parser);
         formatter.print_header(&mut output)?;
         let mut total = 0;
         
-        for file_path in &files {
-            let file = File::open(file_path)?;
-            let reader = BufReader::new(file);
-            let source = file_path.to_string_lossy().to_string();
+        for input in &inputs {
+            // Checkpointed reads resume mid-file and record where they stop, so they stay sequential
+            if checkpoints.is_none() {
+                chunked.parse_input(&mut parser, input, |result| {
+                    if !rejects.reject(&result)? && filter.matches(&result.event) {
+                        writeln!(output, "{}", formatter.format_event(&result.event))?;
+                        total += 1;
+                    }
+                    Ok(true)
+                })?;
+                continue;
+            }
+            
+            let (lines, state) = open_input(input, checkpoints.as_ref())?;
+            let mut groups = parser.group_lines(lines);
             
-            for line in reader.lines() {
-                let line = line?;
-                if line.trim().is_empty() {
+            for group in groups.by_ref() {
+                let group = group?;
+                if group.is_blank() {
                     continue;
                 }
                 
-                let result = parser.parse_line_with_source(&line, &source);
+                let result = parser.parse_group_from_input(&group, input);
+                if rejects.reject(&result)? || !filter.matches(&result.event) {
+                    continue;
+                }
                 writeln!(output, "{}", formatter.format_event(&result.event))?;
                 total += 1;
             }
+            
+            read_to.push((input, state, groups.lines().offset(), groups.lines().lines_read()));
         }
         
-        eprintln!("Converted {} events from {} files", total, files.len());
+        eprintln!("Converted {} events from {} files", total, inputs.len());
+    }
+    
+    // Only what has reached the output counts as read
+    output.flush()?;
+    finish_rejects(&mut rejects)?;
+    if let Some(store) = checkpoints.as_mut() {
+        for (input, state, offset, lines) in read_to {
+            if let Some(state) = state {
+                store.record(input.source(), &state, input.member(), offset, lines);
+            }
+        }
+        store.save()?;
     }
     
     Ok(())
 }
+
+/// Open an input after its saved checkpoint, if checkpoints are in use
+fn open_input(
+    input: &LogInput,
+    checkpoints: Option<&CheckpointStore>,
+) -> Result<(InputLines, Option<FileState>), Box<dyn std::error::Error>> {
+    let Some(store) = checkpoints else {
+        return Ok((input.lines()?, None));
+    };
+    
+    let state = FileState::of(input)?;
+    let (offset, lines_before) = state.as_ref()
+        .map(|state| store.resume_point(input.source(), state, input.member()))
+        .unwrap_or((0, 0));
+    
+    let mut lines = OffsetLines::new(input.open_at(offset)?, offset).after_lines(lines_before);
+    if state.is_some() {
+        // A line still being written is picked up whole by the next run
+        lines = lines.complete_only();
+    }
+    Ok((lines, state))
+}
@@ -1,13 +1,16 @@
 use crate::cli::OutputFormat;
-use crate::CanonicalEvent;
+use crate::field_path::{unflatten, FieldPath};
+use crate::{CanonicalEvent, RejectWriter, TangoConfig, TangoParser};
 use colored::*;
+use std::borrow::Cow;
 use std::io::{self, Write};
 
 pub struct OutputFormatter {
     format: OutputFormat,
     highlight_pattern: Option<regex::Regex>,
-    fields: Option<Vec<String>>,
+    fields: Option<Vec<FieldPath>>,
     include_raw: bool,
+    nest_fields: bool,
 }
 
 impl OutputFormatter {
@@ -17,6 +20,7 @@ impl OutputFormatter {
             highlight_pattern: None,
             fields: None,
             include_raw: true,
+            nest_fields: false,
         }
     }
     
@@ -28,7 +32,13 @@ impl OutputFormatter {
     }
     
     pub fn with_fields(mut self, fields: Option<String>) -> Self {
-        self.fields = fields.map(|f| f.split(',').map(|s| s.trim().to_string()).collect());
+        self.fields = fields.map(|f| f.split(',').map(|s| FieldPath::parse_or_key(s.trim())).collect());
+        self
+    }
+    
+    /// Rebuild nested objects and arrays from flattened field names in JSON and CSV output
+    pub fn with_nested_fields(mut self, nest: bool) -> Self {
+        self.nest_fields = nest;
         self
     }
     
@@ -65,7 +75,7 @@ impl OutputFormatter {
     pub fn print_header(&self, writer: &mut impl Write) -> io::Result<()> {
         match self.format {
             OutputFormat::Csv => {
-                writeln!(writer, "timestamp,level,message,format,fields")?;
+                writeln!(writer, "timestamp,level,message,format,fields,file,line,offset,host")?;
             }
             OutputFormat::Table => {
                 writeln!(writer, "{}", "─".repeat(100).dimmed())?;
@@ -111,14 +121,7 @@ impl OutputFormatter {
         
         // Fields if present
         if !event.fields.is_empty() {
-            let fields_str: Vec<String> = event.fields.iter()
-                .filter(|(k, _)| {
-                    if let Some(ref allowed) = self.fields {
-                        allowed.contains(k)
-                    } else {
-                        true
-                    }
-                })
+            let fields_str: Vec<String> = self.selected_fields(event).into_iter()
                 .map(|(k, v)| format!("{}={}", k.dimmed(), format_value(v)))
                 .collect();
             if !fields_str.is_empty() {
@@ -138,6 +141,9 @@ impl OutputFormatter {
         
         if let Some(ts) = event.timestamp {
             obj.insert("timestamp".to_string(), serde_json::Value::String(ts.to_rfc3339()));
+            if event.offset_assumed == Some(true) {
+                obj.insert("offset_assumed".to_string(), serde_json::Value::Bool(true));
+            }
         }
         
         if let Some(level) = event.level {
@@ -145,24 +151,25 @@ impl OutputFormatter {
         }
         
         obj.insert("message".to_string(), serde_json::Value::String(event.message.clone()));
-        obj.insert("format".to_string(), serde_json::Value::String(format!("{:?}", event.format_type)));
+        obj.insert("format".to_string(), serde_json::Value::String(event.format_type.to_string()));
         
         if !event.fields.is_empty() {
-            let fields: serde_json::Map<String, serde_json::Value> = event.fields.iter()
-                .filter(|(k, _)| {
-                    if let Some(ref allowed) = self.fields {
-                        allowed.contains(k)
-                    } else {
-                        true
-                    }
-                })
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
+            let fields = self.fields_json(event);
             if !fields.is_empty() {
                 obj.insert("fields".to_string(), serde_json::Value::Object(fields));
             }
         }
         
+        let source = serde_json::to_value(&event.source).unwrap_or_default();
+        if let serde_json::Value::Object(source) = source {
+            let source: serde_json::Map<String, serde_json::Value> = source.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .collect();
+            if !source.is_empty() {
+                obj.insert("source".to_string(), serde_json::Value::Object(source));
+            }
+        }
+        
         if self.include_raw {
             obj.insert("raw".to_string(), serde_json::Value::String(event.raw.clone()));
         }
@@ -178,15 +185,41 @@ impl OutputFormatter {
             .map(|l| format!("{:?}", l).to_lowercase())
             .unwrap_or_default();
         let message = event.message.replace('"', "\"\"");
-        let format_type = format!("{:?}", event.format_type);
-        let fields = serde_json::to_string(&event.fields).unwrap_or_default().replace('"', "\"\"");
+        let format_type = event.format_type.to_string();
+        let fields = serde_json::to_string(&self.fields_json(event)).unwrap_or_default().replace('"', "\"\"");
+        let file = event.source.file.as_deref().unwrap_or_default().replace('"', "\"\"");
+        let line = event.source.line.map(|n| n.to_string()).unwrap_or_default();
+        let offset = event.source.offset.map(|n| n.to_string()).unwrap_or_default();
+        let host = event.source.host.as_deref().unwrap_or_default().replace('"', "\"\"");
         
-        format!("{},\"{}\",\"{}\",{},\"{}\"", ts, level, message, format_type, fields)
+        format!("{},\"{}\",\"{}\",{},\"{}\",\"{}\",{},{},\"{}\"",
+            ts, level, message, format_type, fields, file, line, offset, host)
     }
     
     fn format_raw(&self, event: &CanonicalEvent) -> String {
         event.raw.clone()
     }
+    
+    /// The `--fields` paths that hold a value, or every field
+    fn selected_fields<'e>(&self, event: &'e CanonicalEvent) -> Vec<(Cow<'e, str>, &'e serde_json::Value)> {
+        match &self.fields {
+            Some(paths) => paths.iter()
+                .filter_map(|path| path.resolve(&event.fields).map(|v| (Cow::Owned(path.to_string()), v)))
+                .collect(),
+            None => event.fields.iter()
+                .map(|(k, v)| (Cow::Borrowed(k.as_str()), v))
+                .collect(),
+        }
+    }
+    
+    fn fields_json(&self, event: &CanonicalEvent) -> serde_json::Map<String, serde_json::Value> {
+        let fields = self.selected_fields(event);
+        if self.nest_fields {
+            unflatten(fields.iter().map(|(k, v)| (k.as_ref(), *v)))
+        } else {
+            fields.into_iter().map(|(k, v)| (k.into_owned(), v.clone())).collect()
+        }
+    }
 }
 
 fn format_value(v: &serde_json::Value) -> String {
@@ -198,6 +231,21 @@ fn format_value(v: &serde_json::Value) -> String {
     }
 }
 
+/// Rejects writer for the configured file; lines the parser could only read
+/// as plain text count as unmatched
+pub fn open_rejects(config: &TangoConfig, parser: &TangoParser) -> io::Result<RejectWriter> {
+    Ok(RejectWriter::from_config(&config.rejects)?.with_fallback_formats(parser.fallback_formats()))
+}
+
+/// Flush the rejects file and say how many lines went to it
+pub fn finish_rejects(rejects: &mut RejectWriter) -> io::Result<()> {
+    rejects.flush()?;
+    if let Some(path) = rejects.path() {
+        eprintln!("Rejected {} lines to {}", rejects.rejected(), path.display());
+    }
+    Ok(())
+}
+
 pub fn print_stats_summary(
     total: usize,
     parsed: usize,
@@ -1,44 +1,41 @@
 use crate::cli::ParseArgs;
-use crate::commands::output::{OutputFormatter, print_stats_summary};
-use crate::{TangoParser, CanonicalEvent, LogLevel};
+use crate::commands::output::{OutputFormatter, finish_rejects, open_rejects, print_stats_summary};
+use crate::{ChunkedParser, TangoParser, TangoConfig, LogLevel, Filter, ParseError};
+use crate::input::{LogInput, discover_inputs};
+use crate::timestamp::parse_time;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write, stdout};
+use std::io::{IsTerminal, Write, stdout};
 use std::collections::HashMap;
-use chrono::{DateTime, Utc};
 use glob::glob;
 
-pub fn run_parse(args: ParseArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = TangoParser::new();
+pub fn run_parse(args: ParseArgs, config: &TangoConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = TangoParser::with_config(config.clone());
+    let chunked = ChunkedParser::new(&parser);
     let formatter = OutputFormatter::new(args.output)
         .with_highlight(args.grep.as_deref())
         .with_fields(args.fields.clone())
-        .with_raw(!args.no_raw);
+        .with_raw(!args.no_raw)
+        .with_nested_fields(config.flatten.nest_output);
     
     // Expand glob patterns
-    let files = expand_globs(&args.files)?;
+    let inputs = expand_inputs(&args.files, args.source_name.as_deref())?;
     
-    if files.is_empty() {
+    if inputs.is_empty() {
         eprintln!("No files matched the given patterns");
         return Ok(());
     }
     
-    // Parse time filters
-    let since = args.since.as_ref().and_then(|s| parse_time(s));
-    let until = args.until.as_ref().and_then(|s| parse_time(s));
-    
-    // Parse level filters
-    let levels: Option<Vec<LogLevel>> = args.level.as_ref().map(|lvls| {
-        lvls.iter()
-            .filter_map(|l| LogLevel::from_str(l))
-            .collect()
-    });
-    
-    // Parse field filters
-    let field_filters = parse_field_filters(&args.field);
-    
-    // Compile grep pattern
+    // Compile filters once
     let grep_pattern = args.grep.as_ref()
         .and_then(|p| regex::Regex::new(&format!("(?i){}", p)).ok());
+    let filter = build_filter(
+        args.filter.as_deref(),
+        &args.level,
+        &args.since,
+        &args.until,
+        grep_pattern,
+        &args.field,
+    )?;
     
     let mut output: Box<dyn Write> = if let Some(ref path) = args.output_file {
         Box::new(File::create(path)?)
@@ -46,6 +43,7 @@ pub fn run_parse(args: ParseArgs) -> Result<(), Box<dyn std::error::Error>> {
         Box::new(stdout())
     };
     
+    let mut rejects = open_rejects(config, &parser)?;
     formatter.print_header(&mut output)?;
     
     let mut total = 0;
@@ -55,23 +53,13 @@ pub fn run_parse(args: ParseArgs) -> Result<(), Box<dyn std::error::Error>> {
     let mut format_counts: HashMap<String, usize> = HashMap::new();
     let mut output_count = 0;
     
-    for file_path in &files {
+    for input in &inputs {
         if args.format_detect {
-            eprintln!("Processing: {}", file_path.display());
+            eprintln!("Processing: {}", input.source());
         }
         
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let source = file_path.to_string_lossy().to_string();
-        
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
-            }
-            
+        chunked.parse_input(&mut parser, input, |result| {
             total += 1;
-            let result = parser.parse_line_with_source(&line, &source);
             let event = &result.event;
             
             if result.success {
@@ -84,24 +72,29 @@ pub fn run_parse(args: ParseArgs) -> Result<(), Box<dyn std::error::Error>> {
                 with_level += 1;
             }
             
-            let format_name = format!("{:?}", event.format_type);
+            let format_name = event.format_type.to_string();
             *format_counts.entry(format_name).or_insert(0) += 1;
             
+            if rejects.reject(&result)? {
+                return Ok(true);
+            }
+            
             // Apply filters
-            if !matches_filters(event, &levels, &since, &until, &grep_pattern, &field_filters) {
-                continue;
+            if !filter.matches(event) {
+                return Ok(true);
             }
             
             // Check limit
             if let Some(limit) = args.limit {
                 if output_count >= limit {
-                    break;
+                    return Ok(false);
                 }
             }
             
             writeln!(output, "{}", formatter.format_event(event))?;
             output_count += 1;
-        }
+            Ok(true)
+        })?;
         
         if args.format_detect {
             eprintln!("  Format: {:?}", format_counts);
@@ -112,10 +105,28 @@ pub fn run_parse(args: ParseArgs) -> Result<(), Box<dyn std::error::Error>> {
     if args.output_file.is_some() {
         print_stats_summary(total, parsed_ok, with_timestamp, with_level, &format_counts);
     }
+    finish_rejects(&mut rejects)?;
     
     Ok(())
 }
 
+/// Expand glob patterns and open archives into the list of inputs to read.
+/// With no patterns, piped stdin is read instead.
+pub fn expand_inputs(
+    patterns: &[std::path::PathBuf],
+    source_name: Option<&str>,
+) -> Result<Vec<LogInput>, Box<dyn std::error::Error>> {
+    if patterns.is_empty() {
+        if std::io::stdin().is_terminal() {
+            return Err("no input files given (pass files, or pipe logs to stdin)".into());
+        }
+        return Ok(vec![LogInput::stdin(source_name)]);
+    }
+    
+    let files = expand_globs(patterns)?;
+    Ok(discover_inputs(&files, source_name)?)
+}
+
 pub fn expand_globs(patterns: &[std::path::PathBuf]) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
     let mut files = Vec::new();
     for pattern in patterns {
@@ -131,97 +142,39 @@ pub fn expand_globs(patterns: &[std::path::PathBuf]) -> Result<Vec<std::path::Pa
     Ok(files)
 }
 
-pub fn parse_time(s: &str) -> Option<DateTime<Utc>> {
-    // Try RFC3339 first
-    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
-        return Some(dt.with_timezone(&Utc));
-    }
-    
-    // Try common date formats
-    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-        return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-    }
-    if let Ok(dt) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        return Some(DateTime::from_naive_utc_and_offset(dt.and_hms_opt(0, 0, 0)?, Utc));
-    }
-    
-    // Try humantime relative (e.g., "1 hour ago")
-    if let Ok(duration) = humantime::parse_duration(s.trim_end_matches(" ago")) {
-        let now = Utc::now();
-        return Some(now - chrono::Duration::from_std(duration).ok()?);
-    }
+/// Compile `--where` together with the shorthand filter flags into one filter, shared by every command
+pub fn build_filter(
+    expression: Option<&str>,
+    levels: &Option<Vec<String>>,
+    since: &Option<String>,
+    until: &Option<String>,
+    grep: Option<regex::Regex>,
+    field_filters: &Option<Vec<String>>,
+) -> Result<Filter, ParseError> {
+    let mut filter = match expression {
+        Some(expression) => Filter::compile(expression)?,
+        None => Filter::all(),
+    };
     
-    None
-}
-
-pub fn parse_field_filters(filters: &Option<Vec<String>>) -> HashMap<String, String> {
-    let mut map = HashMap::new();
-    if let Some(filters) = filters {
-        for filter in filters {
-            if let Some((key, value)) = filter.split_once('=') {
-                map.insert(key.to_string(), value.to_string());
-            }
-        }
-    }
-    map
-}
-
-pub fn matches_filters(
-    event: &CanonicalEvent,
-    levels: &Option<Vec<LogLevel>>,
-    since: &Option<DateTime<Utc>>,
-    until: &Option<DateTime<Utc>>,
-    grep: &Option<regex::Regex>,
-    field_filters: &HashMap<String, String>,
-) -> bool {
-    // Level filter
-    if let Some(ref allowed_levels) = levels {
-        if let Some(event_level) = event.level {
-            if !allowed_levels.contains(&event_level) {
-                return false;
-            }
-        } else {
-            return false; // No level, but level filter specified
-        }
+    if let Some(levels) = levels {
+        filter = filter.and(Filter::levels(levels.iter().filter_map(|l| LogLevel::from_str(l)).collect()));
     }
     
-    // Time filters
-    if let Some(ref start) = since {
-        if let Some(ts) = event.timestamp {
-            if ts < *start {
-                return false;
-            }
-        }
-    }
-    if let Some(ref end) = until {
-        if let Some(ts) = event.timestamp {
-            if ts > *end {
-                return false;
-            }
-        }
+    let since = since.as_ref().and_then(|s| parse_time(s));
+    let until = until.as_ref().and_then(|s| parse_time(s));
+    if since.is_some() || until.is_some() {
+        filter = filter.and(Filter::time_range(since, until));
     }
     
-    // Grep filter
-    if let Some(ref pattern) = grep {
-        if !pattern.is_match(&event.message) && !pattern.is_match(&event.raw) {
-            return false;
-        }
+    if let Some(pattern) = grep {
+        filter = filter.and(Filter::grep(pattern));
     }
     
-    // Field filters
-    for (key, expected_value) in field_filters {
-        if let Some(value) = event.fields.get(key) {
-            let value_str = match value {
-                serde_json::Value::String(s) => s.clone(),
-                _ => value.to_string(),
-            };
-            if !value_str.contains(expected_value) {
-                return false;
-            }
-        } else {
-            return false;
+    for field_filter in field_filters.iter().flatten() {
+        if let Some((key, value)) = field_filter.split_once('=') {
+            filter = filter.and(Filter::field_contains(key, value));
         }
     }
     
-    true
+    Ok(filter)
 }
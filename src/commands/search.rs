@@ -1,33 +1,23 @@
 use crate::cli::SearchArgs;
-use crate::commands::output::OutputFormatter;
-use crate::commands::parse::{expand_globs, parse_time, parse_field_filters, matches_filters};
-use crate::{TangoParser, LogLevel};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write, stdout};
+use crate::commands::output::{OutputFormatter, finish_rejects, open_rejects};
+use crate::commands::parse::{expand_inputs, build_filter};
+use crate::{ChunkedParser, TangoParser, TangoConfig};
+use std::io::{Write, stdout};
 
-pub fn run_search(args: SearchArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = TangoParser::new();
+pub fn run_search(args: SearchArgs, config: &TangoConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = TangoParser::with_config(config.clone());
+    let chunked = ChunkedParser::new(&parser);
     let formatter = OutputFormatter::new(args.output)
-        .with_highlight(args.grep.as_deref());
+        .with_highlight(args.grep.as_deref())
+        .with_nested_fields(config.flatten.nest_output);
     
-    let files = expand_globs(&args.files)?;
+    let inputs = expand_inputs(&args.files, args.source_name.as_deref())?;
     
-    if files.is_empty() {
+    if inputs.is_empty() {
         eprintln!("No files matched the given patterns");
         return Ok(());
     }
     
-    let since = args.since.as_ref().and_then(|s| parse_time(s));
-    let until = args.until.as_ref().and_then(|s| parse_time(s));
-    
-    let levels: Option<Vec<LogLevel>> = args.level.as_ref().map(|lvls| {
-        lvls.iter()
-            .filter_map(|l| LogLevel::from_str(l))
-            .collect()
-    });
-    
-    let field_filters = parse_field_filters(&args.field);
-    
     let grep_pattern = args.grep.as_ref().and_then(|p| {
         let pattern = if args.ignore_case {
             format!("(?i){}", p)
@@ -36,7 +26,16 @@ pub fn run_search(args: SearchArgs) -> Result<(), Box<dyn std::error::Error>> {
         };
         regex::Regex::new(&pattern).ok()
     });
+    let filter = build_filter(
+        args.filter.as_deref(),
+        &args.level,
+        &args.since,
+        &args.until,
+        grep_pattern,
+        &args.field,
+    )?;
     
+    let mut rejects = open_rejects(config, &parser)?;
     let mut output: Box<dyn Write> = Box::new(stdout());
     formatter.print_header(&mut output)?;
     
@@ -44,21 +43,14 @@ pub fn run_search(args: SearchArgs) -> Result<(), Box<dyn std::error::Error>> {
     let mut context_buffer: Vec<String> = Vec::new();
     let mut pending_after = 0;
     
-    for file_path in &files {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let source = file_path.to_string_lossy().to_string();
-        
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+    for input in &inputs {
+        chunked.parse_input(&mut parser, input, |result| {
+            if rejects.reject(&result)? {
+                return Ok(true);
             }
-            
-            let result = parser.parse_line_with_source(&line, &source);
             let event = &result.event;
             
-            let matches = matches_filters(event, &levels, &since, &until, &grep_pattern, &field_filters);
+            let matches = filter.matches(event);
             let should_show = if args.invert { !matches } else { matches };
             
             if should_show {
@@ -75,7 +67,7 @@ pub fn run_search(args: SearchArgs) -> Result<(), Box<dyn std::error::Error>> {
                 
                 if let Some(limit) = args.limit {
                     if match_count >= limit {
-                        break;
+                        return Ok(false);
                     }
                 }
             } else if pending_after > 0 {
@@ -90,9 +82,11 @@ pub fn run_search(args: SearchArgs) -> Result<(), Box<dyn std::error::Error>> {
                     context_buffer.remove(0);
                 }
             }
-        }
+            Ok(true)
+        })?;
     }
     
     eprintln!("\n{} matches found", match_count);
+    finish_rejects(&mut rejects)?;
     Ok(())
 }
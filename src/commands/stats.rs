@@ -1,21 +1,25 @@
 use crate::cli::StatsArgs;
-use crate::commands::parse::expand_globs;
-use crate::commands::output::print_stats_summary;
-use crate::TangoParser;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::commands::parse::{expand_inputs, build_filter};
+use crate::commands::output::{finish_rejects, open_rejects, print_stats_summary};
+use crate::{ChunkedParser, FieldPath, LockState, TangoParser, TangoConfig, ValueRef};
 use std::collections::HashMap;
 use colored::*;
 
-pub fn run_stats(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = TangoParser::new();
-    let files = expand_globs(&args.files)?;
+pub fn run_stats(args: StatsArgs, config: &TangoConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = TangoParser::with_config(config.clone());
+    let chunked = ChunkedParser::new(&parser);
+    let inputs = expand_inputs(&args.files, args.source_name.as_deref())?;
+    let filter = build_filter(args.filter.as_deref(), &None, &None, &None, None, &None)?;
+    let count_by = args.count_by.as_deref().map(FieldPath::parse_or_key);
+    let unique = args.unique.as_deref().map(FieldPath::parse_or_key);
+    let by = args.by.as_deref().map(FieldPath::parse_or_key);
     
-    if files.is_empty() {
+    if inputs.is_empty() {
         eprintln!("No files matched the given patterns");
         return Ok(());
     }
     
+    let mut rejects = open_rejects(config, &parser)?;
     let mut total = 0;
     let mut parsed_ok = 0;
     let mut with_timestamp = 0;
@@ -25,20 +29,16 @@ pub fn run_stats(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
     let mut field_counts: HashMap<String, HashMap<String, usize>> = HashMap::new();
     let mut time_buckets: HashMap<String, usize> = HashMap::new();
     
-    for file_path in &files {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let source = file_path.to_string_lossy().to_string();
-        
-        for line in reader.lines() {
-            let line = line?;
-            if line.trim().is_empty() {
-                continue;
+    for input in &inputs {
+        // Events borrow from the mapped file; only counted values are copied
+        chunked.parse_input_ref(&mut parser, input, |result| {
+            let event = &result.event;
+            if !filter.matches_ref(event) {
+                rejects.reject_ref(&result)?;
+                return Ok(true);
             }
             
             total += 1;
-            let result = parser.parse_line_with_source(&line, &source);
-            let event = &result.event;
             
             if result.success {
                 parsed_ok += 1;
@@ -65,14 +65,14 @@ pub fn run_stats(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
                 *level_counts.entry(level_name).or_insert(0) += 1;
             }
             
-            let format_name = format!("{:?}", event.format_type);
+            let format_name = event.format_type.to_string();
             *format_counts.entry(format_name).or_insert(0) += 1;
             
             // Count by field
             if let Some(ref count_field) = args.count_by {
-                if let Some(value) = event.fields.get(count_field) {
+                if let Some(value) = count_by.as_ref().and_then(|path| path.resolve_ref(&event.fields)) {
                     let value_str = match value {
-                        serde_json::Value::String(s) => s.clone(),
+                        ValueRef::String(s) => s.to_string(),
                         _ => value.to_string(),
                     };
                     let field_map = field_counts.entry(count_field.clone()).or_insert_with(HashMap::new);
@@ -82,9 +82,9 @@ pub fn run_stats(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
             
             // Unique values
             if let Some(ref unique_field) = args.unique {
-                if let Some(value) = event.fields.get(unique_field) {
+                if let Some(value) = unique.as_ref().and_then(|path| path.resolve_ref(&event.fields)) {
                     let value_str = match value {
-                        serde_json::Value::String(s) => s.clone(),
+                        ValueRef::String(s) => s.to_string(),
                         _ => value.to_string(),
                     };
                     let field_map = field_counts.entry(unique_field.clone()).or_insert_with(HashMap::new);
@@ -94,20 +94,50 @@ pub fn run_stats(args: StatsArgs) -> Result<(), Box<dyn std::error::Error>> {
             
             // Top by field
             if let Some(ref by_field) = args.by {
-                if let Some(value) = event.fields.get(by_field) {
+                if let Some(value) = by.as_ref().and_then(|path| path.resolve_ref(&event.fields)) {
                     let value_str = match value {
-                        serde_json::Value::String(s) => s.clone(),
+                        ValueRef::String(s) => s.to_string(),
                         _ => value.to_string(),
                     };
                     let field_map = field_counts.entry(by_field.clone()).or_insert_with(HashMap::new);
                     *field_map.entry(value_str).or_insert(0) += 1;
                 }
             }
-        }
+            
+            // Rejected lines are counted like any other, as parse counts them, then diverted
+            rejects.reject_ref(&result)?;
+            Ok(true)
+        })?;
     }
     
     // Print basic stats
     print_stats_summary(total, parsed_ok, with_timestamp, with_level, &format_counts);
+    finish_rejects(&mut rejects)?;
+    
+    // Print per-source format lock decisions
+    let locks = parser.format_locks().sources();
+    if !locks.is_empty() {
+        println!("\n{}:", "Format Locks".cyan().bold());
+        for (source, lock) in locks {
+            let decision = match &lock.state {
+                LockState::Locked(format) => format!("locked {} after {} lines (confidence {:.2}), {} misses",
+                    format, lock.locked_at.unwrap_or(0), lock.confidence, lock.misses),
+                LockState::Mixed => {
+                    let shares: Vec<String> = lock.sample_shares().iter()
+                        .map(|(format, share)| format!("{} {:.0}%", format, share * 100.0))
+                        .collect();
+                    format!("not locked, mixed ({})", shares.join(", "))
+                }
+                LockState::Sampling => format!("not locked, {} lines sampled", lock.lines),
+            };
+            let unlocks = if lock.unlocks > 0 {
+                format!(", {} unlocks", lock.unlocks)
+            } else {
+                String::new()
+            };
+            println!("  {:40} {}{}", source, decision, unlocks);
+        }
+    }
     
     // Print level distribution
     if !level_counts.is_empty() {
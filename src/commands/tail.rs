@@ -1,105 +1,303 @@
-use crate::cli::TailArgs;
-use crate::commands::output::OutputFormatter;
-use crate::{TangoParser, LogLevel};
-use std::fs::File;
+use crate::cli::{OutputFormat, TailArgs};
+use crate::commands::output::{OutputFormatter, finish_rejects, open_rejects};
+use crate::commands::parse::build_filter;
+use crate::{TangoParser, TangoConfig, Filter, LineAssembler, RejectWriter, SourceLine};
+use colored::*;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::fs::{File, Metadata};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::thread;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 use std::time::Duration;
 
-pub fn run_tail(args: TailArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = TangoParser::new();
+/// Quiet period after which a pending multi-line event is considered complete
+const FLUSH_DELAY: Duration = Duration::from_millis(200);
+
+pub fn run_tail(args: TailArgs, config: &TangoConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut parser = TangoParser::with_config(config.clone());
     let formatter = OutputFormatter::new(args.output)
-        .with_highlight(args.grep.as_deref());
-    
-    let levels: Option<Vec<LogLevel>> = args.level.as_ref().map(|lvls| {
-        lvls.iter()
-            .filter_map(|l| LogLevel::from_str(l))
-            .collect()
-    });
+        .with_highlight(args.grep.as_deref())
+        .with_nested_fields(config.flatten.nest_output);
     
     let grep_pattern = args.grep.as_ref()
         .and_then(|p| regex::Regex::new(&format!("(?i){}", p)).ok());
+    let filter = build_filter(args.filter.as_deref(), &args.level, &None, &None, grep_pattern, &None)?;
     
-    let source = args.file.to_string_lossy().to_string();
+    let mut files: Vec<TailedFile> = untracked_paths(&args.files, &[], args.follow)
+        .into_iter()
+        .map(|path| TailedFile::new(path, &parser))
+        .collect();
+    if files.is_empty() {
+        return Err("no files to tail".into());
+    }
     
-    // Open file and seek to end minus N lines
-    let mut file = File::open(&args.file)?;
-    let initial_lines = read_last_n_lines(&mut file, args.lines)?;
+    // Label text lines with their file once more than one file can appear
+    let multiple = args.files.len() > 1 || args.files.iter().any(|p| is_glob(p));
+    let mut printer = Printer {
+        formatter,
+        filter,
+        rejects: open_rejects(config, &parser)?,
+        prefix: multiple && matches!(args.output, OutputFormat::Table | OutputFormat::Raw),
+    };
     
-    // Print initial lines
-    for line in initial_lines {
-        let result = parser.parse_line_with_source(&line, &source);
-        let event = &result.event;
-        
-        // Apply filters
-        if let Some(ref allowed_levels) = levels {
-            if let Some(level) = event.level {
-                if !allowed_levels.contains(&level) {
+    // Print the last lines of each file
+    for file in files.iter_mut() {
+        let lines = file.open_at_end(args.lines)?;
+        for line in lines {
+            file.assembler.push_line(line);
+        }
+        file.assembler.flush();
+        file.print_ready(&mut parser, &mut printer);
+    }
+    
+    if !args.follow {
+        finish_rejects(&mut printer.rejects)?;
+        return Ok(());
+    }
+    
+    // Watch the directories holding the files so renames and new files are seen too
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for (dir, mode) in watch_dirs(&args.files) {
+        watcher.watch(&dir, mode)?;
+    }
+    
+    loop {
+        match rx.recv_timeout(FLUSH_DELAY) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Access(_)) {
                     continue;
                 }
-            } else {
-                continue;
+                // New files are read from the start
+                for path in untracked_paths(&args.files, &files, true) {
+                    files.push(TailedFile::new(path, &parser));
+                }
+                for file in files.iter_mut() {
+                    if let Err(e) = file.poll() {
+                        eprintln!("Error reading {}: {}", file.source, e);
+                    }
+                    file.print_ready(&mut parser, &mut printer);
+                }
+            }
+            Ok(Err(e)) => eprintln!("Watch error: {}", e),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                for file in files.iter_mut() {
+                    file.assembler.flush();
+                    file.print_ready(&mut parser, &mut printer);
+                }
             }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         }
+    }
+    
+    Ok(())
+}
+
+struct Printer {
+    formatter: OutputFormatter,
+    filter: Filter,
+    rejects: RejectWriter,
+    prefix: bool,
+}
+
+/// A followed path; survives rotation (rename + create) and truncation
+struct TailedFile {
+    path: PathBuf,
+    source: String,
+    reader: Option<BufReader<File>>,
+    /// Device and inode of the open file, to notice a new file at the same path
+    identity: Option<(u64, u64)>,
+    position: u64,
+    /// Bytes of a line whose newline has not been written yet
+    partial: String,
+    assembler: LineAssembler,
+}
+
+impl TailedFile {
+    fn new(path: PathBuf, parser: &TangoParser) -> Self {
+        Self {
+            source: path.to_string_lossy().to_string(),
+            path,
+            reader: None,
+            identity: None,
+            position: 0,
+            partial: String::new(),
+            assembler: parser.line_assembler(),
+        }
+    }
+    
+    /// Open the file positioned at its end, returning its last `n` lines
+    fn open_at_end(&mut self, n: usize) -> Result<Vec<String>, std::io::Error> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            // Not there yet; picked up when it is created
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let lines = read_last_n_lines(&mut file, n)?;
         
-        if let Some(ref pattern) = grep_pattern {
-            if !pattern.is_match(&event.message) && !pattern.is_match(&event.raw) {
-                continue;
+        let metadata = file.metadata()?;
+        self.identity = file_identity(&metadata);
+        self.position = file.seek(SeekFrom::End(0))?;
+        self.reader = Some(BufReader::new(file));
+        Ok(lines)
+    }
+    
+    /// Read new lines, following the path to a new file after rotation and
+    /// starting over after truncation
+    fn poll(&mut self) -> Result<(), std::io::Error> {
+        // Drain what is left in the open file, even if it has been renamed away
+        self.read_available()?;
+        
+        let metadata = match std::fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(()),
+        };
+        
+        let replaced = self.reader.is_none()
+            || (self.identity.is_some() && file_identity(&metadata) != self.identity);
+        if replaced {
+            let file = File::open(&self.path)?;
+            self.identity = file_identity(&file.metadata()?);
+            self.reader = Some(BufReader::new(file));
+            self.position = 0;
+            self.partial.clear();
+        } else if metadata.len() < self.position {
+            if let Some(reader) = self.reader.as_mut() {
+                reader.seek(SeekFrom::Start(0))?;
             }
+            self.position = 0;
+            self.partial.clear();
         }
         
-        println!("{}", formatter.format_event(event));
+        self.read_available()
     }
     
-    // Follow mode
-    if args.follow {
-        let mut reader = BufReader::new(file);
-        reader.seek(SeekFrom::End(0))?;
+    fn read_available(&mut self) -> Result<(), std::io::Error> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(());
+        };
         
         loop {
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // No new data, wait a bit
-                    thread::sleep(Duration::from_millis(100));
-                }
-                Ok(_) => {
-                    let line = line.trim_end();
-                    if line.is_empty() {
-                        continue;
-                    }
-                    
-                    let result = parser.parse_line_with_source(line, &source);
-                    let event = &result.event;
-                    
-                    // Apply filters
-                    if let Some(ref allowed_levels) = levels {
-                        if let Some(level) = event.level {
-                            if !allowed_levels.contains(&level) {
-                                continue;
-                            }
-                        } else {
-                            continue;
-                        }
-                    }
-                    
-                    if let Some(ref pattern) = grep_pattern {
-                        if !pattern.is_match(&event.message) && !pattern.is_match(&event.raw) {
-                            continue;
-                        }
-                    }
-                    
-                    println!("{}", formatter.format_event(event));
-                }
-                Err(e) => {
-                    eprintln!("Error reading file: {}", e);
-                    break;
+            let read = reader.read_line(&mut self.partial)?;
+            if read == 0 {
+                return Ok(());
+            }
+            self.position += read as u64;
+            
+            if self.partial.ends_with('\n') {
+                let mut line = std::mem::take(&mut self.partial);
+                let offset = self.position - line.len() as u64;
+                // Only the line ending goes; trailing spaces are part of the line, as in `OffsetLines`
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
                 }
+                self.assembler.push_line(SourceLine {
+                    offset: Some(offset),
+                    text: line,
+                    number: None,
+                });
             }
         }
     }
     
-    Ok(())
+    fn print_ready(&mut self, parser: &mut TangoParser, printer: &mut Printer) {
+        while let Some(group) = self.assembler.next_group() {
+            if group.is_blank() {
+                continue;
+            }
+            
+            let mut result = parser.parse_group_with_source(&group, &self.source);
+            result.event.source.file = Some(self.source.clone());
+            match printer.rejects.reject(&result) {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => eprintln!("Error writing rejects: {}", e),
+            }
+            if !printer.filter.matches(&result.event) {
+                continue;
+            }
+            
+            let line = printer.formatter.format_event(&result.event);
+            if printer.prefix {
+                println!("{} {}", format!("[{}]", self.source).cyan(), line);
+            } else {
+                println!("{}", line);
+            }
+        }
+        // Followed files never finish, so rejects are written as they come
+        if let Err(e) = printer.rejects.flush() {
+            eprintln!("Error writing rejects: {}", e);
+        }
+    }
+}
+
+/// Paths matching the patterns that are not followed yet. Plain paths that do not
+/// exist are included when `include_missing` is set, to be picked up once created.
+fn untracked_paths(patterns: &[PathBuf], files: &[TailedFile], include_missing: bool) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for pattern in patterns {
+        if is_glob(pattern) {
+            match glob::glob(&pattern.to_string_lossy()) {
+                Ok(matches) => paths.extend(matches.filter_map(Result::ok).filter(|p| p.is_file())),
+                Err(e) => eprintln!("Invalid pattern {}: {}", pattern.display(), e),
+            }
+        } else if include_missing || pattern.exists() {
+            paths.push(pattern.clone());
+        } else {
+            eprintln!("{}: No such file", pattern.display());
+        }
+    }
+    
+    paths.retain(|path| !files.iter().any(|file| file.path == *path));
+    paths.dedup();
+    paths
+}
+
+fn is_glob(pattern: &Path) -> bool {
+    let pattern = pattern.to_string_lossy();
+    pattern.contains('*') || pattern.contains('?') || pattern.contains('[')
+}
+
+/// Directories to watch: the parent of each path, or for a glob the deepest
+/// directory before the first wildcard (recursively if the wildcard spans directories)
+fn watch_dirs(patterns: &[PathBuf]) -> Vec<(PathBuf, RecursiveMode)> {
+    let mut dirs: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+    for pattern in patterns {
+        let mut dir = PathBuf::new();
+        let mut mode = RecursiveMode::NonRecursive;
+        let components: Vec<_> = pattern.components().collect();
+        for (i, component) in components.iter().enumerate() {
+            let is_last = i + 1 == components.len();
+            if is_last {
+                break;
+            }
+            if is_glob(Path::new(component.as_os_str())) {
+                mode = RecursiveMode::Recursive;
+                break;
+            }
+            dir.push(component);
+        }
+        if dir.as_os_str().is_empty() {
+            dir = PathBuf::from(".");
+        }
+        if !dirs.iter().any(|(existing, _)| *existing == dir) {
+            dirs.push((dir, mode));
+        }
+    }
+    dirs
+}
+
+#[cfg(unix)]
+fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
 }
 
 fn read_last_n_lines(file: &mut File, n: usize) -> Result<Vec<String>, std::io::Error> {
@@ -0,0 +1,251 @@
+use crate::error::ParseError;
+use crate::tango_parser::{ProfileConfig, TangoConfig, TangoParser};
+use std::path::{Path, PathBuf};
+
+/// File names searched in the working directory, in order
+const LOCAL_CONFIG_FILES: &[&str] = &["tango.toml", "tango.yaml", "tango.yml"];
+
+/// File names searched in `$XDG_CONFIG_HOME/tango`, in order
+const USER_CONFIG_FILES: &[&str] = &["config.toml", "config.yaml", "config.yml"];
+
+/// Load configuration from an explicit path, or from the first config file found.
+///
+/// Search order when no path is given: `./tango.toml`, `./tango.yaml`,
+/// `$XDG_CONFIG_HOME/tango/config.toml` (falling back to `~/.config`).
+/// When no file exists the default configuration is returned.
+pub fn load_config(explicit: Option<&Path>) -> Result<TangoConfig, ParseError> {
+    match explicit {
+        Some(path) => load_config_file(path),
+        None => match find_config_file() {
+            Some(path) => load_config_file(&path),
+            None => Ok(TangoConfig::default()),
+        },
+    }
+}
+
+/// Locate the first existing config file in the standard search locations
+pub fn find_config_file() -> Option<PathBuf> {
+    let local = LOCAL_CONFIG_FILES.iter().map(PathBuf::from);
+    let user = user_config_dir()
+        .map(|dir| USER_CONFIG_FILES.iter().map(|name| dir.join(name)).collect::<Vec<_>>())
+        .unwrap_or_default();
+    
+    local.chain(user).find(|path| path.is_file())
+}
+
+/// Per-user config directory (`$XDG_CONFIG_HOME/tango` or `~/.config/tango`)
+fn user_config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("tango"))
+}
+
+/// Read, parse and validate a single config file
+pub fn load_config_file(path: &Path) -> Result<TangoConfig, ParseError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ParseError::IoError {
+        operation: format!("reading config file {}", path.display()),
+        error_message: e.to_string(),
+    })?;
+    
+    let is_yaml = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml") | Some("yml")
+    );
+    let result = if is_yaml {
+        parse_yaml_config(&contents)
+    } else {
+        parse_toml_config(&contents)
+    };
+    
+    // Point errors at the offending file
+    result.map_err(|e| match e {
+        ParseError::ConfigurationError { parameter, error_message } => ParseError::ConfigurationError {
+            parameter,
+            error_message: format!("{} (in {})", error_message, path.display()),
+        },
+        other => other,
+    })
+}
+
+/// Parse and validate a TOML configuration document
+pub fn parse_toml_config(contents: &str) -> Result<TangoConfig, ParseError> {
+    let config: TangoConfig = toml::from_str(contents).map_err(|e| ParseError::ConfigurationError {
+        parameter: "config".to_string(),
+        error_message: e.to_string(),
+    })?;
+    finalize_config(config)
+}
+
+/// Parse and validate a YAML configuration document
+pub fn parse_yaml_config(contents: &str) -> Result<TangoConfig, ParseError> {
+    let config: TangoConfig = serde_yaml::from_str(contents).map_err(|e| ParseError::ConfigurationError {
+        parameter: "config".to_string(),
+        error_message: e.to_string(),
+    })?;
+    finalize_config(config)
+}
+
+/// Fill in defaults derived from the document and validate every profile and binding
+fn finalize_config(mut config: TangoConfig) -> Result<TangoConfig, ParseError> {
+    // Profiles are keyed by name, so the inner name field is optional in files
+    for (name, profile) in config.profiles.iter_mut() {
+        match profile {
+            ProfileConfig::Regex(regex_config) if regex_config.name.is_empty() => {
+                regex_config.name = name.clone();
+            }
+            ProfileConfig::Csv(csv_config) if csv_config.name.is_empty() => {
+                csv_config.name = name.clone();
+            }
+            _ => {}
+        }
+    }
+    
+    let mut names: Vec<&String> = config.profiles.keys().collect();
+    names.sort();
+    for name in names {
+        TangoParser::create_profile_parser(&config.profiles[name])
+            .map_err(|e| profile_error(name, e))?;
+    }
+    
+    TangoParser::with_config(config.clone()).validate_config()?;
+    Ok(config)
+}
+
+/// Qualify a profile construction error with the profile it came from
+fn profile_error(name: &str, error: ParseError) -> ParseError {
+    match error {
+        ParseError::ConfigurationError { parameter, error_message } => ParseError::ConfigurationError {
+            parameter: format!("profiles.{}.{}", name, parameter),
+            error_message,
+        },
+        ParseError::RegexError { pattern, error_message } => ParseError::ConfigurationError {
+            parameter: format!("profiles.{}.pattern", name),
+            error_message: format!("Invalid regex '{}': {}", pattern, error_message),
+        },
+        other => ParseError::ConfigurationError {
+            parameter: format!("profiles.{}", name),
+            error_message: other.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_toml_profiles_and_bindings() {
+        let config = parse_toml_config(r#"
+            cache_max_entries = 50
+            default_source = "app"
+            
+            [streaming_config]
+            batch_size = 10
+            
+            [profiles.myapp]
+            type = "regex"
+            pattern = '^(\S+) (\w+) (.*)$'
+            field_mappings = { ts = 1, lvl = 2, msg = 3 }
+            timestamp_field = "ts"
+            level_field = "lvl"
+            message_field = "msg"
+            
+            [profiles.web]
+            type = "nginx"
+            
+            [[bindings]]
+            pattern = "/var/log/myapp/*.log"
+            profile = "myapp"
+        "#).unwrap();
+        
+        assert_eq!(config.cache_max_entries, 50);
+        assert_eq!(config.streaming_config.batch_size, 10);
+        assert_eq!(config.streaming_config.buffer_size, 64 * 1024);
+        assert!(matches!(config.profiles.get("web"), Some(ProfileConfig::Nginx { .. })));
+        match config.profiles.get("myapp") {
+            Some(ProfileConfig::Regex(regex_config)) => assert_eq!(regex_config.name, "myapp"),
+            other => panic!("Expected regex profile, got {:?}", other),
+        }
+        
+        let mut parser = TangoParser::with_config(config);
+        let result = parser.parse_line_with_source("2025-01-01T00:00:00Z ERROR boom", "/var/log/myapp/a.log");
+        assert!(result.success);
+        assert_eq!(result.event.format_type, crate::models::FormatType::Profile(crate::models::ProfileType::Regex));
+        assert_eq!(result.event.message, "boom");
+    }
+    
+    #[test]
+    fn test_yaml_config() {
+        let config = parse_yaml_config(r#"
+enable_statistics: false
+profiles:
+  events:
+    type: csv
+    column_mappings: { ts: 0, msg: 1 }
+    message_column: msg
+"#).unwrap();
+        
+        assert!(!config.enable_statistics);
+        match config.profiles.get("events") {
+            Some(ProfileConfig::Csv(csv_config)) => {
+                assert_eq!(csv_config.delimiter, ',');
+                assert_eq!(csv_config.name, "events");
+            }
+            other => panic!("Expected csv profile, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_invalid_profile_points_at_profile() {
+        let result = parse_toml_config(r#"
+            [profiles.broken]
+            type = "regex"
+            pattern = "(unclosed"
+        "#);
+        
+        match result {
+            Err(ParseError::ConfigurationError { parameter, .. }) => {
+                assert_eq!(parameter, "profiles.broken.pattern");
+            }
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+
+        let result = parse_toml_config(r#"
+            [profiles.edge]
+            type = "syslog"
+            default_timezone = "America/Chicagoo"
+        "#);
+
+        match result {
+            Err(ParseError::ConfigurationError { parameter, .. }) => {
+                assert_eq!(parameter, "profiles.edge.default_timezone");
+            }
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_binding_to_unknown_profile() {
+        let result = parse_toml_config(r#"
+            [[bindings]]
+            pattern = "*.log"
+            profile = "missing"
+        "#);
+        
+        match result {
+            Err(ParseError::ConfigurationError { parameter, error_message }) => {
+                assert_eq!(parameter, "bindings[0].profile");
+                assert!(error_message.contains("missing"));
+            }
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_missing_explicit_file_is_error() {
+        let result = load_config(Some(Path::new("/nonexistent/tango.toml")));
+        assert!(matches!(result, Err(ParseError::IoError { .. })));
+    }
+}
@@ -0,0 +1,96 @@
+/// Kubernetes and Docker metadata encoded in a container log's path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContainerPath {
+    pub namespace: Option<String>,
+    pub pod: Option<String>,
+    pub pod_uid: Option<String>,
+    pub container: Option<String>,
+    pub container_id: Option<String>,
+}
+
+impl ContainerPath {
+    /// Metadata from the layouts kubelet and Docker write container logs in
+    /// (also inside archives and rotated copies):
+    /// - `/var/log/pods/<namespace>_<pod>_<uid>/<container>/<restarts>.log`
+    /// - `/var/log/containers/<pod>_<namespace>_<container>-<id>.log`
+    /// - `/var/lib/docker/containers/<id>/<id>-json.log`
+    pub fn parse(path: &str) -> Option<Self> {
+        let parts: Vec<&str> = path.split(['/', '\\']).collect();
+        let position = parts.iter().rposition(|part| *part == "pods" || *part == "containers")?;
+        match &parts[position + 1..] {
+            [pod_dir, container, _file] if parts[position] == "pods" => {
+                let mut pod_dir = pod_dir.splitn(3, '_');
+                let (namespace, pod, uid) = (pod_dir.next()?, pod_dir.next()?, pod_dir.next()?);
+                Some(Self {
+                    namespace: Some(namespace.to_string()),
+                    pod: Some(pod.to_string()),
+                    pod_uid: Some(uid.to_string()),
+                    container: Some(container.to_string()),
+                    container_id: None,
+                })
+            }
+            [file] if parts[position] == "containers" => {
+                let name = &file[..file.find(".log")?];
+                let mut name = name.splitn(3, '_');
+                let (pod, namespace, rest) = (name.next()?, name.next()?, name.next()?);
+                let (container, id) = rest.rsplit_once('-').filter(|(_, id)| is_container_id(id))?;
+                Some(Self {
+                    namespace: Some(namespace.to_string()),
+                    pod: Some(pod.to_string()),
+                    pod_uid: None,
+                    container: Some(container.to_string()),
+                    container_id: Some(id.to_string()),
+                })
+            }
+            [id, file] if parts[position] == "containers" && is_container_id(id) && file.starts_with(id) => Some(Self {
+                container_id: Some(id.to_string()),
+                ..Self::default()
+            }),
+            _ => None,
+        }
+    }
+    
+    /// Event fields for the metadata present
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("namespace", &self.namespace),
+            ("pod", &self.pod),
+            ("pod_uid", &self.pod_uid),
+            ("container", &self.container),
+            ("container_id", &self.container_id),
+        ].into_iter().filter_map(|(name, value)| Some((name, value.as_deref()?)))
+    }
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_metadata_from_container_log_paths() {
+        let id = "8f3b5c2e9d1a4b6c7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d";
+        
+        let pods = ContainerPath::parse("/var/log/pods/kube-system_coredns-5d78c9869d-x2x4k_0b1c3f9e-77aa-4d1e-9f2b-3c4d5e6f7a8b/coredns/0.log.20240101-000000.gz").unwrap();
+        assert_eq!(pods.namespace.as_deref(), Some("kube-system"));
+        assert_eq!(pods.pod.as_deref(), Some("coredns-5d78c9869d-x2x4k"));
+        assert_eq!(pods.pod_uid.as_deref(), Some("0b1c3f9e-77aa-4d1e-9f2b-3c4d5e6f7a8b"));
+        assert_eq!(pods.container.as_deref(), Some("coredns"));
+        
+        let link = ContainerPath::parse(&format!("/var/log/containers/api-7c9d_prod_server-{}.log", id)).unwrap();
+        assert_eq!(
+            link.fields().collect::<Vec<_>>(),
+            [("namespace", "prod"), ("pod", "api-7c9d"), ("container", "server"), ("container_id", id)]
+        );
+        
+        let docker = ContainerPath::parse(&format!("backup.tar.gz!/var/lib/docker/containers/{0}/{0}-json.log.1", id)).unwrap();
+        assert_eq!(docker.container_id.as_deref(), Some(id));
+        assert_eq!(docker.pod, None);
+        
+        assert_eq!(ContainerPath::parse("/var/log/syslog"), None);
+        assert_eq!(ContainerPath::parse("/srv/containers/notes.txt"), None);
+    }
+}
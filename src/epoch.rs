@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Unit of an epoch timestamp
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EpochUnit {
+    /// Inferred from the magnitude: seconds below 10^11 (up to the year 5138),
+    /// then milliseconds, microseconds and nanoseconds
+    #[default]
+    Auto,
+    #[serde(alias = "s")]
+    Seconds,
+    #[serde(alias = "ms")]
+    Millis,
+    #[serde(alias = "us")]
+    Micros,
+    #[serde(alias = "ns")]
+    Nanos,
+}
+
+impl EpochUnit {
+    /// Unit of an epoch whose integer part is `integer`
+    pub fn resolve(self, integer: u128) -> Self {
+        match self {
+            EpochUnit::Auto if integer < 100_000_000_000 => EpochUnit::Seconds,
+            EpochUnit::Auto if integer < 100_000_000_000_000 => EpochUnit::Millis,
+            EpochUnit::Auto if integer < 100_000_000_000_000_000 => EpochUnit::Micros,
+            EpochUnit::Auto => EpochUnit::Nanos,
+            unit => unit,
+        }
+    }
+    
+    fn nanos(self) -> u128 {
+        match self {
+            EpochUnit::Auto | EpochUnit::Seconds => 1_000_000_000,
+            EpochUnit::Millis => 1_000_000,
+            EpochUnit::Micros => 1_000,
+            EpochUnit::Nanos => 1,
+        }
+    }
+}
+
+impl FromStr for EpochUnit {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(EpochUnit::Auto),
+            "s" | "seconds" => Ok(EpochUnit::Seconds),
+            "ms" | "millis" => Ok(EpochUnit::Millis),
+            "us" | "micros" => Ok(EpochUnit::Micros),
+            "ns" | "nanos" => Ok(EpochUnit::Nanos),
+            _ => Err(format!("unknown epoch unit '{}' (expected auto, s, ms, us or ns)", s)),
+        }
+    }
+}
+
+/// Instant a decimal epoch (`1700000000`, `1700000000.123`, `-5`, `1.7e9`)
+/// denotes in `unit`; fractions below a nanosecond are dropped
+pub fn parse_epoch(text: &str, unit: EpochUnit) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let is_decimal = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if integer.is_empty() || integer.len() > 20 || !is_decimal(integer) || !is_decimal(fraction) {
+        return parse_float_epoch(text, unit);
+    }
+    
+    let integer: u128 = integer.parse().ok()?;
+    let scale = unit.resolve(integer).nanos();
+    let fraction = &fraction[..fraction.len().min(18)];
+    let fraction_nanos = match fraction.len() {
+        0 => 0,
+        len => fraction.parse::<u128>().ok()? * scale / 10u128.pow(len as u32),
+    };
+    let nanos = i128::try_from(integer * scale + fraction_nanos).ok()?;
+    from_nanos(if negative { -nanos } else { nanos })
+}
+
+/// `parse_epoch` for text fields. Under `Auto` they need at least 9 integer
+/// digits (1973 onwards in seconds) so counts and codes aren't read as times
+pub fn parse_epoch_text(text: &str, unit: EpochUnit) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    let integer_digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if unit == EpochUnit::Auto && integer_digits < 9 {
+        return None;
+    }
+    parse_epoch(text, unit)
+}
+
+/// Epochs in exponent notation, as JSON encoders write large floats
+fn parse_float_epoch(text: &str, unit: EpochUnit) -> Option<DateTime<Utc>> {
+    let value: f64 = text.parse().ok().filter(|value: &f64| value.is_finite())?;
+    let scale = unit.resolve(value.abs().trunc() as u128).nanos();
+    from_nanos((value * scale as f64) as i128)
+}
+
+fn from_nanos(nanos: i128) -> Option<DateTime<Utc>> {
+    let seconds = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+    DateTime::from_timestamp(seconds, nanos.rem_euclid(1_000_000_000) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_units_follow_magnitude_unless_forced() {
+        let expected = DateTime::from_timestamp(1_700_000_000, 123_000_000).unwrap();
+        for epoch in ["1700000000.123", "1700000000123", "1700000000123000", "1700000000123000000"] {
+            assert_eq!(parse_epoch(epoch, EpochUnit::Auto), Some(expected), "{}", epoch);
+        }
+        assert_eq!(parse_epoch("1700000000123.5", EpochUnit::Auto), DateTime::from_timestamp(1_700_000_000, 123_500_000));
+        assert_eq!(parse_epoch("1.7e12", EpochUnit::Auto), DateTime::from_timestamp(1_700_000_000, 0));
+        assert_eq!(parse_epoch("1700000000", EpochUnit::Millis), DateTime::from_timestamp(1_700_000, 0));
+        assert_eq!(parse_epoch("-1.5", EpochUnit::Seconds), DateTime::from_timestamp(-2, 500_000_000));
+        assert!(parse_epoch("17e", EpochUnit::Auto).is_none());
+        
+        assert!(parse_epoch_text("404", EpochUnit::Auto).is_none());
+        assert_eq!(parse_epoch_text("404", EpochUnit::Seconds), DateTime::from_timestamp(404, 0));
+        assert_eq!("ms".parse::<EpochUnit>(), Ok(EpochUnit::Millis));
+    }
+}
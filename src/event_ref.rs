@@ -0,0 +1,315 @@
+use crate::models::{CanonicalEvent, FormatType, LogLevel, LogTimestamp, SourceMetadata};
+use crate::timestamp::Recognized;
+use chrono::{DateTime, Utc};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A JSON value whose strings borrow from the parsed text where no unescaping was needed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(Cow<'a, str>),
+    Array(Vec<ValueRef<'a>>),
+    /// Members in document order; on duplicate keys the last one counts
+    Object(Vec<(Cow<'a, str>, ValueRef<'a>)>),
+}
+
+impl<'a> ValueRef<'a> {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ValueRef::String(text) => Some(text),
+            _ => None,
+        }
+    }
+    
+    /// Member of an object by key
+    pub fn get(&self, key: &str) -> Option<&ValueRef<'a>> {
+        match self {
+            ValueRef::Object(members) => members.iter().rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    
+    /// Copy into an owned `serde_json::Value`
+    pub fn to_value(&self) -> Value {
+        self.clone().into_owned()
+    }
+    
+    pub fn into_owned(self) -> Value {
+        match self {
+            ValueRef::Null => Value::Null,
+            ValueRef::Bool(value) => Value::Bool(value),
+            ValueRef::Number(number) => Value::Number(number),
+            ValueRef::String(text) => Value::String(text.into_owned()),
+            ValueRef::Array(items) => Value::Array(items.into_iter().map(ValueRef::into_owned).collect()),
+            ValueRef::Object(members) => {
+                let mut map = Map::new();
+                for (key, value) in members {
+                    map.insert(key.into_owned(), value.into_owned());
+                }
+                Value::Object(map)
+            }
+        }
+    }
+}
+
+impl From<Value> for ValueRef<'_> {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => ValueRef::Null,
+            Value::Bool(value) => ValueRef::Bool(value),
+            Value::Number(number) => ValueRef::Number(number),
+            Value::String(text) => ValueRef::String(Cow::Owned(text)),
+            Value::Array(items) => ValueRef::Array(items.into_iter().map(ValueRef::from).collect()),
+            Value::Object(map) => ValueRef::Object(map.into_iter()
+                .map(|(key, value)| (Cow::Owned(key), ValueRef::from(value)))
+                .collect()),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(text: &'a str) -> Self {
+        ValueRef::String(Cow::Borrowed(text))
+    }
+}
+
+impl fmt::Display for ValueRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_value())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueRef<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueRefVisitor)
+    }
+}
+
+struct ValueRefVisitor;
+
+impl<'de> Visitor<'de> for ValueRefVisitor {
+    type Value = ValueRef<'de>;
+    
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+    
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(ValueRef::Null)
+    }
+    
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(ValueRef::Null)
+    }
+    
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(ValueRef::Bool(value))
+    }
+    
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(ValueRef::Number(value.into()))
+    }
+    
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(ValueRef::Number(value.into()))
+    }
+    
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Number::from_f64(value).map_or(ValueRef::Null, ValueRef::Number))
+    }
+    
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Borrowed(value)))
+    }
+    
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Owned(value.to_string())))
+    }
+    
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
+        Ok(ValueRef::String(Cow::Owned(value)))
+    }
+    
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(ValueRef::Array(items))
+    }
+    
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut members = Vec::new();
+        while let Some(KeyRef(key)) = map.next_key()? {
+            members.push((key, map.next_value()?));
+        }
+        Ok(ValueRef::Object(members))
+    }
+}
+
+/// Object key that borrows like `ValueRef` strings (serde's `Cow` impl always copies)
+struct KeyRef<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for KeyRef<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.deserialize_str(ValueRefVisitor)? {
+            ValueRef::String(key) => Ok(KeyRef(key)),
+            _ => Err(de::Error::custom("expected a string key")),
+        }
+    }
+}
+
+/// `SourceMetadata` borrowing its names from the input
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceRef<'a> {
+    pub file: Option<Cow<'a, str>>,
+    pub stream: Option<Cow<'a, str>>,
+    pub host: Option<Cow<'a, str>>,
+    /// Byte offset of the event's first line in its input
+    pub offset: Option<u64>,
+    /// 1-based line number of the event's first line
+    pub line: Option<usize>,
+}
+
+impl SourceRef<'_> {
+    pub fn into_owned(self) -> SourceMetadata {
+        SourceMetadata {
+            file: self.file.map(Cow::into_owned),
+            stream: self.stream.map(Cow::into_owned),
+            host: self.host.map(Cow::into_owned),
+            offset: self.offset,
+            line: self.line,
+        }
+    }
+}
+
+impl From<SourceMetadata> for SourceRef<'_> {
+    fn from(source: SourceMetadata) -> Self {
+        Self {
+            file: source.file.map(Cow::Owned),
+            stream: source.stream.map(Cow::Owned),
+            host: source.host.map(Cow::Owned),
+            offset: source.offset,
+            line: source.line,
+        }
+    }
+}
+
+/// `CanonicalEvent` whose text borrows from the line it was parsed from
+/// (or the mapped file holding it); `into_owned` copies it out
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalEventRef<'a> {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub level: Option<LogLevel>,
+    pub message: Cow<'a, str>,
+    pub fields: HashMap<Cow<'a, str>, ValueRef<'a>>,
+    pub raw: Cow<'a, str>,
+    pub source: SourceRef<'a>,
+    pub parse_error: Option<bool>,
+    pub year_inferred: Option<bool>,
+    pub offset_assumed: Option<bool>,
+    pub format_type: FormatType,
+}
+
+impl<'a> CanonicalEventRef<'a> {
+    pub fn new(message: impl Into<Cow<'a, str>>, raw: &'a str, format_type: FormatType) -> Self {
+        Self {
+            timestamp: None,
+            level: None,
+            message: message.into(),
+            fields: HashMap::new(),
+            raw: Cow::Borrowed(raw),
+            source: SourceRef::default(),
+            parse_error: None,
+            year_inferred: None,
+            offset_assumed: None,
+            format_type,
+        }
+    }
+    
+    /// Set the timestamp, marking whether the log gave its offset
+    pub fn set_log_timestamp(&mut self, timestamp: LogTimestamp) {
+        match timestamp {
+            LogTimestamp::Explicit(timestamp) => {
+                self.timestamp = Some(timestamp);
+                self.offset_assumed = Some(false);
+            }
+            LogTimestamp::Naive(naive) => {
+                self.timestamp = Some(naive.and_utc());
+                self.offset_assumed = Some(true);
+            }
+        }
+    }
+    
+    /// Set a timestamp the catalog recognized, as `CanonicalEvent::set_recognized_timestamp`
+    pub fn set_recognized_timestamp(&mut self, recognized: Recognized) {
+        self.set_log_timestamp(recognized.timestamp);
+        if recognized.year_inferred {
+            self.year_inferred = Some(true);
+        }
+    }
+    
+    pub fn add_field(&mut self, key: impl Into<Cow<'a, str>>, value: impl Into<ValueRef<'a>>) {
+        self.fields.insert(key.into(), value.into());
+    }
+    
+    pub fn into_owned(self) -> CanonicalEvent {
+        CanonicalEvent {
+            timestamp: self.timestamp,
+            level: self.level,
+            message: self.message.into_owned(),
+            fields: self.fields.into_iter()
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect(),
+            raw: self.raw.into_owned(),
+            source: self.source.into_owned(),
+            parse_error: self.parse_error,
+            year_inferred: self.year_inferred,
+            offset_assumed: self.offset_assumed,
+            format_type: self.format_type,
+        }
+    }
+}
+
+impl From<CanonicalEvent> for CanonicalEventRef<'_> {
+    fn from(event: CanonicalEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            level: event.level,
+            message: Cow::Owned(event.message),
+            fields: event.fields.into_iter()
+                .map(|(key, value)| (Cow::Owned(key), ValueRef::from(value)))
+                .collect(),
+            raw: Cow::Owned(event.raw),
+            source: event.source.into(),
+            parse_error: event.parse_error,
+            year_inferred: event.year_inferred,
+            offset_assumed: event.offset_assumed,
+            format_type: event.format_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_value_ref_borrows_unescaped_strings() {
+        let line = r#"{"msg":"plain","path":"a\"b","n":[1,2.5,null],"nested":{"ok":true},"msg":"last"}"#;
+        let value: ValueRef = serde_json::from_str(line).unwrap();
+        
+        assert!(matches!(value.get("msg"), Some(ValueRef::String(Cow::Borrowed("last")))));
+        assert!(matches!(value.get("path"), Some(ValueRef::String(Cow::Owned(text))) if text == "a\"b"));
+        assert_eq!(value.get("nested").and_then(|nested| nested.get("ok")), Some(&ValueRef::Bool(true)));
+        assert_eq!(value.into_owned(), serde_json::from_str::<Value>(line).unwrap());
+    }
+}
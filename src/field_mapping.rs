@@ -0,0 +1,155 @@
+use crate::epoch::{parse_epoch, parse_epoch_text, EpochUnit};
+use crate::error::ParseError;
+use crate::event_ref::ValueRef;
+use crate::models::LogTimestamp;
+use crate::timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Keys the canonical timestamp, level and message are read from in JSON and
+/// logfmt lines, tried in order. Dotted names also match nested JSON objects,
+/// so `log.level` finds `{"log":{"level":"warn"}}` as well as a literal key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldMappings {
+    pub timestamp: Vec<String>,
+    pub level: Vec<String>,
+    pub message: Vec<String>,
+    /// Unit of numeric timestamps and epoch strings
+    pub epoch_unit: EpochUnit,
+}
+
+impl Default for FieldMappings {
+    fn default() -> Self {
+        Self {
+            timestamp: names(&["ts", "time", "timestamp", "@timestamp"]),
+            level: names(&["level", "severity", "lvl", "log.level"]),
+            message: names(&["msg", "message", "log.message"]),
+            epoch_unit: EpochUnit::Auto,
+        }
+    }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+impl FieldMappings {
+    /// These mappings with the lists `overrides` sets replaced
+    pub fn overridden_by(&self, overrides: &SourceFieldMappings) -> Self {
+        Self {
+            timestamp: overrides.timestamp.clone().unwrap_or_else(|| self.timestamp.clone()),
+            level: overrides.level.clone().unwrap_or_else(|| self.level.clone()),
+            message: overrides.message.clone().unwrap_or_else(|| self.message.clone()),
+            epoch_unit: overrides.epoch_unit.unwrap_or(self.epoch_unit),
+        }
+    }
+    
+    /// Detection metadata naming the keys tried for each canonical field
+    pub fn describe(&self) -> [(String, String); 3] {
+        [
+            ("timestamp_fields".to_string(), self.timestamp.join(",")),
+            ("level_fields".to_string(), self.level.join(",")),
+            ("message_fields".to_string(), self.message.join(",")),
+        ]
+    }
+    
+    pub fn validate(&self, parameter: &str) -> Result<(), ParseError> {
+        let lists = [("timestamp", &self.timestamp), ("level", &self.level), ("message", &self.message)];
+        for (field, keys) in lists {
+            if keys.iter().any(|key| key.is_empty() || key.split('.').any(str::is_empty)) {
+                return Err(ParseError::ConfigurationError {
+                    parameter: format!("{}.{}", parameter, field),
+                    error_message: "Field names must be non-empty, with no empty path segments".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Field mappings for sources matching a glob pattern; unset lists keep the
+/// global `field_mappings`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFieldMappings {
+    /// Glob pattern matched against the source identifier (e.g. "/var/log/billing/*.log")
+    pub pattern: String,
+    #[serde(default)]
+    pub timestamp: Option<Vec<String>>,
+    #[serde(default)]
+    pub level: Option<Vec<String>>,
+    #[serde(default)]
+    pub message: Option<Vec<String>>,
+    #[serde(default)]
+    pub epoch_unit: Option<EpochUnit>,
+}
+
+/// First of `keys` for which `get` finds a value, with the key it was found under
+pub fn first_mapped<T>(keys: &[String], mut get: impl FnMut(&str) -> Option<T>) -> Option<(&str, T)> {
+    keys.iter().find_map(|key| get(key).map(|value| (key.as_str(), value)))
+}
+
+/// Value at a dotted path: a literal key first, then nested objects
+pub fn lookup_path<'v, 'a>(object: &'v ValueRef<'a>, path: &str) -> Option<&'v ValueRef<'a>> {
+    if let Some(value) = object.get(path) {
+        return Some(value);
+    }
+    // Split at each dot in turn so keys that themselves contain dots still match
+    path.match_indices('.').find_map(|(i, _)| {
+        match object.get(&path[..i]) {
+            Some(nested @ ValueRef::Object(_)) => lookup_path(nested, &path[i + 1..]),
+            _ => None,
+        }
+    })
+}
+
+/// Timestamp held by a mapped field: any format in the timestamp catalog,
+/// or an epoch number or string in `epoch_unit`
+pub fn parse_timestamp_value(value: &ValueRef, epoch_unit: EpochUnit) -> Option<LogTimestamp> {
+    match value {
+        ValueRef::String(s) => {
+            if let Some(recognized) = timestamp::recognize(s, None) {
+                return Some(recognized.timestamp);
+            }
+            // Epochs written as text, e.g. logfmt `ts=1700000000.123`
+            parse_epoch_text(s, epoch_unit).map(LogTimestamp::Explicit)
+        }
+        // Integer and float epochs, in the unit their magnitude suggests
+        ValueRef::Number(n) => parse_epoch(&n.to_string(), epoch_unit).map(LogTimestamp::Explicit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_lookup_path_prefers_literal_keys_then_nests() {
+        let value: ValueRef = serde_json::from_str(r#"{"log":{"level":"warn","a.b":{"c":1}},"log.level":"info"}"#).unwrap();
+        assert_eq!(lookup_path(&value, "log.level").and_then(ValueRef::as_str), Some("info"));
+        
+        let value: ValueRef = serde_json::from_str(r#"{"log":{"level":"warn","a.b":{"c":1}}}"#).unwrap();
+        assert_eq!(lookup_path(&value, "log.level").and_then(ValueRef::as_str), Some("warn"));
+        assert!(lookup_path(&value, "log.a.b.c").is_some());
+        assert!(lookup_path(&value, "log.missing").is_none());
+        
+        let keys = names(&["sev", "log.level"]);
+        assert_eq!(first_mapped(&keys, |key| lookup_path(&value, key)).map(|(key, _)| key), Some("log.level"));
+    }
+    
+    #[test]
+    fn test_source_overrides_replace_only_set_lists() {
+        let overrides = SourceFieldMappings {
+            pattern: "billing-*".to_string(),
+            timestamp: Some(names(&["eventTime"])),
+            level: None,
+            message: None,
+            epoch_unit: Some(EpochUnit::Millis),
+        };
+        let mappings = FieldMappings::default().overridden_by(&overrides);
+        assert_eq!(mappings.timestamp, ["eventTime"]);
+        assert_eq!(mappings.level, FieldMappings::default().level);
+        assert_eq!(mappings.epoch_unit, EpochUnit::Millis);
+        assert!(FieldMappings { level: names(&["log..level"]), ..mappings }.validate("field_mappings").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::error::ParseError;
+use crate::event_ref::ValueRef;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// How nested JSON objects and arrays are flattened into event fields
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlattenConfig {
+    /// Most path segments in a flattened key; deeper values are kept whole
+    /// under their key (unlimited when unset)
+    pub max_depth: Option<usize>,
+    
+    /// Flatten arrays into indexed keys (`items[0].id`) instead of keeping them whole
+    pub index_arrays: bool,
+    
+    /// Rebuild nested objects and arrays from the flattened keys in JSON and CSV output
+    pub nest_output: bool,
+}
+
+impl FlattenConfig {
+    /// Whether values under a key of `depth` segments are flattened further
+    pub fn descends(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
+    
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if self.max_depth == Some(0) {
+            return Err(ParseError::ConfigurationError {
+                parameter: "flatten.max_depth".to_string(),
+                error_message: "Max depth must be at least 1".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// One step of a field path
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A path into an event's fields such as `http.request.method` or `items[0].id`.
+/// It finds a value whether the JSON parser flattened it into that key or kept
+/// it nested under a shorter one, so every option naming a field agrees
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPath {
+    segments: Vec<Segment>,
+}
+
+impl FieldPath {
+    /// Split `a.b[0].c` into keys and indices
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        for part in text.split('.') {
+            let (key, mut indices) = match part.find('[') {
+                Some(start) => (&part[..start], &part[start..]),
+                None => (part, ""),
+            };
+            if key.is_empty() {
+                return Err(format!("empty name in path '{}'", text));
+            }
+            segments.push(Segment::Key(key.to_string()));
+            
+            while let Some(inner) = indices.strip_prefix('[') {
+                let end = inner.find(']').ok_or_else(|| format!("unclosed '[' in path '{}'", text))?;
+                let index = inner[..end].parse().map_err(|_| format!("invalid index in path '{}'", text))?;
+                segments.push(Segment::Index(index));
+                indices = &inner[end + 1..];
+            }
+            if !indices.is_empty() {
+                return Err(format!("unexpected '{}' in path '{}'", indices, text));
+            }
+        }
+        Ok(Self { segments })
+    }
+    
+    /// `parse`, or the whole text as a single key when it is not a valid path
+    pub fn parse_or_key(text: &str) -> Self {
+        Self::parse(text).unwrap_or_else(|_| Self { segments: vec![Segment::Key(text.to_string())] })
+    }
+    
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+    
+    /// Value in owned event fields
+    pub fn resolve<'v>(&self, fields: &'v HashMap<String, Value>) -> Option<&'v Value> {
+        self.resolve_with(|key| fields.get(key), |value, segment| match (segment, value) {
+            (Segment::Key(key), Value::Object(map)) => map.get(key),
+            (Segment::Key(key), Value::Array(items)) => items.get(key.parse::<usize>().ok()?),
+            (Segment::Index(index), Value::Array(items)) => items.get(*index),
+            _ => None,
+        })
+    }
+    
+    /// Value in fields borrowing from their line
+    pub fn resolve_ref<'v, 'a>(&self, fields: &'v HashMap<Cow<'a, str>, ValueRef<'a>>) -> Option<&'v ValueRef<'a>> {
+        self.resolve_with(|key| fields.get(key), |value, segment| match (segment, value) {
+            (Segment::Key(key), ValueRef::Object(_)) => value.get(key),
+            (Segment::Key(key), ValueRef::Array(items)) => items.get(key.parse::<usize>().ok()?),
+            (Segment::Index(index), ValueRef::Array(items)) => items.get(*index),
+            _ => None,
+        })
+    }
+    
+    /// Look up the longest flattened key the path starts with, then descend
+    /// the rest of the path inside its value
+    fn resolve_with<'v, V>(
+        &self,
+        get: impl Fn(&str) -> Option<&'v V>,
+        child: impl Fn(&'v V, &Segment) -> Option<&'v V>,
+    ) -> Option<&'v V> {
+        (1..=self.segments.len()).rev().find_map(|len| {
+            let value = get(&flattened_key(&self.segments[..len]))?;
+            self.segments[len..].iter().try_fold(value, &child)
+        })
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&flattened_key(&self.segments))
+    }
+}
+
+/// Key the JSON parser flattens a path to: `a.b[0].c`
+pub fn flattened_key(segments: &[Segment]) -> String {
+    let mut key = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Key(name) => {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+                key.push_str(name);
+            }
+            Segment::Index(index) => {
+                key.push_str(&format!("[{}]", index));
+            }
+        }
+    }
+    key
+}
+
+/// Rebuild nested objects and arrays from flattened keys, undoing the JSON
+/// parser's flattening. A key that collides with a value already placed on
+/// its path is kept flat
+pub fn unflatten<'f>(fields: impl IntoIterator<Item = (&'f str, &'f Value)>) -> Map<String, Value> {
+    let mut fields: Vec<_> = fields.into_iter().collect();
+    fields.sort_by_key(|(key, _)| *key);
+    
+    let mut root = Value::Object(Map::new());
+    let mut collisions = Vec::new();
+    for (key, value) in fields {
+        let path = FieldPath::parse_or_key(key);
+        if !insert_at(&mut root, &path.segments, value) {
+            collisions.push((key, value));
+        }
+    }
+    let Value::Object(mut map) = root else {
+        unreachable!()
+    };
+    for (key, value) in collisions {
+        map.insert(key.to_string(), value.clone());
+    }
+    map
+}
+
+/// Place `value` at the path below `node`, creating objects and arrays on the
+/// way; false if something else is already there
+fn insert_at(node: &mut Value, segments: &[Segment], value: &Value) -> bool {
+    let Some((segment, rest)) = segments.split_first() else {
+        if node.is_null() {
+            *node = value.clone();
+            return true;
+        }
+        return false;
+    };
+    let child = match (segment, node) {
+        (Segment::Key(key), Value::Object(map)) => map.entry(key.clone()).or_insert(Value::Null),
+        (Segment::Index(index), Value::Array(items)) => {
+            if items.len() <= *index {
+                items.resize(*index + 1, Value::Null);
+            }
+            &mut items[*index]
+        }
+        _ => return false,
+    };
+    if child.is_null() && !rest.is_empty() {
+        *child = match rest[0] {
+            Segment::Key(_) => Value::Object(Map::new()),
+            Segment::Index(_) => Value::Array(Vec::new()),
+        };
+    }
+    insert_at(child, rest, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn test_paths_resolve_flattened_and_nested_values() {
+        let mut fields = HashMap::new();
+        fields.insert("http.request.method".to_string(), json!("GET"));
+        fields.insert("user".to_string(), json!({"id": 7, "roles": ["admin", "ops"]}));
+        fields.insert("items[0].id".to_string(), json!("a1"));
+        
+        let resolve = |path: &str| FieldPath::parse(path).unwrap().resolve(&fields).cloned();
+        assert_eq!(resolve("http.request.method"), Some(json!("GET")));
+        assert_eq!(resolve("user.id"), Some(json!(7)));
+        assert_eq!(resolve("user.roles[1]"), Some(json!("ops")));
+        assert_eq!(resolve("items[0].id"), Some(json!("a1")));
+        assert_eq!(resolve("http.request"), None);
+        assert_eq!(FieldPath::parse("items[0].id").unwrap().to_string(), "items[0].id");
+        assert!(FieldPath::parse("a..b").is_err());
+        assert_eq!(FieldPath::parse_or_key("a[x").segments(), [Segment::Key("a[x".to_string())]);
+    }
+    
+    #[test]
+    fn test_unflatten_rebuilds_objects_and_arrays() {
+        let fields = [
+            ("http.request.method", json!("GET")),
+            ("http.status", json!(200)),
+            ("items[0].id", json!("a1")),
+            ("items[1].id", json!("b2")),
+            ("tags", json!(["x"])),
+            ("tags.extra", json!(true)),
+        ];
+        let nested = unflatten(fields.iter().map(|(key, value)| (*key, value)));
+        assert_eq!(Value::Object(nested), json!({
+            "http": {"request": {"method": "GET"}, "status": 200},
+            "items": [{"id": "a1"}, {"id": "b2"}],
+            "tags": ["x"],
+            "tags.extra": true,
+        }));
+    }
+}
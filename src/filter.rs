@@ -0,0 +1,815 @@
+use crate::timestamp::parse_time;
+use crate::error::ParseError;
+use crate::event_ref::{CanonicalEventRef, ValueRef};
+use crate::field_path::FieldPath;
+use crate::models::{CanonicalEvent, FormatType, LogLevel};
+use chrono::{DateTime, Utc};
+use ipnet::IpNet;
+use regex::Regex;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::net::IpAddr;
+
+/// An event filter compiled from an expression such as
+/// `level>=warn and (fields.status >= 500 or message ~ "timeout") and not host in ["lb1","lb2"]`.
+///
+/// Paths: `level`, `message`, `raw`, `timestamp`, `format`, `source.<file|stream|host|offset>`
+/// and `fields.<name>` with nested keys and `[index]`; any other bare name is a field.
+/// Operators: `== != > >= < <=`, `~` / `!~` (regex), `in [..]` (values or CIDR ranges),
+/// `exists <path>` (or a bare path), combined with `and`, `or`, `not` and parentheses.
+/// Comparisons against a missing value are false.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Always,
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Exists(Path),
+    Compare(Path, CompareOp, Literal),
+    Regex(Path, Regex),
+    In(Path, Vec<Literal>),
+    /// `--grep`: the message or the raw line matches
+    Grep(Regex),
+    /// `-F key=value`: the field's text contains the value
+    FieldContains(FieldPath, String),
+    /// `--since` / `--until`: events without a timestamp pass
+    TimeRange(Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Path {
+    Level,
+    Message,
+    Raw,
+    Timestamp,
+    Format,
+    Source(String),
+    Field(FieldPath),
+}
+
+/// Right-hand side of a comparison, typed at compile time by the path it is compared to
+#[derive(Debug, Clone)]
+enum Literal {
+    Level(LogLevel),
+    Time(DateTime<Utc>),
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Null,
+    Cidr(IpNet),
+}
+
+/// Value of a path on one event
+enum Operand<'a> {
+    Level(LogLevel),
+    Time(DateTime<Utc>),
+    Text(Cow<'a, str>),
+    Number(f64),
+    Json(&'a Value),
+    JsonRef(&'a ValueRef<'a>),
+}
+
+impl Operand<'_> {
+    fn as_text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Operand::Text(text) => Some(Cow::Borrowed(text.as_ref())),
+            Operand::Json(Value::String(text)) => Some(Cow::Borrowed(text)),
+            Operand::Json(Value::Null) => None,
+            Operand::Json(value) => Some(Cow::Owned(value.to_string())),
+            Operand::JsonRef(ValueRef::String(text)) => Some(Cow::Borrowed(text)),
+            Operand::JsonRef(ValueRef::Null) => None,
+            Operand::JsonRef(value) => Some(Cow::Owned(value.to_string())),
+            Operand::Number(number) => Some(Cow::Owned(number.to_string())),
+            Operand::Level(level) => Some(Cow::Owned(format!("{:?}", level).to_lowercase())),
+            Operand::Time(time) => Some(Cow::Owned(time.to_rfc3339())),
+        }
+    }
+    
+    /// Numbers, and strings holding a number (e.g. a status code captured by a regex)
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Operand::Number(number) => Some(*number),
+            Operand::Json(Value::Number(number)) => number.as_f64(),
+            Operand::Json(Value::String(text)) => text.trim().parse().ok(),
+            Operand::JsonRef(ValueRef::Number(number)) => number.as_f64(),
+            Operand::JsonRef(ValueRef::String(text)) => text.trim().parse().ok(),
+            Operand::Text(text) => text.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl Filter {
+    /// Compile a filter expression
+    pub fn compile(expression: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(expression).map_err(|(message, position)| syntax_error(expression, message, position))?;
+        let mut parser = ExprParser { tokens, position: 0, end: expression.len() };
+        
+        let expr = parser.parse_or().map_err(|(message, position)| syntax_error(expression, message, position))?;
+        if let Some((_, position)) = parser.tokens.get(parser.position) {
+            return Err(syntax_error(expression, "unexpected trailing input".to_string(), *position));
+        }
+        Ok(Self { expr })
+    }
+    
+    /// Filter that accepts every event
+    pub fn all() -> Self {
+        Self { expr: Expr::Always }
+    }
+    
+    /// Events with one of the given levels
+    pub fn levels(levels: Vec<LogLevel>) -> Self {
+        let values = levels.into_iter().map(Literal::Level).collect();
+        Self { expr: Expr::In(Path::Level, values) }
+    }
+    
+    /// Events inside a time window; events without a timestamp are kept
+    pub fn time_range(since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> Self {
+        Self { expr: Expr::TimeRange(since, until) }
+    }
+    
+    /// Events whose message or raw line matches `pattern`
+    pub fn grep(pattern: Regex) -> Self {
+        Self { expr: Expr::Grep(pattern) }
+    }
+    
+    /// Events whose field at path `key` contains `value` as text
+    pub fn field_contains(key: &str, value: &str) -> Self {
+        Self { expr: Expr::FieldContains(FieldPath::parse_or_key(key), value.to_string()) }
+    }
+    
+    /// Combine with another filter; both must match
+    pub fn and(self, other: Filter) -> Self {
+        let expr = match (self.expr, other.expr) {
+            (Expr::Always, expr) | (expr, Expr::Always) => expr,
+            (Expr::And(mut left), Expr::And(right)) => {
+                left.extend(right);
+                Expr::And(left)
+            }
+            (Expr::And(mut left), right) => {
+                left.push(right);
+                Expr::And(left)
+            }
+            (left, right) => Expr::And(vec![left, right]),
+        };
+        Self { expr }
+    }
+    
+    /// True if the event passes the filter
+    pub fn matches(&self, event: &CanonicalEvent) -> bool {
+        self.expr.matches(event)
+    }
+    
+    /// True if an event borrowing from its input passes the filter
+    pub fn matches_ref(&self, event: &CanonicalEventRef) -> bool {
+        self.expr.matches(event)
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Read access to the parts of an event a filter looks at, owned or borrowed
+trait FilterEvent {
+    fn level(&self) -> Option<LogLevel>;
+    fn timestamp(&self) -> Option<DateTime<Utc>>;
+    fn message(&self) -> &str;
+    fn raw(&self) -> &str;
+    fn format_type(&self) -> FormatType;
+    fn source_text(&self, name: &str) -> Option<&str>;
+    fn source_offset(&self) -> Option<u64>;
+    /// Value at a path of field keys and indices
+    fn field(&self, path: &FieldPath) -> Option<Operand<'_>>;
+}
+
+impl FilterEvent for CanonicalEvent {
+    fn level(&self) -> Option<LogLevel> {
+        self.level
+    }
+    
+    fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp
+    }
+    
+    fn message(&self) -> &str {
+        &self.message
+    }
+    
+    fn raw(&self) -> &str {
+        &self.raw
+    }
+    
+    fn format_type(&self) -> FormatType {
+        self.format_type.clone()
+    }
+    
+    fn source_text(&self, name: &str) -> Option<&str> {
+        match name {
+            "file" => self.source.file.as_deref(),
+            "stream" => self.source.stream.as_deref(),
+            _ => self.source.host.as_deref(),
+        }
+    }
+    
+    fn source_offset(&self) -> Option<u64> {
+        self.source.offset
+    }
+    
+    fn field(&self, path: &FieldPath) -> Option<Operand<'_>> {
+        path.resolve(&self.fields).map(Operand::Json)
+    }
+}
+
+impl<'e> FilterEvent for CanonicalEventRef<'e> {
+    fn level(&self) -> Option<LogLevel> {
+        self.level
+    }
+    
+    fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp
+    }
+    
+    fn message(&self) -> &str {
+        &self.message
+    }
+    
+    fn raw(&self) -> &str {
+        &self.raw
+    }
+    
+    fn format_type(&self) -> FormatType {
+        self.format_type.clone()
+    }
+    
+    fn source_text(&self, name: &str) -> Option<&str> {
+        match name {
+            "file" => self.source.file.as_deref(),
+            "stream" => self.source.stream.as_deref(),
+            _ => self.source.host.as_deref(),
+        }
+    }
+    
+    fn source_offset(&self) -> Option<u64> {
+        self.source.offset
+    }
+    
+    fn field(&self, path: &FieldPath) -> Option<Operand<'_>> {
+        path.resolve_ref(&self.fields).map(Operand::JsonRef)
+    }
+}
+
+impl Expr {
+    fn matches(&self, event: &impl FilterEvent) -> bool {
+        match self {
+            Expr::Always => true,
+            Expr::And(exprs) => exprs.iter().all(|expr| expr.matches(event)),
+            Expr::Or(exprs) => exprs.iter().any(|expr| expr.matches(event)),
+            Expr::Not(expr) => !expr.matches(event),
+            Expr::Exists(path) => path.resolve(event).is_some(),
+            Expr::Compare(path, op, literal) => path.resolve(event)
+                .is_some_and(|operand| compare(&operand, *op, literal)),
+            Expr::Regex(path, regex) => path.resolve(event)
+                .is_some_and(|operand| operand.as_text().is_some_and(|text| regex.is_match(&text))),
+            Expr::In(path, literals) => path.resolve(event)
+                .is_some_and(|operand| literals.iter().any(|literal| compare(&operand, CompareOp::Eq, literal))),
+            Expr::Grep(regex) => regex.is_match(event.message()) || regex.is_match(event.raw()),
+            Expr::FieldContains(key, expected) => match event.field(key) {
+                Some(Operand::Json(Value::Null) | Operand::JsonRef(ValueRef::Null)) => "null".contains(expected.as_str()),
+                Some(operand) => operand.as_text().is_some_and(|text| text.contains(expected.as_str())),
+                None => false,
+            },
+            Expr::TimeRange(since, until) => match event.timestamp() {
+                Some(ts) => since.is_none_or(|start| ts >= start) && until.is_none_or(|end| ts <= end),
+                None => true,
+            },
+        }
+    }
+}
+
+impl Path {
+    /// Parse a dotted path such as `fields.http.headers[0]` or `source.file`
+    fn parse(text: &str) -> Result<Self, String> {
+        let (head, rest) = match text.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (text, None),
+        };
+        
+        match (head, rest) {
+            ("level", None) => Ok(Path::Level),
+            ("message", None) => Ok(Path::Message),
+            ("raw", None) => Ok(Path::Raw),
+            ("timestamp", None) => Ok(Path::Timestamp),
+            ("format", None) => Ok(Path::Format),
+            ("source", Some(name @ ("file" | "stream" | "host" | "offset"))) => Ok(Path::Source(name.to_string())),
+            ("source", _) => Err(format!("unknown source path '{}' (expected source.file, .stream, .host or .offset)", text)),
+            ("fields", Some(rest)) => Ok(Path::Field(FieldPath::parse(rest)?)),
+            ("fields", None) => Err("expected a field name after 'fields.'".to_string()),
+            _ => Ok(Path::Field(FieldPath::parse(text)?)),
+        }
+    }
+    
+    fn resolve<'a>(&self, event: &'a impl FilterEvent) -> Option<Operand<'a>> {
+        match self {
+            Path::Level => event.level().map(Operand::Level),
+            Path::Message => Some(Operand::Text(Cow::Borrowed(event.message()))),
+            Path::Raw => Some(Operand::Text(Cow::Borrowed(event.raw()))),
+            Path::Timestamp => event.timestamp().map(Operand::Time),
+            Path::Format => Some(Operand::Text(Cow::Owned(event.format_type().to_string().to_lowercase()))),
+            Path::Source(name) if name == "offset" => event.source_offset().map(|offset| Operand::Number(offset as f64)),
+            Path::Source(name) => event.source_text(name).map(|text| Operand::Text(Cow::Borrowed(text))),
+            Path::Field(path) => event.field(path),
+        }
+    }
+}
+
+fn compare(actual: &Operand, op: CompareOp, expected: &Literal) -> bool {
+    let ordering = match (actual, expected) {
+        (Operand::Level(actual), Literal::Level(expected)) => Some(actual.cmp(expected)),
+        (Operand::Time(actual), Literal::Time(expected)) => Some(actual.cmp(expected)),
+        (_, Literal::Number(expected)) => actual.as_number().and_then(|number| number.partial_cmp(expected)),
+        (_, Literal::Text(expected)) => actual.as_text().map(|text| text.as_ref().cmp(expected.as_str())),
+        (Operand::Json(Value::Bool(actual)), Literal::Bool(expected)) => Some(actual.cmp(expected)),
+        (Operand::JsonRef(ValueRef::Bool(actual)), Literal::Bool(expected)) => Some(actual.cmp(expected)),
+        (Operand::Json(Value::Null), Literal::Null) => Some(Ordering::Equal),
+        (Operand::JsonRef(ValueRef::Null), Literal::Null) => Some(Ordering::Equal),
+        (_, Literal::Cidr(network)) => {
+            // Only equality makes sense for a range; a non-IP value falls back to text
+            let inside = match actual.as_text() {
+                Some(text) => match text.parse::<IpAddr>() {
+                    Ok(ip) => network.contains(&ip),
+                    Err(_) => text == network.to_string(),
+                },
+                None => return false,
+            };
+            return match op {
+                CompareOp::Eq => inside,
+                CompareOp::Ne => !inside,
+                _ => false,
+            };
+        }
+        _ => None,
+    };
+    ordering.is_some_and(|ordering| op.accepts(ordering))
+}
+
+fn syntax_error(expression: &str, message: String, position: usize) -> ParseError {
+    ParseError::ConfigurationError {
+        parameter: "filter".to_string(),
+        error_message: format!("{} at position {} in '{}'", message, position + 1, expression),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Compare(CompareOp),
+    Match,
+    NotMatch,
+    And,
+    Or,
+    Not,
+    In,
+    Exists,
+    /// Path, number or unquoted value
+    Word(String),
+    /// Quoted string
+    Str(String),
+}
+
+type Spanned = (Token, usize);
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"()[],\"'=!<>~&|".contains(c)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, (String, usize)> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        
+        let next_is = |expected: char| input[start + c.len_utf8()..].starts_with(expected);
+        let (token, len) = match c {
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            ',' => (Token::Comma, 1),
+            '~' => (Token::Match, 1),
+            '=' if next_is('=') => (Token::Compare(CompareOp::Eq), 2),
+            '=' => (Token::Compare(CompareOp::Eq), 1),
+            '!' if next_is('=') => (Token::Compare(CompareOp::Ne), 2),
+            '!' if next_is('~') => (Token::NotMatch, 2),
+            '!' => (Token::Not, 1),
+            '>' if next_is('=') => (Token::Compare(CompareOp::Ge), 2),
+            '>' => (Token::Compare(CompareOp::Gt), 1),
+            '<' if next_is('=') => (Token::Compare(CompareOp::Le), 2),
+            '<' => (Token::Compare(CompareOp::Lt), 1),
+            '&' if next_is('&') => (Token::And, 2),
+            '|' if next_is('|') => (Token::Or, 2),
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // Only quotes and backslashes are escaped; other sequences
+                        // (regex escapes like `\d`) are kept as written
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped @ ('"' | '\'' | '\\'))) => value.push(escaped),
+                            Some((_, other)) => {
+                                value.push('\\');
+                                value.push(other);
+                            }
+                            None => return Err(("unterminated string".to_string(), start)),
+                        },
+                        Some((_, quote)) if quote == c => break,
+                        Some((_, other)) => value.push(other),
+                        None => return Err(("unterminated string".to_string(), start)),
+                    }
+                }
+                tokens.push((Token::Str(value), start));
+                continue;
+            }
+            c if is_word_char(c) => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if is_word_char(c) {
+                        end = i + c.len_utf8();
+                        chars.next();
+                        continue;
+                    }
+                    // `[n]` directly after a name is an index, not a list
+                    let index_len = match input[i..].strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+                        Some((index, _)) if c == '[' && !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => index.len() + 2,
+                        _ => break,
+                    };
+                    end = i + index_len;
+                    while chars.peek().is_some_and(|&(j, _)| j < end) {
+                        chars.next();
+                    }
+                }
+                
+                let word = &input[start..end];
+                let token = match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "in" => Token::In,
+                    "exists" => Token::Exists,
+                    _ => Token::Word(word.to_string()),
+                };
+                tokens.push((token, start));
+                continue;
+            }
+            other => return Err((format!("unexpected character '{}'", other), start)),
+        };
+        
+        tokens.push((token, start));
+        for _ in 0..len {
+            chars.next();
+        }
+    }
+    
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the token stream; errors carry a byte position
+struct ExprParser {
+    tokens: Vec<Spanned>,
+    position: usize,
+    end: usize,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+    
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map(|(_, offset)| *offset).unwrap_or(self.end)
+    }
+    
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+    
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+    
+    fn parse_or(&mut self) -> Result<Expr, (String, usize)> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.eat(&Token::Or) {
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::Or(exprs) })
+    }
+    
+    fn parse_and(&mut self) -> Result<Expr, (String, usize)> {
+        let mut exprs = vec![self.parse_not()?];
+        while self.eat(&Token::And) {
+            exprs.push(self.parse_not()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::And(exprs) })
+    }
+    
+    fn parse_not(&mut self) -> Result<Expr, (String, usize)> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+    
+    fn parse_primary(&mut self) -> Result<Expr, (String, usize)> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                if !self.eat(&Token::RParen) {
+                    return Err(("expected ')'".to_string(), self.offset()));
+                }
+                Ok(expr)
+            }
+            Some(Token::Exists) => Ok(Expr::Exists(self.parse_path()?)),
+            Some(Token::Word(word)) => {
+                let path = Path::parse(&word).map_err(|message| (message, offset))?;
+                self.parse_condition(path)
+            }
+            Some(_) => Err(("expected a field, '(', 'not' or 'exists'".to_string(), offset)),
+            None => Err(("unexpected end of expression".to_string(), offset)),
+        }
+    }
+    
+    fn parse_path(&mut self) -> Result<Path, (String, usize)> {
+        let offset = self.offset();
+        match self.next() {
+            Some(Token::Word(word)) => Path::parse(&word).map_err(|message| (message, offset)),
+            _ => Err(("expected a field path".to_string(), offset)),
+        }
+    }
+    
+    /// Operator and value after a path; a bare path is an existence check
+    fn parse_condition(&mut self, path: Path) -> Result<Expr, (String, usize)> {
+        match self.peek() {
+            Some(Token::Compare(op)) => {
+                let op = *op;
+                self.position += 1;
+                let literal = self.parse_literal(&path)?;
+                Ok(Expr::Compare(path, op, literal))
+            }
+            Some(Token::Match) | Some(Token::NotMatch) => {
+                let negated = self.next() == Some(Token::NotMatch);
+                let offset = self.offset();
+                let pattern = match self.next() {
+                    Some(Token::Str(pattern)) | Some(Token::Word(pattern)) => pattern,
+                    _ => return Err(("expected a regex after '~'".to_string(), offset)),
+                };
+                let regex = Regex::new(&pattern).map_err(|e| (format!("invalid regex '{}': {}", pattern, e), offset))?;
+                let expr = Expr::Regex(path, regex);
+                Ok(if negated { Expr::Not(Box::new(expr)) } else { expr })
+            }
+            Some(Token::In) => {
+                self.position += 1;
+                Ok(Expr::In(path.clone(), self.parse_list(&path)?))
+            }
+            // `host not in [..]`
+            Some(Token::Not) if self.tokens.get(self.position + 1).map(|(token, _)| token) == Some(&Token::In) => {
+                self.position += 2;
+                Ok(Expr::Not(Box::new(Expr::In(path.clone(), self.parse_list(&path)?))))
+            }
+            _ => Ok(Expr::Exists(path)),
+        }
+    }
+    
+    fn parse_list(&mut self, path: &Path) -> Result<Vec<Literal>, (String, usize)> {
+        if !self.eat(&Token::LBracket) {
+            return Ok(vec![self.parse_literal(path)?]);
+        }
+        
+        let mut literals = Vec::new();
+        loop {
+            literals.push(self.parse_literal(path)?);
+            if self.eat(&Token::RBracket) {
+                return Ok(literals);
+            }
+            if !self.eat(&Token::Comma) {
+                return Err(("expected ',' or ']'".to_string(), self.offset()));
+            }
+        }
+    }
+    
+    /// Value compared against `path`: levels and times are checked here, once
+    fn parse_literal(&mut self, path: &Path) -> Result<Literal, (String, usize)> {
+        let offset = self.offset();
+        let (text, quoted) = match self.next() {
+            Some(Token::Str(text)) => (text, true),
+            Some(Token::Word(text)) => (text, false),
+            _ => return Err(("expected a value".to_string(), offset)),
+        };
+        
+        match path {
+            Path::Level => LogLevel::from_str(&text)
+                .map(Literal::Level)
+                .ok_or_else(|| (format!("unknown level '{}'", text), offset)),
+            Path::Timestamp => parse_time(&text)
+                .map(Literal::Time)
+                .ok_or_else(|| (format!("invalid time '{}'", text), offset)),
+            _ => Ok(value_literal(text, quoted)),
+        }
+    }
+}
+
+fn value_literal(text: String, quoted: bool) -> Literal {
+    if text.contains('/') {
+        if let Ok(network) = text.parse::<IpNet>() {
+            return Literal::Cidr(network);
+        }
+    }
+    if quoted {
+        return Literal::Text(text);
+    }
+    match text.as_str() {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        "null" => Literal::Null,
+        _ => match text.parse::<f64>() {
+            Ok(number) => Literal::Number(number),
+            Err(_) => Literal::Text(text),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FormatType;
+    use serde_json::json;
+    
+    fn event(level: LogLevel, message: &str, fields: Value) -> CanonicalEvent {
+        let mut event = CanonicalEvent::new(message.to_string(), message.to_string(), FormatType::Json);
+        event.set_level(level);
+        if let Value::Object(map) = fields {
+            for (key, value) in map {
+                event.add_field(key, value);
+            }
+        }
+        event
+    }
+    
+    #[test]
+    fn test_boolean_expression_with_levels_numbers_and_lists() {
+        let filter = Filter::compile(
+            r#"level>=warn and (fields.status >= 500 or message ~ "timeout") and not host in ["lb1","lb2"]"#
+        ).unwrap();
+        
+        assert!(filter.matches(&event(LogLevel::Error, "upstream failed", json!({"status": 502, "host": "web1"}))));
+        // Numeric strings compare as numbers
+        assert!(filter.matches(&event(LogLevel::Warn, "slow", json!({"status": "503"}))));
+        assert!(filter.matches(&event(LogLevel::Warn, "read timeout", json!({"status": 200}))));
+        assert!(!filter.matches(&event(LogLevel::Info, "read timeout", json!({"status": 500}))));
+        assert!(!filter.matches(&event(LogLevel::Error, "boom", json!({"status": 500, "host": "lb2"}))));
+        assert!(!filter.matches(&event(LogLevel::Error, "boom", json!({"status": 404}))));
+    }
+    
+    #[test]
+    fn test_string_escapes_keep_regex_escapes() {
+        let filter = Filter::compile(r#"message ~ "status \d{3}\." and host == 'o\'brien'"#).unwrap();
+        assert!(filter.matches(&event(LogLevel::Info, "status 503.", json!({"host": "o'brien"}))));
+        assert!(!filter.matches(&event(LogLevel::Info, "status ddd.", json!({"host": "o'brien"}))));
+        assert!(!filter.matches(&event(LogLevel::Info, "status 503!", json!({"host": "o'brien"}))));
+        
+        let backslash = Filter::compile(r#"path == "C:\\logs""#).unwrap();
+        assert!(backslash.matches(&event(LogLevel::Info, "", json!({"path": r"C:\logs"}))));
+    }
+    
+    #[test]
+    fn test_nested_paths_existence_and_cidr() {
+        let record = event(LogLevel::Info, "request", json!({
+            "http": {"request": {"headers": ["a", "b"]}, "status": 201},
+            "client_ip": "10.1.2.3",
+        }));
+        
+        assert!(Filter::compile("fields.http.status == 201").unwrap().matches(&record));
+        assert!(Filter::compile("fields.http.request.headers[1] == \"b\"").unwrap().matches(&record));
+        assert!(Filter::compile("exists fields.http.request").unwrap().matches(&record));
+        assert!(Filter::compile("not user_id").unwrap().matches(&record));
+        assert!(Filter::compile("client_ip in [10.0.0.0/8, \"192.168.0.0/16\"]").unwrap().matches(&record));
+        assert!(!Filter::compile("client_ip in 192.168.0.0/16").unwrap().matches(&record));
+        // Comparisons against missing fields are false either way
+        assert!(!Filter::compile("fields.missing != 1").unwrap().matches(&record));
+    }
+    
+    #[test]
+    fn test_shorthand_filters_combine() {
+        let filter = Filter::levels(vec![LogLevel::Error])
+            .and(Filter::grep(Regex::new("(?i)disk").unwrap()))
+            .and(Filter::field_contains("mount", "/var"));
+        
+        assert!(filter.matches(&event(LogLevel::Error, "Disk full", json!({"mount": "/var/log"}))));
+        assert!(!filter.matches(&event(LogLevel::Warn, "Disk full", json!({"mount": "/var/log"}))));
+        assert!(Filter::all().matches(&event(LogLevel::Trace, "", json!({}))));
+    }
+    
+    #[test]
+    fn test_borrowed_events_match_like_owned() {
+        use crate::parsers::{JsonParser, LogParser};
+        
+        let line = r#"{"level":"error","msg":"disk full","http":{"status":507,"ok":false},"mount":"/var/log","user":null}"#;
+        let parser = JsonParser::new();
+        let borrowed = parser.parse_ref(line).event;
+        let owned = parser.parse(line).event;
+        
+        for expression in [
+            "level>=warn and fields.http.status >= 500",
+            "fields.http.ok == false",
+            "user == null",
+            "message ~ \"disk\" and not exists fields.http.missing",
+            "mount in [\"/var/log\", \"/tmp\"]",
+            "fields.http.status < 500",
+        ] {
+            let filter = Filter::compile(expression).unwrap();
+            assert_eq!(filter.matches_ref(&borrowed), filter.matches(&owned), "{}", expression);
+        }
+        assert!(Filter::field_contains("mount", "/var").matches_ref(&borrowed));
+    }
+    
+    #[test]
+    fn test_paths_match_json_flattened_to_any_depth() {
+        use crate::field_path::FlattenConfig;
+        use crate::parsers::{JsonParser, LogParser};
+        
+        let line = r#"{"msg":"GET /","http":{"request":{"method":"GET"},"status":503},"items":[{"id":"a1"}]}"#;
+        let filter = Filter::compile(r#"fields.http.status >= 500 and items[0].id == "a1""#).unwrap();
+        for flatten in [
+            FlattenConfig::default(),
+            FlattenConfig { max_depth: Some(1), ..FlattenConfig::default() },
+            FlattenConfig { index_arrays: true, ..FlattenConfig::default() },
+        ] {
+            let parser = JsonParser::new().with_flatten(flatten.clone());
+            let owned = parser.parse(line).event;
+            assert!(filter.matches(&owned), "{:?}", flatten);
+            assert!(filter.matches_ref(&parser.parse_ref(line).event), "{:?}", flatten);
+            assert!(Filter::field_contains("http.request.method", "GE").matches(&owned), "{:?}", flatten);
+        }
+    }
+    
+    #[test]
+    fn test_syntax_errors_report_position() {
+        for expression in ["level >= loud", "(status > 1", "status >", "message ~ \"(\"", "a == 1 b"] {
+            match Filter::compile(expression) {
+                Err(ParseError::ConfigurationError { parameter, error_message }) => {
+                    assert_eq!(parameter, "filter");
+                    assert!(error_message.contains("at position"), "{}", error_message);
+                }
+                other => panic!("Expected syntax error for '{}', got {:?}", expression, other),
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::models::FormatType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Settings for locking a source onto the format its first lines share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatLockConfig {
+    /// Lock sources at all; when disabled every line is classified
+    pub enabled: bool,
+    /// Lines classified before deciding whether to lock
+    pub sample_lines: usize,
+    /// Minimum dominant share times mean confidence required to lock
+    pub min_confidence: f64,
+}
+
+impl Default for FormatLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_lines: 20,
+            min_confidence: 0.7,
+        }
+    }
+}
+
+/// Where a source stands in the lock decision
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockState {
+    /// Still classifying the first lines
+    Sampling,
+    /// Parsed with a single format
+    Locked(FormatType),
+    /// No format dominated the sample; every line is classified
+    Mixed,
+}
+
+/// Lock decision and counters for one source
+#[derive(Debug, Clone)]
+pub struct SourceLock {
+    pub state: LockState,
+    /// Lock score of the dominant format when the decision was made
+    pub confidence: f64,
+    /// Lines seen when the source was last locked
+    pub locked_at: Option<usize>,
+    /// Lines seen from the source
+    pub lines: usize,
+    /// Locked lines the format failed to parse
+    pub misses: usize,
+    /// Times a lock was dropped after repeated misses
+    pub unlocks: usize,
+    consecutive_misses: usize,
+    samples: HashMap<FormatType, (usize, f64)>,
+    sampled: usize,
+}
+
+impl SourceLock {
+    fn new() -> Self {
+        Self {
+            state: LockState::Sampling,
+            confidence: 0.0,
+            locked_at: None,
+            lines: 0,
+            misses: 0,
+            unlocks: 0,
+            consecutive_misses: 0,
+            samples: HashMap::new(),
+            sampled: 0,
+        }
+    }
+    
+    /// Share of each format among the sampled lines, most common first
+    pub fn sample_shares(&self) -> Vec<(FormatType, f64)> {
+        let mut shares: Vec<_> = self.samples.iter()
+            .map(|(format, (count, _))| (format.clone(), *count as f64 / self.sampled.max(1) as f64))
+            .collect();
+        shares.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+        shares
+    }
+    
+    /// Dominant sampled format with its lock score (share times mean confidence)
+    fn dominant(&self) -> Option<(FormatType, f64)> {
+        self.samples.iter()
+            .map(|(format, (count, confidence_sum))| {
+                let share = *count as f64 / self.sampled as f64;
+                (format.clone(), share * (confidence_sum / *count as f64))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Per-source format locks: sample the first lines of each source, lock onto
+/// the dominant format and drop the lock when it keeps missing
+#[derive(Debug, Clone, Default)]
+pub struct FormatLocks {
+    config: FormatLockConfig,
+    sources: HashMap<String, SourceLock>,
+}
+
+impl FormatLocks {
+    pub fn new(config: FormatLockConfig) -> Self {
+        Self {
+            config,
+            sources: HashMap::new(),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+    
+    /// Format a source is locked onto, if any
+    pub fn locked_format(&self, source: &str) -> Option<FormatType> {
+        match &self.sources.get(source)?.state {
+            LockState::Locked(format) => Some(format.clone()),
+            _ => None,
+        }
+    }
+    
+    /// Record a line classified by full detection
+    pub fn record_detection(&mut self, source: &str, format: FormatType, confidence: f64) {
+        let sample_lines = self.config.sample_lines.max(1);
+        let min_confidence = self.config.min_confidence;
+        let lock = self.source_mut(source);
+        lock.lines += 1;
+        if lock.state != LockState::Sampling {
+            return;
+        }
+        
+        let entry = lock.samples.entry(format).or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += confidence;
+        lock.sampled += 1;
+        if lock.sampled < sample_lines {
+            return;
+        }
+        
+        if let Some((format, score)) = lock.dominant() {
+            lock.confidence = score;
+            if score >= min_confidence && format != FormatType::PlainText {
+                lock.state = LockState::Locked(format);
+                lock.locked_at = Some(lock.lines);
+            } else {
+                lock.state = LockState::Mixed;
+            }
+        }
+    }
+    
+    /// Record a line parsed with the locked format; a miss is expected to be
+    /// classified and recorded with `record_detection` next. After
+    /// `sample_lines` consecutive misses the lock is dropped and the source
+    /// is sampled again
+    pub fn record_locked_parse(&mut self, source: &str, success: bool) {
+        let sample_lines = self.config.sample_lines.max(1);
+        let lock = self.source_mut(source);
+        if success {
+            lock.lines += 1;
+            lock.consecutive_misses = 0;
+            return;
+        }
+        
+        lock.misses += 1;
+        lock.consecutive_misses += 1;
+        if lock.consecutive_misses >= sample_lines {
+            lock.state = LockState::Sampling;
+            lock.unlocks += 1;
+            lock.consecutive_misses = 0;
+            lock.samples.clear();
+            lock.sampled = 0;
+        }
+    }
+    
+    /// Lock decisions by source name
+    pub fn sources(&self) -> Vec<(&str, &SourceLock)> {
+        let mut sources: Vec<_> = self.sources.iter()
+            .map(|(source, lock)| (source.as_str(), lock))
+            .collect();
+        sources.sort_by(|a, b| a.0.cmp(b.0));
+        sources
+    }
+    
+    pub fn clear(&mut self) {
+        self.sources.clear();
+    }
+    
+    /// Fold in locks learned from a later part of the same sources: counters
+    /// add up and a source takes the first lock decision made for it
+    pub fn merge(&mut self, later: &FormatLocks) {
+        for (source, lock) in &later.sources {
+            let Some(earlier) = self.sources.get_mut(source) else {
+                self.sources.insert(source.clone(), lock.clone());
+                continue;
+            };
+            if earlier.state == LockState::Sampling && lock.state != LockState::Sampling {
+                earlier.state = lock.state.clone();
+                earlier.confidence = lock.confidence;
+                earlier.locked_at = lock.locked_at.map(|lines| lines + earlier.lines);
+                earlier.samples = lock.samples.clone();
+                earlier.sampled = lock.sampled;
+            }
+            earlier.lines += lock.lines;
+            earlier.misses += lock.misses;
+            earlier.unlocks += lock.unlocks;
+        }
+    }
+    
+    fn source_mut(&mut self, source: &str) -> &mut SourceLock {
+        if !self.sources.contains_key(source) {
+            self.sources.insert(source.to_string(), SourceLock::new());
+        }
+        self.sources.get_mut(source).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn locks(sample_lines: usize) -> FormatLocks {
+        FormatLocks::new(FormatLockConfig { sample_lines, ..FormatLockConfig::default() })
+    }
+    
+    #[test]
+    fn test_locks_after_sample_and_unlocks_on_misses() {
+        let mut locks = locks(3);
+        for _ in 0..2 {
+            locks.record_detection("app.log", FormatType::Json, 0.95);
+        }
+        assert_eq!(locks.locked_format("app.log"), None);
+        locks.record_detection("app.log", FormatType::Json, 0.95);
+        assert_eq!(locks.locked_format("app.log"), Some(FormatType::Json));
+        
+        // Isolated misses keep the lock
+        locks.record_locked_parse("app.log", false);
+        locks.record_locked_parse("app.log", true);
+        locks.record_locked_parse("app.log", false);
+        locks.record_locked_parse("app.log", false);
+        assert_eq!(locks.locked_format("app.log"), Some(FormatType::Json));
+        
+        locks.record_locked_parse("app.log", false);
+        assert_eq!(locks.locked_format("app.log"), None);
+        
+        let (_, lock) = locks.sources()[0];
+        assert_eq!(lock.state, LockState::Sampling);
+        assert_eq!(lock.locked_at, Some(3));
+        assert_eq!(lock.lines, 4);
+        assert_eq!(lock.misses, 4);
+        assert_eq!(lock.unlocks, 1);
+    }
+    
+    #[test]
+    fn test_mixed_sources_stay_unlocked() {
+        let mut locks = locks(4);
+        locks.record_detection("mixed.log", FormatType::Json, 0.95);
+        locks.record_detection("mixed.log", FormatType::Logfmt, 0.9);
+        locks.record_detection("mixed.log", FormatType::Json, 0.95);
+        locks.record_detection("mixed.log", FormatType::Logfmt, 0.9);
+        locks.record_detection("plain.log", FormatType::PlainText, 0.1);
+        
+        assert_eq!(locks.locked_format("mixed.log"), None);
+        let (_, lock) = locks.sources()[0];
+        assert_eq!(lock.state, LockState::Mixed);
+        assert_eq!(lock.sample_shares()[0], (FormatType::Json, 0.5));
+        assert_eq!(locks.sources()[1].1.state, LockState::Sampling);
+    }
+}
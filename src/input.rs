@@ -0,0 +1,670 @@
+use crate::event_ref::SourceRef;
+use crate::models::SourceMetadata;
+use crate::multiline::SourceLine;
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Bytes needed to recognise a tar header ("ustar" magic at offset 257)
+const TAR_PROBE_LEN: usize = 512;
+
+/// Buffer size for readers handed to the parsers
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Compression formats recognised by their magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    /// Detect the compression format from the first bytes of a stream
+    pub fn detect(prefix: &[u8]) -> Self {
+        if prefix.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if prefix.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if prefix.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if prefix.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Path argument that selects standard input
+pub const STDIN_PATH: &str = "-";
+
+/// Default source identifier for standard input
+pub const STDIN_SOURCE: &str = "stdin";
+
+/// Where the bytes of an input come from
+#[derive(Debug, Clone, PartialEq)]
+enum Location {
+    File(PathBuf),
+    /// A tar member; `data` is where discovery found its content in the decoded archive
+    ArchiveMember { archive: PathBuf, member: PathBuf, data: Option<(Range<u64>, ArchiveStream)> },
+    Stdin,
+}
+
+/// Decoded stream of a tar archive, shared by the inputs discovered in it so
+/// that reading its members in archive order decodes the archive once
+#[derive(Clone, Default)]
+struct ArchiveStream(Arc<Mutex<ArchiveCursor>>);
+
+#[derive(Default)]
+struct ArchiveCursor {
+    reader: Option<Box<dyn Read + Send>>,
+    /// Bytes of the decoded archive consumed so far
+    position: u64,
+}
+
+impl ArchiveStream {
+    /// Reader for the bytes of `data`, positioned on first read
+    fn member(&self, archive: &Path, data: Range<u64>) -> MemberReader {
+        MemberReader {
+            stream: self.clone(),
+            archive: archive.to_path_buf(),
+            position: data.start,
+            end: data.end,
+        }
+    }
+}
+
+impl std::fmt::Debug for ArchiveStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ArchiveStream")
+    }
+}
+
+/// The shared stream only caches decoding; it is not part of an input's identity
+impl PartialEq for ArchiveStream {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// One member's content, read from its archive's shared stream
+struct MemberReader {
+    stream: ArchiveStream,
+    archive: PathBuf,
+    position: u64,
+    end: u64,
+}
+
+impl Read for MemberReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.end || buf.is_empty() {
+            return Ok(0);
+        }
+        let mut cursor = self.stream.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        
+        // Another member was read past this one's position: decode again from the start
+        if cursor.reader.is_none() || cursor.position > self.position {
+            cursor.reader = Some(open_decoded(&self.archive)?.0);
+            cursor.position = 0;
+        }
+        let ArchiveCursor { reader, position } = &mut *cursor;
+        let reader = reader.as_mut().expect("archive stream was just opened");
+        if *position < self.position {
+            let skipped = io::copy(&mut reader.take(self.position - *position), &mut io::sink())?;
+            *position += skipped;
+            if *position < self.position {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "archive ended inside a member"));
+            }
+        }
+        
+        let len = buf.len().min((self.end - self.position) as usize);
+        let read = reader.read(&mut buf[..len])?;
+        if read == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "archive ended inside a member"));
+        }
+        *position += read as u64;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+/// A log stream to read: a plain or compressed file, a member of a tar archive, or stdin
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogInput {
+    location: Location,
+    source: String,
+}
+
+impl LogInput {
+    /// Input reading a whole file
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let source = path.to_string_lossy().to_string();
+        Self { location: Location::File(path), source }
+    }
+    
+    /// Input reading one member of a tar archive
+    pub fn archive_member(archive: impl Into<PathBuf>, member: impl Into<PathBuf>) -> Self {
+        Self::member_at(archive.into(), member.into(), None)
+    }
+    
+    /// Archive member whose content is `data` of the archive's decoded `stream`
+    fn member_at(archive: PathBuf, member: PathBuf, data: Option<(Range<u64>, ArchiveStream)>) -> Self {
+        let source = format!("{}!/{}", archive.to_string_lossy(), member.to_string_lossy());
+        Self { location: Location::ArchiveMember { archive, member, data }, source }
+    }
+    
+    /// Input reading standard input, identified by `source_name` if given
+    pub fn stdin(source_name: Option<&str>) -> Self {
+        let source = source_name.unwrap_or(STDIN_SOURCE).to_string();
+        Self { location: Location::Stdin, source }
+    }
+    
+    /// Source identifier used for profile matching, the format cache and output
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+    
+    /// Path of the file on disk (the archive for archive members), if any
+    pub fn path(&self) -> Option<&Path> {
+        match &self.location {
+            Location::File(path) => Some(path),
+            Location::ArchiveMember { archive, .. } => Some(archive),
+            Location::Stdin => None,
+        }
+    }
+    
+    /// Path of the member inside the archive, if any
+    pub fn member(&self) -> Option<&Path> {
+        match &self.location {
+            Location::ArchiveMember { member, .. } => Some(member),
+            _ => None,
+        }
+    }
+    
+    /// Last modification time of the file (the archive for archive members)
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.path()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok())
+    }
+    
+    /// True when reading standard input
+    pub fn is_stdin(&self) -> bool {
+        matches!(self.location, Location::Stdin)
+    }
+    
+    /// Source metadata attached to events read from this input
+    pub fn source_metadata(&self) -> SourceMetadata {
+        self.source_ref().into_owned()
+    }
+    
+    /// Source metadata borrowing its names from the input
+    pub fn source_ref(&self) -> SourceRef<'_> {
+        let mut metadata = SourceRef::default();
+        match &self.location {
+            Location::Stdin => {
+                metadata.stream = Some(Cow::Borrowed(STDIN_SOURCE));
+                if self.source != STDIN_SOURCE {
+                    metadata.file = Some(Cow::Borrowed(&self.source));
+                }
+            }
+            _ => metadata.file = Some(Cow::Borrowed(&self.source)),
+        }
+        metadata
+    }
+    
+    /// Open the input, decompressing transparently
+    pub fn open(&self) -> io::Result<Box<dyn BufRead + Send>> {
+        let reader = match &self.location {
+            Location::File(path) => decompress(File::open(path)?)?,
+            Location::ArchiveMember { archive, data: Some((data, stream)), .. } => {
+                decompress(stream.member(archive, data.clone()))
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", self.source, e)))?
+            }
+            Location::ArchiveMember { archive, member, data: None } => {
+                let (reader, _) = open_decoded(archive)?;
+                let data = seek_to_member(reader, member).map_err(|e| {
+                    io::Error::new(e.kind(), format!("{}: {}", self.source, e))
+                })?;
+                decompress(data)?
+            }
+            Location::Stdin => decompress(io::stdin())?,
+        };
+        
+        Ok(Box::new(BufReader::with_capacity(READ_BUFFER_SIZE, reader)))
+    }
+    
+    /// Open the input as lines tagged with their byte offset and line number
+    pub fn lines(&self) -> io::Result<OffsetLines<Box<dyn BufRead + Send>>> {
+        Ok(OffsetLines::new(self.open()?, 0))
+    }
+    
+    /// Path of an uncompressed file on disk, which can be read at any offset
+    pub fn plain_file(&self) -> io::Result<Option<&Path>> {
+        let Location::File(path) = &self.location else {
+            return Ok(None);
+        };
+        let (prefix, _) = peek(File::open(path)?, 6)?;
+        Ok((Compression::detect(&prefix) == Compression::None).then_some(path.as_path()))
+    }
+    
+    /// Open the input positioned `offset` bytes into its decoded content
+    pub fn open_at(&self, offset: u64) -> io::Result<Box<dyn BufRead + Send>> {
+        if offset == 0 {
+            return self.open();
+        }
+        
+        // Plain files can seek straight to the offset
+        if let Location::File(path) = &self.location {
+            let mut file = File::open(path)?;
+            let (prefix, _) = peek(&mut file, 6)?;
+            if Compression::detect(&prefix) == Compression::None {
+                file.seek(SeekFrom::Start(offset))?;
+                return Ok(Box::new(BufReader::with_capacity(READ_BUFFER_SIZE, file)));
+            }
+        }
+        
+        // Compressed data can only be skipped by decoding it
+        let mut reader = self.open()?;
+        io::copy(&mut reader.by_ref().take(offset), &mut io::sink())?;
+        Ok(reader)
+    }
+}
+
+/// Lines of a reader that keep track of the byte offset after the last line returned
+pub struct OffsetLines<R> {
+    reader: R,
+    offset: u64,
+    lines_read: usize,
+    complete_only: bool,
+}
+
+impl<R: BufRead> OffsetLines<R> {
+    /// Lines of `reader`, whose first byte is at `offset` in the input
+    pub fn new(reader: R, offset: u64) -> Self {
+        Self { reader, offset, lines_read: 0, complete_only: false }
+    }
+    
+    /// Number lines as if `lines` lines came before `offset`
+    pub fn after_lines(mut self, lines: usize) -> Self {
+        self.lines_read = lines;
+        self
+    }
+    
+    /// Leave a final line without a newline unread; it may still be being written
+    pub fn complete_only(mut self) -> Self {
+        self.complete_only = true;
+        self
+    }
+    
+    /// Byte offset just past the last line returned
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    
+    /// Lines returned so far, including those counted by `after_lines`
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+}
+
+impl<R: BufRead> Iterator for OffsetLines<R> {
+    type Item = io::Result<SourceLine>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(len) => {
+                if !line.ends_with('\n') && self.complete_only {
+                    return None;
+                }
+                let offset = self.offset;
+                self.offset += len as u64;
+                self.lines_read += 1;
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Some(Ok(SourceLine { text: line, offset: Some(offset), number: Some(self.lines_read) }))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// A plain file mapped into memory, so lines can be borrowed instead of copied
+pub struct MappedFile {
+    /// None for an empty file, which cannot be mapped
+    map: Option<Mmap>,
+}
+
+impl MappedFile {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Self { map: None });
+        }
+        // SAFETY: the mapping is only read. As with any mmap reader, truncating the
+        // file while it is being parsed can fault the process
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self { map: Some(map) })
+    }
+    
+    /// The file's contents
+    pub fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+    
+    pub fn len(&self) -> u64 {
+        self.bytes().len() as u64
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.bytes().is_empty()
+    }
+    
+    /// Lines of the whole file
+    pub fn lines(&self) -> MappedLines<'_> {
+        MappedLines::new(self.bytes(), 0)
+    }
+}
+
+/// Lines borrowed from a byte buffer, tagged like `OffsetLines`
+pub struct MappedLines<'a> {
+    data: &'a [u8],
+    /// Offset of `data[0]` in the input
+    base: u64,
+    position: usize,
+    lines_read: usize,
+}
+
+impl<'a> MappedLines<'a> {
+    /// Lines of `data`, whose first byte is at `offset` in the input
+    pub fn new(data: &'a [u8], offset: u64) -> Self {
+        Self { data, base: offset, position: 0, lines_read: 0 }
+    }
+    
+    /// Byte offset just past the last line returned
+    pub fn offset(&self) -> u64 {
+        self.base + self.position as u64
+    }
+}
+
+impl<'a> Iterator for MappedLines<'a> {
+    type Item = io::Result<SourceLine<&'a str>>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.data[self.position..];
+        if rest.is_empty() {
+            return None;
+        }
+        
+        let offset = self.offset();
+        let (mut line, len) = match memchr::memchr(b'\n', rest) {
+            Some(end) => (&rest[..end], end + 1),
+            None => (rest, rest.len()),
+        };
+        self.position += len;
+        self.lines_read += 1;
+        if len > line.len() {
+            line = line.strip_suffix(b"\r").unwrap_or(line);
+        }
+        
+        // Same error as reading the line through `BufRead::read_line`
+        Some(std::str::from_utf8(line)
+            .map(|text| SourceLine { text, offset: Some(offset), number: Some(self.lines_read) })
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")))
+    }
+}
+
+/// Resolve paths into inputs, expanding tar archives into one input per regular member.
+///
+/// `-` selects stdin, named `source_name` if given. Pipes and other non-regular
+/// files are read as a single stream, since probing them would consume data.
+pub fn discover_inputs(paths: &[PathBuf], source_name: Option<&str>) -> io::Result<Vec<LogInput>> {
+    let mut inputs = Vec::new();
+    
+    for path in paths {
+        if path.as_os_str() == STDIN_PATH {
+            inputs.push(LogInput::stdin(source_name));
+            continue;
+        }
+        
+        let is_regular = std::fs::metadata(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?
+            .is_file();
+        if !is_regular {
+            inputs.push(LogInput::file(path));
+            continue;
+        }
+        
+        let (reader, is_tar) = open_decoded(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        
+        if !is_tar {
+            inputs.push(LogInput::file(path));
+            continue;
+        }
+        
+        // Members remember where their data starts, so reading them in order is one pass
+        let stream = ArchiveStream::default();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let start = entry.raw_file_position();
+                let data = (start..start + entry.size(), stream.clone());
+                inputs.push(LogInput::member_at(path.clone(), entry.path()?.into_owned(), Some(data)));
+            }
+        }
+    }
+    
+    Ok(inputs)
+}
+
+/// Wrap a reader with the decoder matching its magic bytes
+pub fn decompress<R: Read + Send + 'static>(reader: R) -> io::Result<Box<dyn Read + Send>> {
+    let (prefix, reader) = peek(reader, 6)?;
+    let reader = Cursor::new(prefix.clone()).chain(reader);
+    
+    Ok(match Compression::detect(&prefix) {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+    })
+}
+
+/// Open a file, decompress it and report whether the content is a tar archive
+fn open_decoded(path: &Path) -> io::Result<(Box<dyn Read + Send>, bool)> {
+    let decoded = decompress(File::open(path)?)?;
+    let (prefix, rest) = peek(decoded, TAR_PROBE_LEN)?;
+    let is_tar = prefix.len() >= 262 && &prefix[257..262] == b"ustar";
+    
+    Ok((Box::new(Cursor::new(prefix).chain(rest)), is_tar))
+}
+
+/// Read up to `len` bytes from the front of a stream without losing them
+fn peek<R: Read>(mut reader: R, len: usize) -> io::Result<(Vec<u8>, R)> {
+    let mut prefix = Vec::with_capacity(len);
+    (&mut reader).take(len as u64).read_to_end(&mut prefix)?;
+    Ok((prefix, reader))
+}
+
+/// Advance a tar stream to the data of `member` and return a reader limited to it
+fn seek_to_member(reader: Box<dyn Read + Send>, member: &Path) -> io::Result<io::Take<Box<dyn Read + Send>>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut size = None;
+    
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.header().entry_type().is_file() && entry.path()? == member {
+            // The header has been consumed; the entry data starts at the current position
+            size = Some(entry.size());
+            break;
+        }
+    }
+    
+    match size {
+        Some(size) => Ok(archive.into_inner().take(size)),
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "member not found in archive")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    
+    fn read_all(input: &LogInput) -> String {
+        let mut content = String::new();
+        input.open().unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+    
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+    
+    #[test]
+    fn test_detect_compression() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]), Compression::Zstd);
+        assert_eq!(Compression::detect(b"BZh91AY"), Compression::Bzip2);
+        assert_eq!(Compression::detect(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]), Compression::Xz);
+        assert_eq!(Compression::detect(b"{\"level\":"), Compression::None);
+        assert_eq!(Compression::detect(b""), Compression::None);
+    }
+    
+    #[test]
+    fn test_compressed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "line one\nline two\n";
+        
+        let plain = dir.path().join("plain.log");
+        std::fs::write(&plain, text).unwrap();
+        let gz = dir.path().join("rotated.log.1");
+        std::fs::write(&gz, gzip(text.as_bytes())).unwrap();
+        let zst = dir.path().join("app.log.zst");
+        std::fs::write(&zst, zstd::encode_all(text.as_bytes(), 0).unwrap()).unwrap();
+        let bz = dir.path().join("app.log.bz2");
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        std::fs::write(&bz, encoder.finish().unwrap()).unwrap();
+        let xz = dir.path().join("app.log.xz");
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        encoder.write_all(text.as_bytes()).unwrap();
+        std::fs::write(&xz, encoder.finish().unwrap()).unwrap();
+        
+        let inputs = discover_inputs(&[plain, gz, zst, bz, xz], None).unwrap();
+        assert_eq!(inputs.len(), 5);
+        for input in &inputs {
+            assert!(input.member().is_none());
+            assert_eq!(read_all(input), text, "content mismatch for {}", input.source());
+        }
+    }
+    
+    #[test]
+    fn test_mapped_lines_match_offset_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mapped.log");
+        std::fs::write(&path, "first\r\nsecond\n\nlast without newline").unwrap();
+        
+        let mapped = MappedFile::open(&path).unwrap();
+        let borrowed: Vec<SourceLine> = mapped.lines().map(|line| line.unwrap().into()).collect();
+        let read: Vec<SourceLine> = LogInput::file(&path).lines().unwrap().map(|line| line.unwrap()).collect();
+        assert_eq!(borrowed, read);
+        assert_eq!(borrowed[3].offset, Some(15));
+        
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(MappedFile::open(&path).unwrap().lines().count(), 0);
+    }
+    
+    #[test]
+    fn test_stdin_input() {
+        let inputs = discover_inputs(&[PathBuf::from("-")], Some("kubectl")).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert!(inputs[0].is_stdin());
+        assert_eq!(inputs[0].source(), "kubectl");
+        
+        let metadata = inputs[0].source_metadata();
+        assert_eq!(metadata.stream.as_deref(), Some("stdin"));
+        assert_eq!(metadata.file.as_deref(), Some("kubectl"));
+        
+        let metadata = LogInput::stdin(None).source_metadata();
+        assert_eq!(metadata.file, None);
+    }
+    
+    #[test]
+    fn test_tar_gz_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, content) in [("logs/app.log", &b"app line\n"[..]), ("logs/db.log", &b"db line\n"[..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, content).unwrap();
+        }
+        let archive = dir.path().join("incident.tar.gz");
+        std::fs::write(&archive, gzip(&builder.into_inner().unwrap())).unwrap();
+        
+        let inputs = discover_inputs(&[archive.clone()], None).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[1].source(), format!("{}!/logs/db.log", archive.display()));
+        assert_eq!(read_all(&inputs[0]), "app line\n");
+        assert_eq!(read_all(&inputs[1]), "db line\n");
+        
+        // Reading in archive order continues the shared stream where the last member ended
+        let Location::ArchiveMember { data: Some((data, stream)), .. } = &inputs[1].location else { panic!() };
+        assert_eq!(stream.0.lock().unwrap().position, data.end);
+        
+        // Going back or interleaving members decodes again from the start as needed
+        let mut db = inputs[1].open().unwrap();
+        assert_eq!(read_all(&inputs[0]), "app line\n");
+        let mut content = String::new();
+        db.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "db line\n");
+        assert_eq!(read_all(&LogInput::archive_member(&archive, "logs/db.log")), "db line\n");
+    }
+    
+    #[test]
+    fn test_open_at_offset_and_complete_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "first\r\nsecond\npartial";
+        let plain = dir.path().join("offsets.log");
+        std::fs::write(&plain, text).unwrap();
+        let gz = dir.path().join("offsets.log.gz");
+        std::fs::write(&gz, gzip(text.as_bytes())).unwrap();
+        
+        for path in [plain, gz] {
+            let input = LogInput::file(&path);
+            let mut lines = OffsetLines::new(input.open_at(7).unwrap(), 7).after_lines(1).complete_only();
+            let line = lines.next().unwrap().unwrap();
+            assert_eq!((line.text.as_str(), line.offset, line.number), ("second", Some(7), Some(2)));
+            assert!(lines.next().is_none());
+            assert_eq!(lines.offset(), 14);
+            
+            let mut lines = input.lines().unwrap();
+            let read: Vec<_> = lines.by_ref().map(|line| line.unwrap()).collect();
+            assert_eq!(read.iter().map(|line| line.text.as_str()).collect::<Vec<_>>(), ["first", "second", "partial"]);
+            assert_eq!(read.iter().map(|line| line.offset.unwrap()).collect::<Vec<_>>(), [0, 7, 14]);
+            assert_eq!(lines.offset(), text.len() as u64);
+        }
+    }
+}
@@ -1,14 +1,32 @@
 pub mod models;
+pub mod event_ref;
 pub mod error;
 pub mod statistics;
 pub mod parse_result;
 pub mod parsers;
 pub mod profiles;
+pub mod log_format;
 pub mod classifier;
+pub mod format_lock;
 pub mod resilient_parser;
 pub mod streaming_parser;
 pub mod parallel_parser;
+pub mod chunked_parser;
 pub mod tango_parser;
+pub mod config;
+pub mod multiline;
+pub mod input;
+pub mod year_inference;
+pub mod timezone;
+pub mod epoch;
+pub mod timestamp;
+pub mod container;
+pub mod field_mapping;
+pub mod field_path;
+pub mod filter;
+pub mod merge;
+pub mod checkpoint;
+pub mod rejects;
 pub mod integration_test;
 pub mod tango_integration_tests;
 pub mod cli;
@@ -20,11 +38,27 @@ pub mod parallel_tests;
 pub use models::*;
 pub use error::ParseError;
 pub use statistics::ParsingStatistics;
-pub use parse_result::ParseResult;
-pub use parsers::{LogParser, JsonParser, LogfmtParser, PatternParser, PlainTextParser, ProfileParser};
+pub use event_ref::{CanonicalEventRef, SourceRef, ValueRef};
+pub use parse_result::{ParseResult, ParseResultRef};
+pub use parsers::{LogParser, CriParser, DockerJsonParser, JsonParser, KlogParser, LogfmtParser, PatternParser, PlainTextParser, ProfileParser, Rfc5424Parser, ParserRegistry, RegisteredParser};
 pub use profiles::*;
 pub use classifier::{FormatClassifier, TangoFormatClassifier, FormatCache, FormatCacheEntry, CacheStats};
 pub use resilient_parser::ResilientParser;
 pub use streaming_parser::{StreamingParser, StreamingConfig, RegexCache, ParsingStructures};
 pub use parallel_parser::{ParallelParser, ParallelConfig, ParallelResult, ThreadSafeParsingStructures, WorkItem};
-pub use tango_parser::{TangoParser, TangoConfig, ProfileConfig};
\ No newline at end of file
+pub use chunked_parser::ChunkedParser;
+pub use tango_parser::{TangoParser, TangoConfig, ProfileConfig, SourceBinding};
+pub use multiline::{MultilineConfig, LineAssembler, LineGroup, LineGroupRef, SourceLine};
+pub use input::{LogInput, Compression, MappedFile, MappedLines, OffsetLines};
+pub use year_inference::YearContext;
+pub use filter::Filter;
+pub use epoch::EpochUnit;
+pub use timestamp::{Recognized, TimestampFormat};
+pub use container::ContainerPath;
+pub use field_mapping::{FieldMappings, SourceFieldMappings};
+pub use field_path::{FieldPath, FlattenConfig};
+pub use log_format::CompiledLogFormat;
+pub use format_lock::{FormatLockConfig, FormatLocks, LockState, SourceLock};
+pub use merge::{MergeConfig, MergedEvents};
+pub use checkpoint::{CheckpointStore, Checkpoint, FileState};
+pub use rejects::{RejectConfig, RejectWriter};
@@ -0,0 +1,591 @@
+use crate::error::ParseError;
+use crate::models::*;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+
+/// Built-in Apache `LogFormat` definitions
+pub const APACHE_PRESETS: &[(&str, &str)] = &[
+    ("common", r#"%h %l %u %t "%r" %>s %b"#),
+    ("combined", r#"%h %l %u %t "%r" %>s %b "%{Referer}i" "%{User-agent}i""#),
+    ("vhost_combined", r#"%v:%p %h %l %u %t "%r" %>s %O "%{Referer}i" "%{User-Agent}i""#),
+];
+
+/// Built-in nginx `log_format` definitions
+pub const NGINX_PRESETS: &[(&str, &str)] = &[
+    ("combined", r#"$remote_addr - $remote_user [$time_local] "$request" $status $body_bytes_sent "$http_referer" "$http_user_agent""#),
+    ("main", r#"$remote_addr - $remote_user [$time_local] "$request" $status $body_bytes_sent "$http_referer" "$http_user_agent" "$http_x_forwarded_for""#),
+];
+
+/// How a captured value is stored
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Text,
+    Integer,
+    Float,
+    /// HTTP status; also sets the event level
+    Status,
+    /// Request line, split into method/path/query/protocol
+    Request,
+    /// Query string, without its leading `?`
+    Query,
+    Timestamp(TimeFormat),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TimeFormat {
+    /// `10/Oct/2000:13:55:36 -0700`
+    Clf,
+    Iso8601,
+    EpochSeconds,
+    EpochMillis,
+    EpochMicros,
+    Strftime(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Capture {
+    field: String,
+    kind: Kind,
+}
+
+impl Capture {
+    fn new(field: &str, kind: Kind) -> Self {
+        Self { field: field.to_string(), kind }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    Field(Capture),
+    /// Apache `%t`: a timestamp written inside brackets
+    BracketedTime,
+}
+
+/// An access log format compiled into a regex with typed captures
+#[derive(Debug, Clone)]
+pub struct CompiledLogFormat {
+    format: String,
+    regex: Regex,
+    captures: Vec<Capture>,
+}
+
+impl CompiledLogFormat {
+    /// Compile an Apache `LogFormat` string or the name of a preset (`common`, `combined`, `vhost_combined`)
+    pub fn apache(format: &str) -> Result<Self, ParseError> {
+        let format = preset(APACHE_PRESETS, format).unwrap_or(format);
+        let tokens = tokenize_apache(format).map_err(|message| format_error(format, message))?;
+        Self::build(format, tokens)
+    }
+    
+    /// Compile an nginx `log_format` string, a full `log_format name '...';` directive,
+    /// or the name of a preset (`combined`, `main`)
+    pub fn nginx(definition: &str) -> Result<Self, ParseError> {
+        let format = match preset(NGINX_PRESETS, definition) {
+            Some(format) => format.to_string(),
+            None => strip_log_format_directive(definition).map_err(|message| format_error(definition, message))?,
+        };
+        let tokens = tokenize_nginx(&format).map_err(|message| format_error(&format, message))?;
+        Self::build(&format, tokens)
+    }
+    
+    /// The format string this was compiled from
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+    
+    /// The generated regex
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+    
+    pub fn is_match(&self, line: &str) -> bool {
+        self.regex.is_match(line)
+    }
+    
+    /// Parse a line into an event of the given profile type; `None` if the line does not match
+    pub fn parse(&self, line: &str, profile_type: ProfileType) -> Option<CanonicalEvent> {
+        let captures = self.regex.captures(line)?;
+        let mut event = CanonicalEvent::new(String::new(), line.to_string(), FormatType::Profile(profile_type));
+        
+        for (i, capture) in self.captures.iter().enumerate() {
+            let Some(value) = captures.get(i + 1).map(|m| unescape(m.as_str())) else {
+                continue;
+            };
+            // Apache and nginx both write "-" for a missing value
+            if value.is_empty() || value == "-" {
+                continue;
+            }
+            store_value(&mut event, capture, value);
+        }
+        
+        if let Some(request) = event.fields.get("request").and_then(|v| v.as_str()) {
+            event.message = request.to_string();
+        }
+        Some(event)
+    }
+    
+    fn build(format: &str, tokens: Vec<Token>) -> Result<Self, ParseError> {
+        let mut pattern = String::from("^");
+        let mut captures = Vec::new();
+        
+        for (i, token) in tokens.iter().enumerate() {
+            let next = match tokens.get(i + 1) {
+                Some(Token::Literal(text)) => text.chars().next(),
+                Some(Token::BracketedTime) => Some('['),
+                _ => None,
+            };
+            match token {
+                Token::Literal(text) => pattern.push_str(&regex::escape(text)),
+                Token::BracketedTime => {
+                    pattern.push_str(r"\[([^\]]*)\]");
+                    captures.push(Capture::new("timestamp", Kind::Timestamp(TimeFormat::Clf)));
+                }
+                Token::Field(capture) => {
+                    pattern.push('(');
+                    pattern.push_str(&value_pattern(&capture.kind, next));
+                    pattern.push(')');
+                    captures.push(capture.clone());
+                }
+            }
+        }
+        
+        let regex = Regex::new(&pattern).map_err(|e| ParseError::ConfigurationError {
+            parameter: "format".to_string(),
+            error_message: format!("Log format '{}' compiles to an invalid regex: {}", format, e),
+        })?;
+        Ok(Self { format: format.to_string(), regex, captures })
+    }
+}
+
+/// Regex for a value, bounded by the character that follows it in the format
+fn value_pattern(kind: &Kind, next: Option<char>) -> String {
+    if let Kind::Timestamp(TimeFormat::Clf) = kind {
+        return r"\d{2}/\w{3}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}".to_string();
+    }
+    match next {
+        Some('"') => r#"(?:[^"\\]|\\.)*"#.to_string(),
+        Some(c) if c.is_whitespace() => match kind {
+            // A custom time format may itself contain spaces
+            Kind::Timestamp(TimeFormat::Strftime(_)) => ".*?".to_string(),
+            _ => r"\S*".to_string(),
+        },
+        Some(c) => format!("[^{}]*", regex::escape(&c.to_string())),
+        None => r"\S*".to_string(),
+    }
+}
+
+fn store_value(event: &mut CanonicalEvent, capture: &Capture, value: String) {
+    let field = capture.field.clone();
+    match &capture.kind {
+        Kind::Text => event.add_field(field, serde_json::Value::String(value)),
+        Kind::Integer => event.add_field(field, number_or_text(&value, value.parse::<i64>().ok().map(Into::into))),
+        Kind::Float => event.add_field(field, number_or_text(
+            &value,
+            value.parse::<f64>().ok().and_then(serde_json::Number::from_f64),
+        )),
+        Kind::Query => {
+            let query = value.strip_prefix('?').unwrap_or(&value);
+            if !query.is_empty() {
+                event.add_field(field, serde_json::Value::String(query.to_string()));
+            }
+        }
+        Kind::Status => match value.parse::<u16>() {
+            Ok(status) => {
+                event.add_field(field, serde_json::Value::Number(status.into()));
+                event.set_level(level_for_status(status));
+            }
+            Err(_) => event.add_field(field, serde_json::Value::String(value)),
+        },
+        Kind::Request => {
+            split_request(event, &value);
+            event.add_field(field, serde_json::Value::String(value));
+        }
+        Kind::Timestamp(format) => match parse_time(format, &value) {
+            Some(timestamp) => event.set_log_timestamp(timestamp),
+            None => event.add_field(field, serde_json::Value::String(value)),
+        },
+    }
+}
+
+fn number_or_text(value: &str, number: Option<serde_json::Number>) -> serde_json::Value {
+    match number {
+        Some(number) => serde_json::Value::Number(number),
+        // e.g. "0.004, 0.010" when nginx tried several upstreams
+        None => serde_json::Value::String(value.to_string()),
+    }
+}
+
+/// Split `GET /path?query HTTP/1.1` into its parts
+fn split_request(event: &mut CanonicalEvent, request: &str) {
+    let mut parts = request.splitn(3, ' ');
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return;
+    };
+    if method.is_empty() || !method.chars().all(|c| c.is_ascii_uppercase()) {
+        return;
+    }
+    
+    event.add_field("method".to_string(), serde_json::Value::String(method.to_string()));
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (target, None),
+    };
+    event.add_field("path".to_string(), serde_json::Value::String(path.to_string()));
+    if let Some(query) = query.filter(|query| !query.is_empty()) {
+        event.add_field("query".to_string(), serde_json::Value::String(query.to_string()));
+    }
+    if let Some(protocol) = parts.next() {
+        event.add_field("protocol".to_string(), serde_json::Value::String(protocol.to_string()));
+    }
+}
+
+pub(crate) fn level_for_status(status: u16) -> LogLevel {
+    match status {
+        400..=499 => LogLevel::Warn,
+        500..=599 => LogLevel::Error,
+        _ => LogLevel::Info,
+    }
+}
+
+fn parse_time(format: &TimeFormat, value: &str) -> Option<LogTimestamp> {
+    let epoch = |scale: f64| {
+        let seconds = value.parse::<f64>().ok()? / scale;
+        let nanos = (seconds.fract() * 1e9).round() as u32;
+        Utc.timestamp_opt(seconds.trunc() as i64, nanos).single().map(LogTimestamp::Explicit)
+    };
+    match format {
+        TimeFormat::Clf => DateTime::parse_from_str(value, "%d/%b/%Y:%H:%M:%S %z")
+            .ok()
+            .map(|dt| LogTimestamp::Explicit(dt.with_timezone(&Utc))),
+        TimeFormat::Iso8601 => DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|dt| LogTimestamp::Explicit(dt.with_timezone(&Utc))),
+        TimeFormat::EpochSeconds => epoch(1.0),
+        TimeFormat::EpochMillis => epoch(1e3),
+        TimeFormat::EpochMicros => epoch(1e6),
+        TimeFormat::Strftime(format) => match DateTime::parse_from_str(value, format) {
+            Ok(dt) => Some(LogTimestamp::Explicit(dt.with_timezone(&Utc))),
+            Err(_) => NaiveDateTime::parse_from_str(value, format).ok().map(LogTimestamp::Naive),
+        },
+    }
+}
+
+/// Undo the escaping Apache (`\"`, `\\`) and nginx (`\x22`) apply inside values
+fn unescape(value: &str) -> String {
+    if !value.contains('\\') {
+        return value.to_string();
+    }
+    
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('"') | Some('\\') => out.push(chars.next().unwrap()),
+            Some('x') => {
+                let hex: String = chars.clone().skip(1).take(2).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) if hex.len() == 2 && byte.is_ascii() => {
+                        out.push(byte as char);
+                        chars.nth(2);
+                    }
+                    _ => out.push(c),
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn preset<'a>(presets: &'a [(&str, &str)], name: &str) -> Option<&'a str> {
+    presets.iter().find(|(preset, _)| *preset == name).map(|(_, format)| *format)
+}
+
+fn format_error(format: &str, message: String) -> ParseError {
+    ParseError::ConfigurationError {
+        parameter: "format".to_string(),
+        error_message: format!("{} in log format '{}'", message, format),
+    }
+}
+
+/// Split an Apache format string into literals and directives
+fn tokenize_apache(format: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let chars: Vec<char> = format.chars().collect();
+    let mut i = 0;
+    
+    while i < chars.len() {
+        if chars[i] != '%' {
+            literal.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        if chars.get(i) == Some(&'%') {
+            literal.push('%');
+            i += 1;
+            continue;
+        }
+        
+        // Status conditions and redirect modifiers: %400,501{...}i, %>s, %<s, %!200u
+        while i < chars.len() && matches!(chars[i], '<' | '>' | '!' | ',' | '0'..='9') {
+            i += 1;
+        }
+        let argument = if chars.get(i) == Some(&'{') {
+            let close = chars[i..].iter().position(|c| *c == '}')
+                .ok_or_else(|| format!("Unterminated '{{' at position {}", i))?;
+            let argument: String = chars[i + 1..i + close].iter().collect();
+            i += close + 1;
+            Some(argument)
+        } else {
+            None
+        };
+        let directive = *chars.get(i).ok_or_else(|| format!("Incomplete directive at position {}", start))?;
+        i += 1;
+        
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(apache_directive(directive, argument.as_deref())
+            .ok_or_else(|| format!("Unknown directive '%{}' at position {}", directive, start))?);
+    }
+    
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+fn apache_directive(directive: char, argument: Option<&str>) -> Option<Token> {
+    use Kind::*;
+    let field = |name: &str, kind: Kind| Some(Token::Field(Capture::new(name, kind)));
+    
+    match (directive, argument) {
+        ('t', None) => Some(Token::BracketedTime),
+        ('t', Some(argument)) => {
+            let argument = argument.strip_prefix("begin:").or_else(|| argument.strip_prefix("end:")).unwrap_or(argument);
+            let format = match argument {
+                "sec" => TimeFormat::EpochSeconds,
+                "msec" => TimeFormat::EpochMillis,
+                "usec" => TimeFormat::EpochMicros,
+                strftime => TimeFormat::Strftime(strftime.to_string()),
+            };
+            field("timestamp", Timestamp(format))
+        }
+        ('h', _) | ('a', None) => field("client_ip", Text),
+        ('a', Some(_)) => field("peer_ip", Text),
+        ('A', _) => field("server_ip", Text),
+        ('l', _) => field("ident", Text),
+        ('u', _) => field("remote_user", Text),
+        ('r', _) => field("request", Request),
+        ('s', _) => field("status", Status),
+        ('b', _) | ('B', _) => field("size", Integer),
+        ('O', _) => field("bytes_sent", Integer),
+        ('I', _) => field("bytes_received", Integer),
+        ('S', _) => field("bytes_transferred", Integer),
+        ('D', _) => field("request_time_us", Integer),
+        ('T', None) | ('T', Some("s")) => field("request_time", Integer),
+        ('T', Some("ms")) => field("request_time_ms", Integer),
+        ('T', Some("us")) => field("request_time_us", Integer),
+        ('v', _) => field("vhost", Text),
+        ('V', _) => field("server_name", Text),
+        ('p', Some("remote")) => field("remote_port", Integer),
+        ('p', _) => field("port", Integer),
+        ('P', Some("tid")) | ('P', Some("hextid")) => field("tid", Text),
+        ('P', _) => field("pid", Integer),
+        ('m', _) => field("method", Text),
+        ('U', _) => field("path", Text),
+        ('q', _) => field("query", Query),
+        ('H', _) => field("protocol", Text),
+        ('X', _) => field("connection_status", Text),
+        ('k', _) => field("keepalive_requests", Integer),
+        ('L', _) => field("log_id", Text),
+        ('R', _) => field("handler", Text),
+        ('f', _) => field("filename", Text),
+        ('i', Some(header)) | ('e', Some(header)) | ('n', Some(header)) | ('C', Some(header)) => {
+            field(&snake_case(header), Text)
+        }
+        ('o', Some(header)) => field(&format!("response_{}", snake_case(header)), Text),
+        _ => None,
+    }
+}
+
+/// Split an nginx format string into literals and `$variables`
+fn tokenize_nginx(format: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let chars: Vec<char> = format.chars().collect();
+    let mut i = 0;
+    
+    while i < chars.len() {
+        if chars[i] != '$' {
+            literal.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        let name: String = if chars.get(i) == Some(&'{') {
+            let close = chars[i..].iter().position(|c| *c == '}')
+                .ok_or_else(|| format!("Unterminated '${{' at position {}", start))?;
+            let name = chars[i + 1..i + close].iter().collect();
+            i += close + 1;
+            name
+        } else {
+            let len = chars[i..].iter().take_while(|c| c.is_ascii_alphanumeric() || **c == '_').count();
+            let name = chars[i..i + len].iter().collect();
+            i += len;
+            name
+        };
+        if name.is_empty() {
+            return Err(format!("Missing variable name at position {}", start));
+        }
+        
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(Token::Field(nginx_variable(&name)));
+    }
+    
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+fn nginx_variable(name: &str) -> Capture {
+    use Kind::*;
+    match name {
+        "remote_addr" | "realip_remote_addr" => Capture::new("client_ip", Text),
+        "time_local" => Capture::new("timestamp", Timestamp(TimeFormat::Clf)),
+        "time_iso8601" => Capture::new("timestamp", Timestamp(TimeFormat::Iso8601)),
+        "msec" => Capture::new("timestamp", Timestamp(TimeFormat::EpochSeconds)),
+        "request" => Capture::new("request", Request),
+        "status" => Capture::new("status", Status),
+        "body_bytes_sent" => Capture::new("size", Integer),
+        "request_length" => Capture::new("bytes_received", Integer),
+        "request_method" => Capture::new("method", Text),
+        "uri" | "document_uri" => Capture::new("path", Text),
+        "args" | "query_string" => Capture::new("query", Query),
+        "server_protocol" => Capture::new("protocol", Text),
+        "bytes_sent" | "connection" | "connection_requests" | "pid" | "server_port" | "remote_port"
+        | "upstream_status" | "upstream_bytes_received" | "upstream_bytes_sent" => Capture::new(name, Integer),
+        _ if name.ends_with("_time") => Capture::new(name, Float),
+        _ => match (name.strip_prefix("http_"), name.strip_prefix("sent_http_")) {
+            (Some(header), _) => Capture::new(header, Text),
+            (_, Some(header)) => Capture::new(&format!("response_{}", header), Text),
+            _ => Capture::new(name, Text),
+        },
+    }
+}
+
+/// The format string of a `log_format name [escape=...] '...' '...';` directive,
+/// or the input unchanged when it is not one
+fn strip_log_format_directive(definition: &str) -> Result<String, String> {
+    let definition = definition.trim();
+    let Some(rest) = definition.strip_prefix("log_format") else {
+        return Ok(definition.to_string());
+    };
+    let rest = rest.trim().trim_end_matches(';');
+    
+    // Skip the format name and any escape= parameter
+    let mut rest = rest.trim_start();
+    while !rest.is_empty() && !rest.starts_with(['\'', '"']) {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        rest = rest[end..].trim_start();
+    }
+    
+    // Adjacent quoted strings are concatenated
+    let mut format = String::new();
+    while let Some(quote) = rest.chars().next() {
+        if quote != '\'' && quote != '"' {
+            return Err(format!("Unexpected '{}' between quoted strings", quote));
+        }
+        let end = rest[1..].find(quote).ok_or("Unterminated quoted string")?;
+        format.push_str(&rest[1..1 + end]);
+        rest = rest[end + 2..].trim_start();
+    }
+    if format.is_empty() {
+        return Err("Missing format string".to_string());
+    }
+    Ok(format)
+}
+
+/// `User-Agent` -> `user_agent`
+fn snake_case(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_apache_combined_preset() {
+        let format = CompiledLogFormat::apache("combined").unwrap();
+        let line = r#"10.0.0.5 - alice [10/Oct/2000:13:55:36 -0700] "GET /search?q=rust&page=2 HTTP/1.1" 503 - "https://example.com/" "Mozilla/5.0 (X11; \"Linux\")""#;
+        let event = format.parse(line, ProfileType::Apache).unwrap();
+        
+        assert_eq!(event.message, "GET /search?q=rust&page=2 HTTP/1.1");
+        assert_eq!(event.level, Some(LogLevel::Error));
+        assert_eq!(event.timestamp.unwrap().to_rfc3339(), "2000-10-10T20:55:36+00:00");
+        assert_eq!(event.fields["remote_user"], "alice");
+        assert_eq!(event.fields["method"], "GET");
+        assert_eq!(event.fields["path"], "/search");
+        assert_eq!(event.fields["query"], "q=rust&page=2");
+        assert_eq!(event.fields["protocol"], "HTTP/1.1");
+        assert_eq!(event.fields["status"], 503);
+        assert!(!event.fields.contains_key("size"));
+        assert_eq!(event.fields["referer"], "https://example.com/");
+        assert_eq!(event.fields["user_agent"], r#"Mozilla/5.0 (X11; "Linux")"#);
+        assert!(!event.fields.contains_key("ident"));
+    }
+    
+    #[test]
+    fn test_apache_custom_format_with_durations() {
+        let format = CompiledLogFormat::apache(r#"%v:%p %a %t "%r" %>s %B %D %{X-Request-Id}i"#).unwrap();
+        let line = r#"www.example.com:443 192.0.2.1 [01/Jan/2025:00:00:00 +0000] "POST /api HTTP/2.0" 201 512 1834 abc-123"#;
+        let event = format.parse(line, ProfileType::Apache).unwrap();
+        
+        assert_eq!(event.fields["vhost"], "www.example.com");
+        assert_eq!(event.fields["port"], 443);
+        assert_eq!(event.fields["size"], 512);
+        assert_eq!(event.fields["request_time_us"], 1834);
+        assert_eq!(event.fields["x_request_id"], "abc-123");
+        
+        assert!(matches!(
+            CompiledLogFormat::apache("%h %Z"),
+            Err(ParseError::ConfigurationError { parameter, .. }) if parameter == "format"
+        ));
+    }
+    
+    #[test]
+    fn test_nginx_log_format_directive() {
+        let format = CompiledLogFormat::nginx(r#"
+            log_format upstream escape=default '$remote_addr - $remote_user [$time_local] '
+                                '"$request" $status $body_bytes_sent '
+                                'rt=$request_time uct="$upstream_connect_time" urt="$upstream_response_time" ua=$upstream_addr';
+        "#).unwrap();
+        let line = r#"203.0.113.9 - bob [10/Oct/2024:13:55:36 +0000] "GET /health HTTP/1.1" 200 17 rt=0.125 uct="0.001" urt="0.004, 0.120" ua=10.1.0.7:8080"#;
+        let event = format.parse(line, ProfileType::Nginx).unwrap();
+        
+        assert_eq!(event.fields["remote_user"], "bob");
+        assert_eq!(event.fields["size"], 17);
+        assert_eq!(event.fields["request_time"], 0.125);
+        assert_eq!(event.fields["upstream_connect_time"], 0.001);
+        assert_eq!(event.fields["upstream_response_time"], "0.004, 0.120");
+        assert_eq!(event.fields["upstream_addr"], "10.1.0.7:8080");
+        assert_eq!(event.level, Some(LogLevel::Info));
+        assert!(event.timestamp.is_some());
+        
+        let combined = CompiledLogFormat::nginx("combined").unwrap();
+        assert!(combined.is_match(r#"127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET / HTTP/1.1" 200 1 "-" "curl/8.0""#));
+    }
+}
@@ -1,16 +1,71 @@
 use clap::Parser;
 use tango::cli::{Cli, Commands};
 use tango::commands::{run_parse, run_search, run_stats, run_tail, run_convert};
+use tango::config::load_config;
+use tango::tango_parser::TangoParser;
+use tango::timezone::parse_timezone;
 
 fn main() {
     let cli = Cli::parse();
     
+    let mut config = match load_config(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if cli.parallel > 0 {
+        config.parallel_config.num_threads = cli.parallel;
+    }
+    if let Some(megabytes) = cli.memory_limit {
+        config.streaming_config.memory_limit_bytes = megabytes * 1024 * 1024;
+    }
+    if cli.year.is_some() {
+        config.year = cli.year;
+    }
+    if let Some(tz) = cli.tz {
+        if let Err(e) = parse_timezone(&tz, "--tz") {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        config.default_timezone = Some(tz);
+    }
+    if cli.rejects.is_some() {
+        config.rejects.path = cli.rejects;
+        config.rejects.min_confidence = cli.min_confidence.or(config.rejects.min_confidence);
+        if let Err(e) = config.rejects.validate() {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(unit) = cli.epoch_unit {
+        config.field_mappings.epoch_unit = unit;
+    }
+    if cli.flatten_depth.is_some() {
+        config.flatten.max_depth = cli.flatten_depth;
+    }
+    config.flatten.index_arrays |= cli.index_arrays;
+    config.flatten.nest_output |= cli.nest_fields;
+    if let Err(e) = config.flatten.validate() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    if cli.input_format.is_some() || cli.profile.is_some() {
+        config.format = cli.input_format;
+        config.profile = cli.profile;
+        if let Err(e) = TangoParser::validate_forced_format(&config) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    
     let result = match cli.command {
-        Commands::Parse(args) => run_parse(args),
-        Commands::Search(args) => run_search(args),
-        Commands::Stats(args) => run_stats(args),
-        Commands::Tail(args) => run_tail(args),
-        Commands::Convert(args) => run_convert(args),
+        Commands::Parse(args) => run_parse(args, &config),
+        Commands::Search(args) => run_search(args, &config),
+        Commands::Stats(args) => run_stats(args, &config),
+        Commands::Tail(args) => run_tail(args, &config),
+        Commands::Convert(args) => run_convert(args, &config),
     };
     
     if let Err(e) = result {
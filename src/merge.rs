@@ -0,0 +1,344 @@
+use crate::models::CanonicalEvent;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+
+/// Settings for merging several time-ordered sources
+#[derive(Debug, Clone, Copy)]
+pub struct MergeConfig {
+    /// How far back in time a source may jump and still be put in order
+    pub reorder_window: Duration,
+    /// Budget for events held in the reorder window; beyond it they are spilled to disk
+    pub memory_limit_bytes: usize,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            reorder_window: Duration::seconds(5),
+            memory_limit_bytes: 100 * 1024 * 1024, // 100MB
+        }
+    }
+}
+
+/// An event with its sort key. Events without a timestamp sort with the preceding
+/// event of their source; `seq` keeps the input order among equal timestamps.
+#[derive(Debug, Serialize, Deserialize)]
+struct Keyed {
+    key: DateTime<Utc>,
+    seq: u64,
+    source: usize,
+    event: CanonicalEvent,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.key, self.seq).cmp(&(other.key, other.seq))
+    }
+}
+
+/// A sorted run of events spilled to a temporary file, read back one event at a time
+struct SpilledRun {
+    reader: BufReader<File>,
+    head: Option<Keyed>,
+}
+
+impl SpilledRun {
+    fn write(events: Vec<Keyed>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(tempfile::tempfile()?);
+        for event in &events {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n")?;
+        }
+        
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        let mut run = Self { reader: BufReader::new(file), head: None };
+        run.advance()?;
+        Ok(run)
+    }
+    
+    fn advance(&mut self) -> io::Result<()> {
+        let mut line = String::new();
+        self.head = if self.reader.read_line(&mut line)? == 0 {
+            None
+        } else {
+            Some(serde_json::from_str(&line)?)
+        };
+        Ok(())
+    }
+}
+
+/// Where the next event to emit comes from
+enum Candidate {
+    Pending,
+    Run(usize),
+}
+
+/// Heap-based k-way merge of per-source event streams by timestamp.
+///
+/// Each source is expected to be roughly time-ordered: an event may be up to
+/// `reorder_window` older than the newest event read so far and still be placed
+/// correctly. Events in the window that exceed the memory budget are written out
+/// as sorted runs and merged back in, so memory stays bounded for any input size.
+pub struct MergedEvents<F> {
+    next_event: F,
+    config: MergeConfig,
+    /// Next unread event of each unfinished source
+    heads: BinaryHeap<Reverse<Keyed>>,
+    /// Events read but not yet emitted
+    pending: BinaryHeap<Reverse<Keyed>>,
+    pending_bytes: usize,
+    runs: Vec<SpilledRun>,
+    spilled_runs: usize,
+    /// Sort key given to the previous event of each source
+    last_keys: Vec<Option<DateTime<Utc>>>,
+    /// Newest timestamp read from any source
+    watermark: Option<DateTime<Utc>>,
+    seq: u64,
+    started: bool,
+}
+
+/// Merge `sources` event streams; `next_event(i)` yields the next event of source `i`
+pub fn merge_by_timestamp<F>(sources: usize, config: MergeConfig, next_event: F) -> MergedEvents<F>
+where
+    F: FnMut(usize) -> Option<io::Result<CanonicalEvent>>,
+{
+    MergedEvents {
+        next_event,
+        config,
+        heads: BinaryHeap::with_capacity(sources),
+        pending: BinaryHeap::new(),
+        pending_bytes: 0,
+        runs: Vec::new(),
+        spilled_runs: 0,
+        last_keys: vec![None; sources],
+        watermark: None,
+        seq: 0,
+        started: false,
+    }
+}
+
+impl<F> MergedEvents<F>
+where
+    F: FnMut(usize) -> Option<io::Result<CanonicalEvent>>,
+{
+    /// Number of sorted runs written to disk so far
+    pub fn spilled_runs(&self) -> usize {
+        self.spilled_runs
+    }
+    
+    /// Read the next event of `source` into the head heap
+    fn read_head(&mut self, source: usize) -> io::Result<()> {
+        let event = match (self.next_event)(source) {
+            Some(event) => event?,
+            None => return Ok(()),
+        };
+        
+        let key = event.timestamp
+            .or(self.last_keys[source])
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        self.last_keys[source] = Some(key);
+        self.seq += 1;
+        self.heads.push(Reverse(Keyed { key, seq: self.seq, source, event }));
+        Ok(())
+    }
+    
+    /// Move the oldest head into the reorder window and read its replacement
+    fn advance_sources(&mut self) -> io::Result<()> {
+        let Some(Reverse(keyed)) = self.heads.pop() else {
+            return Ok(());
+        };
+        let source = keyed.source;
+        
+        self.watermark = self.watermark.max(Some(keyed.key));
+        self.pending_bytes += estimated_size(&keyed.event);
+        self.pending.push(Reverse(keyed));
+        
+        if self.pending_bytes > self.config.memory_limit_bytes {
+            self.spill()?;
+        }
+        self.read_head(source)
+    }
+    
+    /// Write the reorder window out as one sorted run
+    fn spill(&mut self) -> io::Result<()> {
+        let events: Vec<Keyed> = std::mem::take(&mut self.pending)
+            .into_sorted_vec()
+            .into_iter()
+            .rev()
+            .map(|Reverse(keyed)| keyed)
+            .collect();
+        self.pending_bytes = 0;
+        self.runs.push(SpilledRun::write(events)?);
+        self.spilled_runs += 1;
+        Ok(())
+    }
+    
+    /// Oldest unemitted event across the reorder window and the spilled runs
+    fn candidate(&self) -> Option<(Candidate, &Keyed)> {
+        let pending = self.pending.peek().map(|Reverse(keyed)| (Candidate::Pending, keyed));
+        let runs = self.runs.iter().enumerate()
+            .filter_map(|(i, run)| run.head.as_ref().map(|keyed| (Candidate::Run(i), keyed)));
+        pending.into_iter().chain(runs).min_by(|a, b| a.1.cmp(b.1))
+    }
+    
+    /// True if no unread event can be older than `keyed`
+    fn is_settled(&self, keyed: &Keyed) -> bool {
+        if self.heads.is_empty() {
+            return true;
+        }
+        let head_is_newer = self.heads.peek().is_some_and(|Reverse(head)| head > keyed);
+        let outside_window = self.watermark
+            .is_some_and(|watermark| keyed.key + self.config.reorder_window <= watermark);
+        head_is_newer && outside_window
+    }
+    
+    fn take(&mut self, candidate: Candidate) -> io::Result<CanonicalEvent> {
+        match candidate {
+            Candidate::Pending => {
+                let Reverse(keyed) = self.pending.pop().expect("candidate came from pending");
+                self.pending_bytes = self.pending_bytes.saturating_sub(estimated_size(&keyed.event));
+                Ok(keyed.event)
+            }
+            Candidate::Run(i) => {
+                let run = &mut self.runs[i];
+                let keyed = run.head.take().expect("candidate came from run");
+                run.advance()?;
+                if run.head.is_none() {
+                    self.runs.swap_remove(i);
+                }
+                Ok(keyed.event)
+            }
+        }
+    }
+    
+    fn next_event(&mut self) -> io::Result<Option<CanonicalEvent>> {
+        if !self.started {
+            self.started = true;
+            for source in 0..self.last_keys.len() {
+                self.read_head(source)?;
+            }
+        }
+        
+        loop {
+            let settled = match self.candidate() {
+                Some((candidate, keyed)) if self.is_settled(keyed) => Some(candidate),
+                _ => None,
+            };
+            if let Some(candidate) = settled {
+                return self.take(candidate).map(Some);
+            }
+            if self.heads.is_empty() {
+                return Ok(None);
+            }
+            self.advance_sources()?;
+        }
+    }
+}
+
+impl<F> Iterator for MergedEvents<F>
+where
+    F: FnMut(usize) -> Option<io::Result<CanonicalEvent>>,
+{
+    type Item = io::Result<CanonicalEvent>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+/// Rough heap footprint of an event, for the reorder window budget
+fn estimated_size(event: &CanonicalEvent) -> usize {
+    let fields: usize = event.fields.iter()
+        .map(|(key, value)| key.len() + value_size(value) + 64)
+        .sum();
+    std::mem::size_of::<Keyed>() + event.raw.len() + event.message.len() + fields
+}
+
+/// Rough size of a field value without serializing it: string lengths, plus
+/// the size of a `Value` for every value
+fn value_size(value: &Value) -> usize {
+    let own = std::mem::size_of::<Value>();
+    match value {
+        Value::String(text) => own + text.len(),
+        Value::Array(items) => own + items.iter().map(value_size).sum::<usize>(),
+        Value::Object(map) => own + map.iter().map(|(key, value)| key.len() + value_size(value)).sum::<usize>(),
+        _ => own,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FormatType;
+    use chrono::TimeZone;
+    
+    fn event(second: Option<u32>, message: &str) -> CanonicalEvent {
+        let mut event = CanonicalEvent::new(message.to_string(), message.to_string(), FormatType::PlainText);
+        event.timestamp = second.map(|s| Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, s).unwrap());
+        event
+    }
+    
+    fn merge(sources: Vec<Vec<CanonicalEvent>>, config: MergeConfig) -> (Vec<String>, usize) {
+        let mut sources: Vec<_> = sources.into_iter().map(|events| events.into_iter()).collect();
+        let mut merged = merge_by_timestamp(sources.len(), config, |i| sources[i].next().map(Ok));
+        let messages = merged.by_ref().map(|event| event.unwrap().message).collect();
+        (messages, merged.spilled_runs())
+    }
+    
+    #[test]
+    fn test_merges_sorted_sources() {
+        let config = MergeConfig { reorder_window: Duration::zero(), ..MergeConfig::default() };
+        let (messages, spilled) = merge(vec![
+            vec![event(Some(1), "a1"), event(Some(4), "a4"), event(Some(5), "a5")],
+            vec![event(Some(2), "b2"), event(Some(3), "b3"), event(Some(6), "b6")],
+            vec![],
+        ], config);
+        
+        assert_eq!(messages, ["a1", "b2", "b3", "a4", "a5", "b6"]);
+        assert_eq!(spilled, 0);
+    }
+    
+    #[test]
+    fn test_reorders_within_window_and_keeps_untimed_events_in_place() {
+        let config = MergeConfig { reorder_window: Duration::seconds(5), ..MergeConfig::default() };
+        let (messages, _) = merge(vec![
+            vec![event(Some(10), "a10"), event(Some(8), "a8"), event(None, "a8-trace"), event(Some(20), "a20")],
+            vec![event(Some(9), "b9"), event(Some(12), "b12")],
+        ], config);
+        
+        assert_eq!(messages, ["a8", "a8-trace", "b9", "a10", "b12", "a20"]);
+    }
+    
+    #[test]
+    fn test_spills_sorted_runs_when_window_exceeds_memory() {
+        let config = MergeConfig { reorder_window: Duration::seconds(60), memory_limit_bytes: 1 };
+        let sources = (0..3)
+            .map(|source| (0..10).map(|i| event(Some(i * 3 + source), &format!("{}", i * 3 + source))).collect())
+            .collect();
+        let (messages, spilled) = merge(sources, config);
+        
+        let expected: Vec<String> = (0..30).map(|i| i.to_string()).collect();
+        assert_eq!(messages, expected);
+        assert!(spilled > 0);
+    }
+}
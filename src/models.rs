@@ -1,7 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::error::ParseError;
+use crate::timestamp::Recognized;
 
 /// Parsing statistics for monitoring and debugging
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -165,7 +167,10 @@ pub struct SourceMetadata {
     pub file: Option<String>,
     pub stream: Option<String>,
     pub host: Option<String>,
+    /// Byte offset of the event's first line in its input
     pub offset: Option<u64>,
+    /// 1-based line number of the event's first line
+    pub line: Option<usize>,
 }
 
 impl Default for SourceMetadata {
@@ -175,19 +180,42 @@ impl Default for SourceMetadata {
             stream: None,
             host: None,
             offset: None,
+            line: None,
         }
     }
 }
 
 /// Detected log format types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FormatType {
     Json,
     Logfmt,
     Pattern,
     TimestampLevel,
+    /// RFC 5424 syslog with structured data
+    Rfc5424,
+    /// Kubernetes CRI container log: `TIMESTAMP STREAM P|F PAYLOAD`
+    Cri,
+    /// Docker json-file container log: `{"log":..,"stream":..,"time":..}`
+    DockerJson,
+    /// Kubernetes klog header: `Lmmdd hh:mm:ss.uuuuuu threadid file:line] msg`
+    Klog,
     Profile(ProfileType),
     PlainText,
+    /// Parsed by a parser registered under this name
+    Custom(ParserName),
+}
+
+/// Name a parser is registered under, shared by the events it parses
+pub type ParserName = Arc<str>;
+
+impl std::fmt::Display for FormatType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatType::Custom(name) => f.write_str(name),
+            other => write!(f, "{:?}", other),
+        }
+    }
 }
 
 /// User-defined profile types
@@ -200,6 +228,15 @@ pub enum ProfileType {
     Syslog,
 }
 
+/// A timestamp as written in the log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogTimestamp {
+    /// Carried an explicit UTC offset (or was an epoch value)
+    Explicit(DateTime<Utc>),
+    /// Wall-clock time without an offset, local to the source's timezone
+    Naive(NaiveDateTime),
+}
+
 /// Canonical event model - unified representation for all parsed log events
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CanonicalEvent {
@@ -224,6 +261,12 @@ pub struct CanonicalEvent {
     /// True if parsing encountered errors but continued
     pub parse_error: Option<bool>,
     
+    /// True if the timestamp carried no year and the year was inferred
+    pub year_inferred: Option<bool>,
+    
+    /// True if the timestamp carried no UTC offset and the default timezone was assumed
+    pub offset_assumed: Option<bool>,
+    
     /// Detected format type for debugging and optimization
     pub format_type: FormatType,
 }
@@ -239,6 +282,8 @@ impl CanonicalEvent {
             raw,
             source: SourceMetadata::default(),
             parse_error: None,
+            year_inferred: None,
+            offset_assumed: None,
             format_type,
         }
     }
@@ -253,6 +298,8 @@ impl CanonicalEvent {
             raw,
             source: SourceMetadata::default(),
             parse_error: Some(true),
+            year_inferred: None,
+            offset_assumed: None,
             format_type: FormatType::PlainText,
         }
     }
@@ -267,6 +314,29 @@ impl CanonicalEvent {
         self.timestamp = Some(timestamp);
     }
     
+    /// Set the timestamp as parsed, recording whether its offset was explicit;
+    /// naive times are taken as UTC until a default timezone is applied
+    pub fn set_log_timestamp(&mut self, timestamp: LogTimestamp) {
+        match timestamp {
+            LogTimestamp::Explicit(timestamp) => {
+                self.timestamp = Some(timestamp);
+                self.offset_assumed = Some(false);
+            }
+            LogTimestamp::Naive(naive) => {
+                self.timestamp = Some(naive.and_utc());
+                self.offset_assumed = Some(true);
+            }
+        }
+    }
+    
+    /// Set a timestamp the catalog recognized, marking whether its year is to be inferred
+    pub fn set_recognized_timestamp(&mut self, recognized: Recognized) {
+        self.set_log_timestamp(recognized.timestamp);
+        if recognized.year_inferred {
+            self.year_inferred = Some(true);
+        }
+    }
+    
     /// Set the log level
     pub fn set_level(&mut self, level: LogLevel) {
         self.level = Some(level);
@@ -307,7 +377,7 @@ mod tests {
                 FormatType::TimestampLevel,
                 FormatType::PlainText,
             ];
-            *g.choose(&formats).unwrap()
+            g.choose(&formats).unwrap().clone()
         }
     }
     
@@ -318,6 +388,7 @@ mod tests {
                 stream: if bool::arbitrary(g) { Some(String::arbitrary(g)) } else { None },
                 host: if bool::arbitrary(g) { Some(String::arbitrary(g)) } else { None },
                 offset: if bool::arbitrary(g) { Some(u64::arbitrary(g)) } else { None },
+                line: if bool::arbitrary(g) { Some(usize::arbitrary(g)) } else { None },
             }
         }
     }
@@ -331,7 +402,7 @@ mod tests {
         raw: String,
         format_type: FormatType,
     ) -> bool {
-        let event = CanonicalEvent::new(message.clone(), raw.clone(), format_type);
+        let event = CanonicalEvent::new(message.clone(), raw.clone(), format_type.clone());
         
         // The parsed result should always contain a canonical event with required structure
         // Message field must be populated (requirement 1.2)
@@ -0,0 +1,533 @@
+use crate::error::ParseError;
+use crate::event_ref::{CanonicalEventRef, ValueRef};
+use crate::models::CanonicalEvent;
+use crate::parsers::CriParser;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io;
+
+/// Rules for grouping physical lines into a single log event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultilineConfig {
+    /// Enable multi-line assembly
+    pub enabled: bool,
+    
+    /// Lines starting with whitespace continue the previous event
+    pub indent_continuation: bool,
+    
+    /// Only lines starting with a timestamp (or a JSON object) begin a new event
+    pub timestamp_anchor: bool,
+    
+    /// Regex matching the first line of an event; every other line is a continuation
+    pub start_pattern: Option<String>,
+    
+    /// Regex matching the last line of an event
+    pub end_pattern: Option<String>,
+    
+    /// Additional regexes marking continuation lines (e.g. "Caused by:")
+    pub continuation_patterns: Vec<String>,
+    
+    /// Maximum number of lines in one event before it is force-closed
+    pub max_lines: usize,
+}
+
+impl Default for MultilineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            indent_continuation: true,
+            timestamp_anchor: false,
+            start_pattern: None,
+            end_pattern: None,
+            continuation_patterns: vec![
+                r"^Caused by: ".to_string(),
+                r"^\.\.\. \d+ (more|common frames omitted)".to_string(),
+                r"^Traceback \(most recent call last\):".to_string(),
+                r"^goroutine \d+ \[".to_string(),
+            ],
+            max_lines: 500,
+        }
+    }
+}
+
+impl MultilineConfig {
+    /// Configuration that passes every line through as its own event
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// A physical line and where it was read from; `T` is `&str` for lines
+/// borrowed from a mapped file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLine<T = String> {
+    pub text: T,
+    /// Byte offset of the line's first byte in its input
+    pub offset: Option<u64>,
+    /// 1-based line number in its input, when known
+    pub number: Option<usize>,
+}
+
+impl From<String> for SourceLine {
+    fn from(text: String) -> Self {
+        Self { text, offset: None, number: None }
+    }
+}
+
+impl<'a> From<&'a str> for SourceLine<&'a str> {
+    fn from(text: &'a str) -> Self {
+        Self { text, offset: None, number: None }
+    }
+}
+
+impl From<SourceLine<&str>> for SourceLine {
+    fn from(line: SourceLine<&str>) -> Self {
+        Self { text: line.text.to_string(), offset: line.offset, number: line.number }
+    }
+}
+
+/// Consecutive lines that form one log event
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineGroup<T = String> {
+    /// Physical lines, first line first
+    pub lines: Vec<T>,
+    /// 1-based line number of the first line
+    pub line_number: usize,
+    /// Byte offset of the first line in its input
+    pub offset: Option<u64>,
+}
+
+/// Line group borrowing its lines from the input
+pub type LineGroupRef<'a> = LineGroup<&'a str>;
+
+impl LineGroupRef<'_> {
+    /// Copy the lines out of the input
+    pub fn into_owned(self) -> LineGroup {
+        LineGroup {
+            lines: self.lines.into_iter().map(str::to_string).collect(),
+            line_number: self.line_number,
+            offset: self.offset,
+        }
+    }
+}
+
+impl<T: AsRef<str>> LineGroup<T> {
+    /// True for a group holding a single blank line
+    pub fn is_blank(&self) -> bool {
+        self.lines.iter().all(|line| line.as_ref().trim().is_empty())
+    }
+    
+    /// Continuation lines after the first
+    pub fn continuation(&self) -> &[T] {
+        &self.lines[1..]
+    }
+}
+
+/// Incrementally groups lines according to a `MultilineConfig`
+#[derive(Debug, Clone)]
+pub struct LineAssembler<T = String> {
+    enabled: bool,
+    indent_continuation: bool,
+    timestamp_anchor: bool,
+    start: Option<Regex>,
+    end: Option<Regex>,
+    continuation: Vec<Regex>,
+    timestamp_start: Regex,
+    max_lines: usize,
+    pending: Option<LineGroup<T>>,
+    ready: VecDeque<LineGroup<T>>,
+    lines_seen: usize,
+}
+
+impl LineAssembler {
+    /// Compile the rules in a multi-line configuration
+    pub fn new(config: &MultilineConfig) -> Result<Self, ParseError> {
+        let compile = |parameter: &str, pattern: &str| {
+            Regex::new(pattern).map_err(|e| ParseError::ConfigurationError {
+                parameter: format!("multiline.{}", parameter),
+                error_message: format!("Invalid regex '{}': {}", pattern, e),
+            })
+        };
+        
+        let start = config.start_pattern.as_deref()
+            .map(|p| compile("start_pattern", p))
+            .transpose()?;
+        let end = config.end_pattern.as_deref()
+            .map(|p| compile("end_pattern", p))
+            .transpose()?;
+        let continuation = config.continuation_patterns.iter()
+            .map(|p| compile("continuation_patterns", p))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(Self {
+            enabled: config.enabled,
+            indent_continuation: config.indent_continuation,
+            timestamp_anchor: config.timestamp_anchor,
+            start,
+            end,
+            continuation,
+            timestamp_start: Regex::new(concat!(
+                r"^(\{|<\d{1,3}>|\[?\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}",
+                r"|\[?[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}|\d{2}-\d{2} \d{2}:\d{2}:\d{2}",
+                r"|\d{10}(\.\d+)?\b)",
+            )).unwrap(),
+            max_lines: config.max_lines.max(1),
+            pending: None,
+            ready: VecDeque::new(),
+            lines_seen: 0,
+        })
+    }
+}
+
+impl<T: AsRef<str>> LineAssembler<T> {
+    /// An empty assembler with the same rules, grouping lines of another type
+    pub fn for_lines<U>(&self) -> LineAssembler<U> {
+        LineAssembler {
+            enabled: self.enabled,
+            indent_continuation: self.indent_continuation,
+            timestamp_anchor: self.timestamp_anchor,
+            start: self.start.clone(),
+            end: self.end.clone(),
+            continuation: self.continuation.clone(),
+            timestamp_start: self.timestamp_start.clone(),
+            max_lines: self.max_lines,
+            pending: None,
+            ready: VecDeque::new(),
+            lines_seen: 0,
+        }
+    }
+    
+    /// Feed the next physical line
+    pub fn push_line(&mut self, line: impl Into<SourceLine<T>>) {
+        let SourceLine { text: line, offset, number } = line.into();
+        self.lines_seen = number.unwrap_or(self.lines_seen + 1);
+        
+        let continues = match &self.pending {
+            Some(group) => self.continues(group, line.as_ref()),
+            None => false,
+        };
+        
+        if continues {
+            if let Some(group) = self.pending.as_mut() {
+                group.lines.push(line);
+            }
+        } else {
+            if let Some(group) = self.pending.take() {
+                self.ready.push_back(group);
+            }
+            self.pending = Some(LineGroup {
+                lines: vec![line],
+                line_number: self.lines_seen,
+                offset,
+            });
+        }
+        
+        // Close the group immediately when it cannot grow any further
+        let closed = match &self.pending {
+            Some(group) => !self.awaits_partial(group) && (!self.enabled || self.ends(group)),
+            None => false,
+        };
+        if closed {
+            if let Some(group) = self.pending.take() {
+                self.ready.push_back(group);
+            }
+        }
+    }
+    
+    /// Take the next completed group, if any
+    pub fn next_group(&mut self) -> Option<LineGroup<T>> {
+        self.ready.pop_front()
+    }
+    
+    /// Close the pending group (end of input or idle stream)
+    pub fn flush(&mut self) {
+        if let Some(group) = self.pending.take() {
+            self.ready.push_back(group);
+        }
+    }
+    
+    /// True when a partially assembled group is waiting for more lines
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+    
+    /// False when no line can be told to begin an event (only an end marker is
+    /// set), so input cannot be split without reading it from the start
+    pub fn finds_event_starts(&self) -> bool {
+        !self.enabled || self.start.is_some() || self.end.is_none()
+    }
+    
+    /// True when `line` begins a new event whatever came before it, so input can
+    /// be split there without changing how lines are grouped
+    pub fn starts_event(&self, line: &str) -> bool {
+        if !self.enabled || line.trim().is_empty() {
+            return true;
+        }
+        if let Some(start) = &self.start {
+            return start.is_match(line);
+        }
+        if self.end.is_some() {
+            return false;
+        }
+        if self.timestamp_anchor {
+            return self.timestamp_start.is_match(line);
+        }
+        
+        let continues = (self.indent_continuation && line.starts_with([' ', '\t']))
+            || self.continuation.iter().any(|re| re.is_match(line));
+        !continues
+    }
+    
+    /// `starts_event` for a line read right after `previous`, which may be a
+    /// partial CRI line the runtime continues on this one
+    pub fn starts_event_after(&self, previous: Option<&str>, line: &str) -> bool {
+        !previous.is_some_and(|previous| CriParser::continues(previous, line)) && self.starts_event(line)
+    }
+    
+    /// Whether the group ends in a partial CRI line, so its next line is joined
+    /// to it whatever the rules
+    fn awaits_partial(&self, group: &LineGroup<T>) -> bool {
+        group.lines.len() < self.max_lines
+            && group.lines.last().is_some_and(|last| CriParser::is_partial(last.as_ref()))
+    }
+    
+    /// Decide whether `line` continues `group`
+    fn continues(&self, group: &LineGroup<T>, line: &str) -> bool {
+        if group.lines.len() >= self.max_lines {
+            return false;
+        }
+        if let Some(last) = group.lines.last() {
+            if CriParser::continues(last.as_ref(), line) {
+                return true;
+            }
+        }
+        if !self.enabled || line.trim().is_empty() || group.is_blank() {
+            return false;
+        }
+        
+        if let Some(start) = &self.start {
+            return !start.is_match(line);
+        }
+        
+        // Without a start marker the group stays open until the end marker
+        if self.end.is_some() {
+            return true;
+        }
+        
+        if self.timestamp_anchor {
+            return !self.timestamp_start.is_match(line);
+        }
+        
+        (self.indent_continuation && line.starts_with([' ', '\t']))
+            || self.continuation.iter().any(|re| re.is_match(line))
+    }
+    
+    /// Decide whether `group` is complete after its last line
+    fn ends(&self, group: &LineGroup<T>) -> bool {
+        if group.is_blank() {
+            return true;
+        }
+        match (&self.end, group.lines.last()) {
+            (Some(end), Some(last)) => end.is_match(last.as_ref()),
+            _ => false,
+        }
+    }
+}
+
+impl Default for LineAssembler {
+    fn default() -> Self {
+        Self::new(&MultilineConfig::default()).unwrap()
+    }
+}
+
+/// Iterator adapter turning a line iterator into line groups
+pub struct GroupedLines<I, T = String> {
+    lines: I,
+    assembler: LineAssembler<T>,
+    finished: bool,
+}
+
+impl<I, L, T> GroupedLines<I, T>
+where
+    I: Iterator<Item = io::Result<L>>,
+    L: Into<SourceLine<T>>,
+    T: AsRef<str>,
+{
+    pub fn new(lines: I, assembler: LineAssembler<T>) -> Self {
+        Self {
+            lines,
+            assembler,
+            finished: false,
+        }
+    }
+    
+    /// The underlying line iterator
+    pub fn lines(&self) -> &I {
+        &self.lines
+    }
+}
+
+impl<I, L, T> Iterator for GroupedLines<I, T>
+where
+    I: Iterator<Item = io::Result<L>>,
+    L: Into<SourceLine<T>>,
+    T: AsRef<str>,
+{
+    type Item = io::Result<LineGroup<T>>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(group) = self.assembler.next_group() {
+                return Some(Ok(group));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.lines.next() {
+                Some(Ok(line)) => self.assembler.push_line(line),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.assembler.flush();
+                    self.finished = true;
+                }
+            }
+        }
+    }
+}
+
+/// Fold the continuation lines of a group into an event parsed from its first line
+pub fn attach_continuation(event: &mut CanonicalEvent, group: &LineGroup) {
+    if group.lines.len() < 2 {
+        return;
+    }
+    
+    event.raw = group.lines.join("\n");
+    let trace: Vec<serde_json::Value> = group.continuation().iter()
+        .map(|line| serde_json::Value::String(line.clone()))
+        .collect();
+    event.add_field("stack_trace".to_string(), trace);
+}
+
+/// `attach_continuation` for an event borrowing from the group's lines; only the
+/// joined raw text is copied
+pub fn attach_continuation_ref<'a>(event: &mut CanonicalEventRef<'a>, group: &LineGroupRef<'a>) {
+    if group.lines.len() < 2 {
+        return;
+    }
+    
+    event.raw = Cow::Owned(group.lines.join("\n"));
+    let trace = group.continuation().iter()
+        .map(|line| ValueRef::from(*line))
+        .collect();
+    event.add_field("stack_trace", ValueRef::Array(trace));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn group_all(config: &MultilineConfig, input: &str) -> Vec<LineGroup> {
+        let lines = input.lines().map(|l| Ok(l.to_string()));
+        GroupedLines::new(lines, LineAssembler::new(config).unwrap())
+            .map(|g| g.unwrap())
+            .collect()
+    }
+    
+    #[test]
+    fn test_indented_java_trace() {
+        let input = "2025-01-01 12:00:00 ERROR Request failed\n\
+                     java.lang.IllegalStateException: boom\n\
+                     \tat com.example.Foo.bar(Foo.java:10)\n\
+                     \tat com.example.Main.main(Main.java:3)\n\
+                     Caused by: java.io.IOException: closed\n\
+                     \t... 2 more\n\
+                     2025-01-01 12:00:01 INFO next";
+        
+        let groups = group_all(&MultilineConfig { timestamp_anchor: true, ..Default::default() }, input);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].lines.len(), 6);
+        assert_eq!(groups[1].line_number, 7);
+        
+        // Indentation alone splits off the exception line
+        let groups = group_all(&MultilineConfig::default(), input);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[1].lines.len(), 5);
+    }
+    
+    #[test]
+    fn test_blank_lines_stay_separate() {
+        let groups = group_all(&MultilineConfig::default(), "first\n\n  indented\nsecond");
+        assert_eq!(groups.len(), 4);
+        assert!(groups[1].is_blank());
+    }
+    
+    #[test]
+    fn test_start_and_end_patterns() {
+        let config = MultilineConfig {
+            start_pattern: Some(r"^BEGIN".to_string()),
+            ..Default::default()
+        };
+        let groups = group_all(&config, "BEGIN a\nx\ny\nBEGIN b\nz");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].lines, vec!["BEGIN a", "x", "y"]);
+        
+        let config = MultilineConfig {
+            end_pattern: Some(r";$".to_string()),
+            ..Default::default()
+        };
+        let groups = group_all(&config, "select *\nfrom t;\nselect 1;");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].lines.len(), 2);
+    }
+    
+    #[test]
+    fn test_disabled_and_max_lines() {
+        let groups = group_all(&MultilineConfig::disabled(), "a\n  b\n  c");
+        assert_eq!(groups.len(), 3);
+        
+        let config = MultilineConfig { max_lines: 2, ..Default::default() };
+        let groups = group_all(&config, "a\n  b\n  c");
+        assert_eq!(groups.len(), 2);
+    }
+    
+    #[test]
+    fn test_partial_cri_lines_join_even_when_disabled() {
+        let input = "2024-01-01T00:00:00.1Z stdout P first \n\
+                     2024-01-01T00:00:00.2Z stderr F interleaved\n\
+                     2024-01-01T00:00:00.3Z stdout F second\n\
+                     2024-01-01T00:00:00.4Z stdout F next";
+        let groups = group_all(&MultilineConfig::disabled(), input);
+        assert_eq!(groups.iter().map(|g| g.lines.len()).collect::<Vec<_>>(), [1, 1, 1, 1]);
+        
+        let input = "2024-01-01T00:00:00.1Z stdout P first \n\
+                     2024-01-01T00:00:00.3Z stdout F second\n\
+                     2024-01-01T00:00:00.4Z stdout F next";
+        let groups = group_all(&MultilineConfig::disabled(), input);
+        assert_eq!(groups.iter().map(|g| g.lines.len()).collect::<Vec<_>>(), [2, 1]);
+        
+        let assembler = LineAssembler::default();
+        let lines: Vec<&str> = input.lines().collect();
+        assert!(!assembler.starts_event_after(Some(lines[0]), lines[1]));
+        assert!(assembler.starts_event_after(Some(lines[1]), lines[2]));
+    }
+    
+    #[test]
+    fn test_invalid_pattern_is_configuration_error() {
+        let config = MultilineConfig {
+            start_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        match LineAssembler::new(&config) {
+            Err(ParseError::ConfigurationError { parameter, .. }) => {
+                assert_eq!(parameter, "multiline.start_pattern");
+            }
+            other => panic!("Expected configuration error, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::models::*;
 use crate::parse_result::ParseResult;
-use crate::parsers::{LogParser, JsonParser, LogfmtParser, PatternParser, PlainTextParser};
+use crate::parsers::{LogParser, ParserRegistry};
 use crate::classifier::{TangoFormatClassifier, FormatClassifier, FormatCache};
 use crate::statistics::{ParsingStatistics, StatisticsMonitor};
 use std::collections::HashMap;
@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 
 /// Configuration for parallel processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ParallelConfig {
     /// Number of worker threads to use (0 = auto-detect)
     pub num_threads: usize,
@@ -44,12 +45,7 @@ impl Default for ParallelConfig {
 pub struct ThreadSafeParsingStructures {
     /// Shared format cache (thread-safe)
     shared_cache: Arc<RwLock<FormatCache>>,
-    /// Thread-local parser instances (not shared)
-    json_parser: JsonParser,
-    logfmt_parser: LogfmtParser,
-    pattern_parser: PatternParser,
-    plain_text_parser: PlainTextParser,
-    /// Thread-local classifier (uses shared cache)
+    /// Thread-local classifier (uses shared cache); its registry holds the parser instances
     classifier: TangoFormatClassifier,
 }
 
@@ -57,23 +53,21 @@ impl ThreadSafeParsingStructures {
     pub fn new(shared_cache: Arc<RwLock<FormatCache>>) -> Self {
         Self {
             shared_cache,
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
-            plain_text_parser: PlainTextParser::new(),
             classifier: TangoFormatClassifier::new(),
         }
     }
     
+    /// Detect and parse with the parsers in `registry`
+    pub fn with_registry(mut self, registry: ParserRegistry) -> Self {
+        self.classifier.set_registry(registry);
+        self
+    }
+    
     /// Get the appropriate parser for a format type
     pub fn get_parser(&self, format_type: FormatType) -> &dyn LogParser {
-        match format_type {
-            FormatType::Json => &self.json_parser,
-            FormatType::Logfmt => &self.logfmt_parser,
-            FormatType::TimestampLevel | FormatType::Pattern => &self.pattern_parser,
-            FormatType::PlainText => &self.plain_text_parser,
-            FormatType::Profile(_) => &self.plain_text_parser, // Fallback for profiles
-        }
+        let registry = self.classifier.registry();
+        // Formats without a registered parser (profiles) fall back to plain text
+        registry.for_format(format_type).unwrap_or_else(|| registry.plain_text()).parser()
     }
     
     /// Detect format with shared caching
@@ -82,7 +76,7 @@ impl ThreadSafeParsingStructures {
         {
             let mut cache = self.shared_cache.write();
             if let Some(entry) = cache.get(source) {
-                return entry.format_type;
+                return entry.format_type.clone();
             }
         }
         
@@ -94,7 +88,7 @@ impl ThreadSafeParsingStructures {
             let mut cache = self.shared_cache.write();
             cache.put(
                 source.to_string(),
-                format_type,
+                format_type.clone(),
                 0.8,
                 None,
                 HashMap::new(),
@@ -125,6 +119,8 @@ pub struct ParallelParser {
     config: ParallelConfig,
     shared_cache: Arc<RwLock<FormatCache>>,
     global_statistics: Arc<Mutex<StatisticsMonitor>>,
+    /// Parsers each worker's structures detect and parse with
+    registry: ParserRegistry,
 }
 
 impl ParallelParser {
@@ -145,10 +141,17 @@ impl ParallelParser {
         Self {
             shared_cache: Arc::new(RwLock::new(FormatCache::new())),
             global_statistics: Arc::new(Mutex::new(StatisticsMonitor::new())),
+            registry: ParserRegistry::new(),
             config,
         }
     }
     
+    /// Detect and parse with the parsers in `registry`
+    pub fn with_parser_registry(mut self, registry: ParserRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+    
     /// Parse multiple independent log streams in parallel
     pub fn parse_streams_parallel<R: Read + Send + 'static>(
         &self,
@@ -176,7 +179,8 @@ impl ParallelParser {
         let mut local_stats = StatisticsMonitor::new();
         
         // Create thread-safe parsing structures
-        let parsing_structures = ThreadSafeParsingStructures::new(self.shared_cache.clone());
+        let parsing_structures = ThreadSafeParsingStructures::new(self.shared_cache.clone())
+            .with_registry(self.registry.clone());
         
         loop {
             let mut line = String::new();
@@ -200,9 +204,9 @@ impl ParallelParser {
             // Update local statistics
             if result.success {
                 if let Some(processing_time) = result.processing_time_micros {
-                    local_stats.record_success(result.event.format_type, processing_time);
+                    local_stats.record_success(result.event.format_type.clone(), processing_time);
                 } else {
-                    local_stats.record_success(result.event.format_type, 0);
+                    local_stats.record_success(result.event.format_type.clone(), 0);
                 }
             } else {
                 if let Some(error) = &result.error {
@@ -245,7 +249,8 @@ impl ParallelParser {
         let results: Vec<ParseResult> = work_items
             .into_par_iter()
             .map(|work_item| {
-                let parsing_structures = ThreadSafeParsingStructures::new(self.shared_cache.clone());
+                let parsing_structures = ThreadSafeParsingStructures::new(self.shared_cache.clone())
+                    .with_registry(self.registry.clone());
                 self.parse_line_with_structures(
                     &parsing_structures,
                     &work_item.line,
@@ -260,9 +265,9 @@ impl ParallelParser {
         for result in &results {
             if result.success {
                 if let Some(processing_time) = result.processing_time_micros {
-                    aggregated_stats.record_success(result.event.format_type, processing_time);
+                    aggregated_stats.record_success(result.event.format_type.clone(), processing_time);
                 } else {
-                    aggregated_stats.record_success(result.event.format_type, 0);
+                    aggregated_stats.record_success(result.event.format_type.clone(), 0);
                 }
             } else {
                 if let Some(error) = &result.error {
@@ -322,9 +327,11 @@ impl ParallelParser {
             let work_recv = work_receiver.clone();
             let result_send = result_sender.clone();
             let shared_cache = self.shared_cache.clone();
+            let registry = self.registry.clone();
             
             let handle = thread::spawn(move || {
-                let parsing_structures = ThreadSafeParsingStructures::new(shared_cache);
+                let parsing_structures = ThreadSafeParsingStructures::new(shared_cache)
+                    .with_registry(registry);
                 
                 while let Ok(work_item) = work_recv.recv() {
                     let result = Self::parse_line_with_structures_static(
@@ -355,9 +362,9 @@ impl ParallelParser {
             // Update statistics
             if result.success {
                 if let Some(processing_time) = result.processing_time_micros {
-                    aggregated_stats.record_success(result.event.format_type, processing_time);
+                    aggregated_stats.record_success(result.event.format_type.clone(), processing_time);
                 } else {
-                    aggregated_stats.record_success(result.event.format_type, 0);
+                    aggregated_stats.record_success(result.event.format_type.clone(), 0);
                 }
             } else {
                 if let Some(error) = &result.error {
@@ -409,7 +416,7 @@ impl ParallelParser {
             let mut cache = parsing_structures.shared_cache.write();
             if let Some(entry) = cache.get(source) {
                 // Use cached format type to get the appropriate parser
-                let parser = parsing_structures.get_parser(entry.format_type);
+                let parser = parsing_structures.get_parser(entry.format_type.clone());
                 let cached_result = parser.parse(line);
                 if cached_result.success {
                     let mut result = cached_result.with_line_number(line_number);
@@ -421,93 +428,23 @@ impl ParallelParser {
             }
         }
         
-        // Implement the fallback chain: JSON → logfmt → timestamp patterns → plain text
-        
-        // Stage 1: Try JSON parsing first
-        if line.trim_start().starts_with('{') {
-            let json_result = parsing_structures.json_parser.parse(line);
-            if json_result.success {
-                // Update shared cache with successful detection
-                {
-                    let mut cache = parsing_structures.shared_cache.write();
-                    cache.put(
-                        source.to_string(),
-                        FormatType::Json,
-                        json_result.confidence,
-                        None,
-                        HashMap::new(),
-                    );
-                }
-                
-                let mut result = json_result.with_line_number(line_number);
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                result.processing_time_micros = Some(processing_time);
-                return result;
-            }
-        }
+        // Fall back to the registered parsers in priority order:
+        // JSON → RFC 5424 → logfmt → timestamp patterns → plain text, with custom parsers around them
+        let (format_type, parsed) = parsing_structures.classifier.registry().parse_first(line);
         
-        // Stage 2: Try logfmt parsing
-        if parsing_structures.logfmt_parser.can_parse(line) {
-            let logfmt_result = parsing_structures.logfmt_parser.parse(line);
-            if logfmt_result.success {
-                // Update shared cache with successful detection
-                {
-                    let mut cache = parsing_structures.shared_cache.write();
-                    cache.put(
-                        source.to_string(),
-                        FormatType::Logfmt,
-                        logfmt_result.confidence,
-                        None,
-                        HashMap::new(),
-                    );
-                }
-                
-                let mut result = logfmt_result.with_line_number(line_number);
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                result.processing_time_micros = Some(processing_time);
-                return result;
-            }
-        }
-        
-        // Stage 3: Try timestamp+level pattern parsing
-        if parsing_structures.pattern_parser.can_parse(line) {
-            let pattern_result = parsing_structures.pattern_parser.parse(line);
-            if pattern_result.success {
-                // Update shared cache with successful detection
-                {
-                    let mut cache = parsing_structures.shared_cache.write();
-                    cache.put(
-                        source.to_string(),
-                        FormatType::TimestampLevel,
-                        pattern_result.confidence,
-                        None,
-                        HashMap::new(),
-                    );
-                }
-                
-                let mut result = pattern_result.with_line_number(line_number);
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                result.processing_time_micros = Some(processing_time);
-                return result;
-            }
-        }
-        
-        // Stage 4: Fall back to plain text (always succeeds)
-        let plain_result = parsing_structures.plain_text_parser.parse(line);
-        
-        // Update shared cache with plain text fallback
+        // Update shared cache with the detected format
         {
             let mut cache = parsing_structures.shared_cache.write();
             cache.put(
                 source.to_string(),
-                FormatType::PlainText,
-                plain_result.confidence,
+                format_type,
+                parsed.confidence,
                 None,
                 HashMap::new(),
             );
         }
         
-        let mut result = plain_result.with_line_number(line_number);
+        let mut result = parsed.with_line_number(line_number);
         let processing_time = start_time.elapsed().as_micros() as u64;
         result.processing_time_micros = Some(processing_time);
         result
@@ -496,7 +496,7 @@ fn test_stress_high_concurrency() {
     let format_counts: std::collections::HashMap<FormatType, usize> = result.results
         .iter()
         .fold(std::collections::HashMap::new(), |mut acc, r| {
-            *acc.entry(r.event.format_type).or_insert(0) += 1;
+            *acc.entry(r.event.format_type.clone()).or_insert(0) += 1;
             acc
         });
     
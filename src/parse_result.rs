@@ -1,5 +1,8 @@
-use crate::models::CanonicalEvent;
+use crate::models::{CanonicalEvent, FormatType};
 use crate::error::ParseError;
+use crate::event_ref::CanonicalEventRef;
+use crate::timestamp::TimestampFormat;
+use std::borrow::Cow;
 
 /// Result of a parsing operation with enhanced error reporting
 #[derive(Debug, Clone)]
@@ -10,6 +13,13 @@ pub struct ParseResult {
     pub confidence: f64,
     pub line_number: Option<usize>,
     pub processing_time_micros: Option<u64>,
+    /// Formats the line was tried as before parsing failed
+    pub attempted_formats: Vec<FormatType>,
+    /// For a line detection left to plain text, why the most plausible format
+    /// rejected it (set when rejects are written)
+    pub fallback_error: Option<ParseError>,
+    /// Catalog format the event's timestamp was recognized in
+    pub timestamp_format: Option<TimestampFormat>,
 }
 
 impl ParseResult {
@@ -22,6 +32,9 @@ impl ParseResult {
             confidence,
             line_number: None,
             processing_time_micros: None,
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
         }
     }
     
@@ -34,6 +47,9 @@ impl ParseResult {
             confidence,
             line_number: None,
             processing_time_micros: Some(processing_time_micros),
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
         }
     }
     
@@ -47,6 +63,9 @@ impl ParseResult {
             confidence: 0.0,
             line_number: None,
             processing_time_micros: None,
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
         }
     }
     
@@ -65,6 +84,9 @@ impl ParseResult {
             confidence: 0.0,
             line_number,
             processing_time_micros,
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
         }
     }
     
@@ -80,6 +102,18 @@ impl ParseResult {
         self
     }
     
+    /// Record a format the line was tried as
+    pub fn with_attempted_format(mut self, format_type: FormatType) -> Self {
+        self.attempted_formats.push(format_type);
+        self
+    }
+    
+    /// Record the catalog format the timestamp was recognized in
+    pub fn with_timestamp_format(mut self, timestamp_format: Option<TimestampFormat>) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+    
     /// Get a detailed error description including context
     pub fn detailed_error_description(&self) -> Option<String> {
         if let Some(error) = &self.error {
@@ -98,4 +132,101 @@ impl ParseResult {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// Result of parsing into an event that borrows from the parsed line
+#[derive(Debug, Clone)]
+pub struct ParseResultRef<'a> {
+    pub success: bool,
+    pub event: CanonicalEventRef<'a>,
+    pub error: Option<ParseError>,
+    pub confidence: f64,
+    pub line_number: Option<usize>,
+    /// Formats the line was tried as before parsing failed
+    pub attempted_formats: Vec<FormatType>,
+    /// For a line detection left to plain text, why the most plausible format
+    /// rejected it (set when rejects are written)
+    pub fallback_error: Option<ParseError>,
+    /// Catalog format the event's timestamp was recognized in
+    pub timestamp_format: Option<TimestampFormat>,
+}
+
+impl<'a> ParseResultRef<'a> {
+    /// Create a successful parse result
+    pub fn success(event: CanonicalEventRef<'a>, confidence: f64) -> Self {
+        Self {
+            success: true,
+            event,
+            error: None,
+            confidence,
+            line_number: None,
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
+        }
+    }
+    
+    /// Create a failed parse result; the event carries the raw line and the error message
+    pub fn failure(raw: &'a str, error: ParseError) -> Self {
+        let mut event = CanonicalEventRef::new(Cow::Owned(error.to_string()), raw, FormatType::PlainText);
+        event.parse_error = Some(true);
+        Self {
+            success: false,
+            event,
+            error: Some(error),
+            confidence: 0.0,
+            line_number: None,
+            attempted_formats: Vec::new(),
+            fallback_error: None,
+            timestamp_format: None,
+        }
+    }
+    
+    /// Set line number for this parse result
+    pub fn with_line_number(mut self, line_number: usize) -> Self {
+        self.line_number = Some(line_number);
+        self
+    }
+    
+    /// Record a format the line was tried as
+    pub fn with_attempted_format(mut self, format_type: FormatType) -> Self {
+        self.attempted_formats.push(format_type);
+        self
+    }
+    
+    /// Record the catalog format the timestamp was recognized in
+    pub fn with_timestamp_format(mut self, timestamp_format: Option<TimestampFormat>) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+    
+    /// Copy the event out of the parsed text
+    pub fn into_owned(self) -> ParseResult {
+        ParseResult {
+            success: self.success,
+            event: self.event.into_owned(),
+            error: self.error,
+            confidence: self.confidence,
+            line_number: self.line_number,
+            processing_time_micros: None,
+            attempted_formats: self.attempted_formats,
+            fallback_error: self.fallback_error,
+            timestamp_format: self.timestamp_format,
+        }
+    }
+}
+
+impl From<ParseResult> for ParseResultRef<'_> {
+    fn from(result: ParseResult) -> Self {
+        Self {
+            success: result.success,
+            event: result.event.into(),
+            error: result.error,
+            confidence: result.confidence,
+            line_number: result.line_number,
+            attempted_formats: result.attempted_formats,
+            fallback_error: result.fallback_error,
+            timestamp_format: result.timestamp_format,
+        }
+    }
+}
@@ -1,22 +1,48 @@
 use crate::models::*;
-use crate::parse_result::ParseResult;
+use crate::parse_result::{ParseResult, ParseResultRef};
+use crate::timestamp::TimestampFormat;
 
 /// Common interface for all log parsers
 pub trait LogParser {
     fn parse(&self, line: &str) -> ParseResult;
+    /// Parse into an event borrowing from `line`; parsers that cannot borrow copy
+    /// their owned result
+    fn parse_ref<'a>(&self, line: &'a str) -> ParseResultRef<'a> {
+        self.parse(line).into()
+    }
+    /// Parse trying `learned`, the timestamp format the line's source last
+    /// matched, before the rest of the timestamp catalog; parsers reading no
+    /// catalog timestamps ignore it
+    fn parse_learned(&self, line: &str, _learned: Option<TimestampFormat>) -> ParseResult {
+        self.parse(line)
+    }
+    /// `parse_learned` into an event borrowing from `line`
+    fn parse_learned_ref<'a>(&self, line: &'a str, _learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
+        self.parse_ref(line)
+    }
     fn can_parse(&self, line: &str) -> bool;
     fn get_format_type(&self) -> FormatType;
 }
 
 // Re-export individual parser modules
+pub mod cri_parser;
+pub mod docker_parser;
 pub mod json_parser;
+pub mod klog_parser;
 pub mod logfmt_parser;
 pub mod pattern_parser;
 pub mod plain_text_parser;
 pub mod profile_parser;
+pub mod registry;
+pub mod rfc5424_parser;
 
+pub use cri_parser::CriParser;
+pub use docker_parser::DockerJsonParser;
 pub use json_parser::JsonParser;
+pub use klog_parser::KlogParser;
 pub use logfmt_parser::LogfmtParser;
 pub use pattern_parser::PatternParser;
 pub use plain_text_parser::PlainTextParser;
-pub use profile_parser::ProfileParser;
\ No newline at end of file
+pub use profile_parser::ProfileParser;
+pub use registry::{ParserRegistry, RegisteredParser};
+pub use rfc5424_parser::Rfc5424Parser;
@@ -0,0 +1,162 @@
+use crate::models::*;
+use crate::error::ParseError;
+use crate::parse_result::ParseResult;
+use crate::parsers::LogParser;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::time::Instant;
+
+/// Kubernetes CRI container log parser (containerd, CRI-O):
+/// `TIMESTAMP STREAM TAG PAYLOAD`, as written under `/var/log/pods`.
+/// The payload is left in the message for `TangoParser` to parse in turn
+#[derive(Clone)]
+pub struct CriParser {
+    line_regex: Regex,
+}
+
+impl CriParser {
+    pub fn new() -> Self {
+        Self {
+            // RFC 3339 time, stdout or stderr, then P (partial) or F (full) with optional `:`-separated tags
+            line_regex: Regex::new(
+                r"(?s)^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:\d{2})) (stdout|stderr) ([PF])(?::\S*)?(?: (.*))?$"
+            ).unwrap(),
+        }
+    }
+    
+    /// Stream and tag of a CRI line, checked without a regex
+    fn header(line: &str) -> Option<(&str, &str)> {
+        let mut parts = line.splitn(4, ' ');
+        let timestamp = parts.next()?;
+        let stream = parts.next()?;
+        let tag = parts.next()?;
+        let looks_cri = timestamp.len() >= 20
+            && timestamp.as_bytes()[10] == b'T'
+            && timestamp.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+            && matches!(stream, "stdout" | "stderr");
+        looks_cri.then_some((stream, tag))
+    }
+    
+    /// Whether `next` carries on the payload of `previous`: a partial (`P`) CRI
+    /// line followed by one from the same stream
+    pub fn continues(previous: &str, next: &str) -> bool {
+        match (Self::header(previous), Self::header(next)) {
+            (Some((stream, tag)), Some((next_stream, _))) => tag.starts_with('P') && stream == next_stream,
+            _ => false,
+        }
+    }
+    
+    /// Whether `line` is a partial CRI line awaiting the rest of its payload
+    pub fn is_partial(line: &str) -> bool {
+        Self::header(line).is_some_and(|(_, tag)| tag.starts_with('P'))
+    }
+    
+    /// One CRI line from a partial line and the lines continuing it: the first
+    /// line's time and stream with every payload joined. `None` unless each
+    /// line continues the one before
+    pub fn reassemble<T: AsRef<str>>(lines: &[T]) -> Option<String> {
+        let first = lines.first()?.as_ref();
+        if lines.len() < 2 || !lines.windows(2).all(|pair| Self::continues(pair[0].as_ref(), pair[1].as_ref())) {
+            return None;
+        }
+        
+        // Still partial when the last piece is: its continuation never arrived
+        let tag = if Self::is_partial(lines[lines.len() - 1].as_ref()) { "P" } else { "F" };
+        let mut header = first.splitn(3, ' ');
+        let mut line = format!("{} {} {} ", header.next()?, header.next()?, tag);
+        for part in lines {
+            let payload = part.as_ref().splitn(4, ' ').nth(3).unwrap_or("");
+            line.push_str(payload);
+        }
+        Some(line)
+    }
+}
+
+impl Default for CriParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogParser for CriParser {
+    fn parse(&self, line: &str) -> ParseResult {
+        let start_time = Instant::now();
+        
+        let Some(captures) = self.line_regex.captures(line) else {
+            let error = ParseError::PatternMatchError {
+                input: line.to_string(),
+                attempted_patterns: vec!["TIMESTAMP STREAM P|F PAYLOAD".to_string()],
+            };
+            return ParseResult::failure(line.to_string(), error);
+        };
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&captures[1]) else {
+            let error = ParseError::TimestampParseError {
+                input: captures[1].to_string(),
+                attempted_formats: vec!["RFC3339".to_string()],
+            };
+            return ParseResult::failure(line.to_string(), error);
+        };
+        
+        let payload = captures.get(4).map_or("", |payload| payload.as_str());
+        let mut event = CanonicalEvent::new(payload.to_string(), line.to_string(), FormatType::Cri);
+        event.set_log_timestamp(LogTimestamp::Explicit(timestamp.with_timezone(&Utc)));
+        event.add_field("stream".to_string(), Value::String(captures[2].to_string()));
+        // A partial line whose continuation never arrived
+        if &captures[3] == "P" {
+            event.add_field("partial".to_string(), Value::Bool(true));
+        }
+        
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        ParseResult::success_with_timing(event, 0.95, processing_time)
+    }
+    
+    fn can_parse(&self, line: &str) -> bool {
+        Self::header(line).is_some() && self.line_regex.is_match(line)
+    }
+    
+    fn get_format_type(&self) -> FormatType {
+        FormatType::Cri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_parse_cri_lines() {
+        let parser = CriParser::new();
+        let result = parser.parse(r#"2024-01-01T00:00:00.123456789Z stderr F {"level":"warn","msg":"slow"}"#);
+        assert!(result.success);
+        assert_eq!(result.event.format_type, FormatType::Cri);
+        assert_eq!(result.event.message, r#"{"level":"warn","msg":"slow"}"#);
+        assert_eq!(result.event.fields["stream"], "stderr");
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::nanoseconds(123_456_789)));
+        
+        let empty = parser.parse("2024-01-01T00:00:00+01:00 stdout F");
+        assert!(empty.success);
+        assert_eq!(empty.event.message, "");
+        
+        assert!(!parser.can_parse("2024-01-01T00:00:00Z INFO F started"));
+        assert!(!parser.parse("2024-01-01 00:00:00 stdout F text").success);
+    }
+    
+    #[test]
+    fn test_partial_lines_reassemble_per_stream() {
+        let lines = [
+            "2024-01-01T00:00:00.1Z stdout P {\"msg\":\"a lo",
+            "2024-01-01T00:00:00.2Z stdout P ng li",
+            "2024-01-01T00:00:00.3Z stdout F ne\"}",
+        ];
+        assert!(CriParser::is_partial(lines[0]));
+        assert!(CriParser::continues(lines[1], lines[2]));
+        assert!(!CriParser::continues(lines[0], "2024-01-01T00:00:00.2Z stderr F other"));
+        assert_eq!(
+            CriParser::reassemble(&lines).as_deref(),
+            Some("2024-01-01T00:00:00.1Z stdout F {\"msg\":\"a long line\"}")
+        );
+        assert_eq!(CriParser::reassemble(&lines[2..]), None);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::models::*;
+use crate::error::ParseError;
+use crate::parse_result::ParseResult;
+use crate::parsers::LogParser;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::time::Instant;
+
+/// One line of Docker's json-file log driver
+#[derive(Deserialize)]
+struct DockerLine {
+    log: String,
+    stream: Option<String>,
+    time: Option<String>,
+    /// Labels and environment variables chosen with `--log-opt labels/env`
+    #[serde(default)]
+    attrs: Map<String, Value>,
+}
+
+/// Docker json-file container log parser for `/var/lib/docker/containers`.
+/// The application's line is the `log` string; it is left in the message for
+/// `TangoParser` to parse in turn
+#[derive(Clone, Default)]
+pub struct DockerJsonParser;
+
+impl DockerJsonParser {
+    pub fn new() -> Self {
+        Self
+    }
+    
+    /// json-file always writes `log` first, so the prefix identifies its lines
+    pub fn detects(line: &str) -> bool {
+        line.trim_start().starts_with(r#"{"log":"#)
+    }
+}
+
+impl LogParser for DockerJsonParser {
+    fn parse(&self, line: &str) -> ParseResult {
+        let start_time = Instant::now();
+        
+        let docker: DockerLine = match serde_json::from_str(line) {
+            Ok(docker) => docker,
+            Err(e) => {
+                let error = ParseError::JsonSyntaxError {
+                    message: e.to_string(),
+                    line_number: Some(e.line()),
+                    column: Some(e.column()),
+                };
+                return ParseResult::failure(line.to_string(), error);
+            }
+        };
+        
+        // The driver keeps the newline that ended the application's line
+        let message = docker.log.strip_suffix('\n').unwrap_or(&docker.log);
+        let message = message.strip_suffix('\r').unwrap_or(message);
+        let mut event = CanonicalEvent::new(message.to_string(), line.to_string(), FormatType::DockerJson);
+        
+        if let Some(time) = &docker.time {
+            match DateTime::parse_from_rfc3339(time) {
+                Ok(timestamp) => event.set_log_timestamp(LogTimestamp::Explicit(timestamp.with_timezone(&Utc))),
+                Err(_) => {
+                    let error = ParseError::TimestampParseError {
+                        input: time.clone(),
+                        attempted_formats: vec!["RFC3339".to_string()],
+                    };
+                    return ParseResult::failure(line.to_string(), error);
+                }
+            }
+        }
+        if let Some(stream) = docker.stream {
+            event.add_field("stream".to_string(), Value::String(stream));
+        }
+        // Lines over 16 KiB are split into entries without the trailing newline
+        if !docker.log.ends_with('\n') {
+            event.add_field("partial".to_string(), Value::Bool(true));
+        }
+        for (key, value) in docker.attrs {
+            event.add_field(key, value);
+        }
+        
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        ParseResult::success_with_timing(event, 0.95, processing_time)
+    }
+    
+    fn can_parse(&self, line: &str) -> bool {
+        Self::detects(line)
+    }
+    
+    fn get_format_type(&self) -> FormatType {
+        FormatType::DockerJson
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_parse_docker_json_file_lines() {
+        let parser = DockerJsonParser::new();
+        let line = r#"{"log":"{\"level\":\"error\",\"msg\":\"db down\"}\n","stream":"stderr","attrs":{"service":"api"},"time":"2024-01-01T00:00:00.5Z"}"#;
+        assert!(parser.can_parse(line));
+        
+        let result = parser.parse(line);
+        assert!(result.success);
+        assert_eq!(result.event.format_type, FormatType::DockerJson);
+        assert_eq!(result.event.message, r#"{"level":"error","msg":"db down"}"#);
+        assert_eq!(result.event.fields["stream"], "stderr");
+        assert_eq!(result.event.fields["service"], "api");
+        assert!(!result.event.fields.contains_key("partial"));
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + chrono::Duration::milliseconds(500)));
+        
+        assert!(!parser.can_parse(r#"{"msg":"log","log":"x"}"#));
+        assert!(!parser.parse(r#"{"log":"x","time":"yesterday"}"#).success);
+    }
+}
@@ -1,121 +1,92 @@
 use crate::models::*;
 use crate::error::ParseError;
-use crate::parse_result::ParseResult;
+use crate::event_ref::{CanonicalEventRef, ValueRef};
+use crate::parse_result::{ParseResult, ParseResultRef};
 use crate::parsers::LogParser;
-use chrono::{DateTime, Utc};
-use serde_json::{Map, Value};
+use crate::field_mapping::{first_mapped, lookup_path, parse_timestamp_value, FieldMappings};
+use crate::field_path::FlattenConfig;
+use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::time::Instant;
 
 /// JSON parser for structured JSON logs
 #[derive(Clone)]
-pub struct JsonParser;
+pub struct JsonParser {
+    field_mappings: FieldMappings,
+    flatten: FlattenConfig,
+}
 
 impl JsonParser {
     pub fn new() -> Self {
-        Self
+        Self::with_field_mappings(FieldMappings::default())
     }
     
-    /// Extract timestamp from JSON value using common field names
-    fn extract_timestamp(&self, json: &Map<String, Value>) -> Option<DateTime<Utc>> {
-        let timestamp_fields = ["ts", "time", "timestamp", "@timestamp"];
-        
-        for field in &timestamp_fields {
-            if let Some(value) = json.get(*field) {
-                if let Some(timestamp) = self.parse_timestamp_value(value) {
-                    return Some(timestamp);
-                }
-            }
-        }
-        None
+    /// Parser reading the timestamp, level and message from the mapped keys
+    pub fn with_field_mappings(field_mappings: FieldMappings) -> Self {
+        Self { field_mappings, flatten: FlattenConfig::default() }
     }
     
-    /// Parse timestamp from various JSON value types
-    fn parse_timestamp_value(&self, value: &Value) -> Option<DateTime<Utc>> {
-        match value {
-            Value::String(s) => {
-                // Try parsing ISO8601/RFC3339 formats
-                if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
-                    return Some(dt.with_timezone(&Utc));
-                }
-                // Try parsing ISO8601 without timezone
-                if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-                    return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-                }
-                // Try parsing other common formats
-                if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-                    return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-                }
-            }
-            Value::Number(n) => {
-                // Handle Unix timestamps (seconds or milliseconds)
-                if let Some(timestamp) = n.as_i64() {
-                    // Try as seconds first
-                    if let Some(dt) = DateTime::from_timestamp(timestamp, 0) {
-                        return Some(dt);
-                    }
-                    // Try as milliseconds
-                    if let Some(dt) = DateTime::from_timestamp_millis(timestamp) {
-                        return Some(dt);
-                    }
-                }
-            }
-            _ => {}
-        }
-        None
+    /// Parser flattening nested objects and arrays as `flatten` says
+    pub fn with_flatten(mut self, flatten: FlattenConfig) -> Self {
+        self.flatten = flatten;
+        self
     }
     
-    /// Extract log level from JSON value using common field names
-    fn extract_level(&self, json: &Map<String, Value>) -> Option<LogLevel> {
-        let level_fields = ["level", "severity", "lvl", "log.level"];
-        
-        for field in &level_fields {
-            if let Some(value) = json.get(*field) {
-                if let Some(level_str) = value.as_str() {
-                    if let Some(level) = LogLevel::from_str(level_str) {
-                        return Some(level);
-                    }
-                }
-            }
-        }
-        None
+    pub fn field_mappings(&self) -> &FieldMappings {
+        &self.field_mappings
     }
     
-    /// Extract message from JSON value using common field names
-    fn extract_message(&self, json: &Map<String, Value>) -> Option<String> {
-        let message_fields = ["msg", "message", "log.message"];
-        
-        for field in &message_fields {
-            if let Some(value) = json.get(*field) {
-                if let Some(msg) = value.as_str() {
-                    return Some(msg.to_string());
-                }
-            }
-        }
-        None
+    pub fn flatten(&self) -> &FlattenConfig {
+        &self.flatten
     }
     
-    /// Flatten nested JSON objects using dot notation
-    fn flatten_object(&self, obj: &Map<String, Value>, prefix: &str, result: &mut HashMap<String, Value>) {
-        for (key, value) in obj {
-            let full_key = if prefix.is_empty() {
-                key.clone()
-            } else {
-                format!("{}.{}", prefix, key)
-            };
-            
-            match value {
-                Value::Object(nested) => {
-                    self.flatten_object(nested, &full_key, result);
-                }
-                Value::Array(arr) => {
-                    // Convert arrays to string representation for simplicity
-                    result.insert(full_key, Value::String(format!("{:?}", arr)));
+    /// Extract timestamp from the first mapped field holding one
+    fn extract_timestamp(&self, json: &ValueRef) -> Option<(&str, LogTimestamp)> {
+        first_mapped(&self.field_mappings.timestamp, |path| {
+            lookup_path(json, path).and_then(|value| parse_timestamp_value(value, self.field_mappings.epoch_unit))
+        })
+    }
+    
+    /// Extract log level from the first mapped field holding a known level
+    fn extract_level(&self, json: &ValueRef) -> Option<(&str, LogLevel)> {
+        first_mapped(&self.field_mappings.level, |path| {
+            lookup_path(json, path).and_then(ValueRef::as_str).and_then(LogLevel::from_str)
+        })
+    }
+    
+    /// Extract message from the first mapped field holding a string
+    fn extract_message<'a>(&self, json: &ValueRef<'a>) -> Option<(&str, Cow<'a, str>)> {
+        first_mapped(&self.field_mappings.message, |path| match lookup_path(json, path) {
+            Some(ValueRef::String(msg)) => Some(msg.clone()),
+            _ => None,
+        })
+    }
+    
+    /// Flatten a member into dotted keys for objects and indexed keys for
+    /// arrays (`http.request.method`, `items[0].id`), down to the max depth;
+    /// values below it, empty containers and unindexed arrays are kept whole
+    fn flatten_value<'a>(
+        &self,
+        key: Cow<'a, str>,
+        value: ValueRef<'a>,
+        depth: usize,
+        result: &mut HashMap<Cow<'a, str>, ValueRef<'a>>,
+    ) {
+        match value {
+            ValueRef::Object(members) if !members.is_empty() && self.flatten.descends(depth) => {
+                for (name, nested) in members {
+                    self.flatten_value(Cow::Owned(format!("{}.{}", key, name)), nested, depth + 1, result);
                 }
-                _ => {
-                    result.insert(full_key, value.clone());
+            }
+            ValueRef::Array(items) if !items.is_empty() && self.flatten.index_arrays && self.flatten.descends(depth) => {
+                for (index, item) in items.into_iter().enumerate() {
+                    self.flatten_value(Cow::Owned(format!("{}[{}]", key, index)), item, depth + 1, result);
                 }
             }
+            _ => {
+                result.insert(key, value);
+            }
         }
     }
     
@@ -141,76 +112,68 @@ impl JsonParser {
 impl LogParser for JsonParser {
     fn parse(&self, line: &str) -> ParseResult {
         let start_time = Instant::now();
-        
-        // Try to parse as JSON
-        match serde_json::from_str::<Value>(line) {
-            Ok(Value::Object(json_obj)) => {
-                let mut event = CanonicalEvent::new(
-                    String::new(), // Will be set below
-                    line.to_string(),
+        let result = self.parse_ref(line).into_owned();
+        result.with_processing_time(start_time.elapsed().as_micros() as u64)
+    }
+    
+    fn parse_ref<'a>(&self, line: &'a str) -> ParseResultRef<'a> {
+        // Try to parse as JSON; strings without escapes borrow from the line
+        match serde_json::from_str::<ValueRef>(line) {
+            Ok(json_obj @ ValueRef::Object(_)) => {
+                let mut event = CanonicalEventRef::new(
+                    "", // Will be set below
+                    line,
                     FormatType::Json,
                 );
                 
                 // Extract timestamp with error handling
-                if let Some(timestamp) = self.extract_timestamp(&json_obj) {
-                    event.set_timestamp(timestamp);
+                let timestamp = self.extract_timestamp(&json_obj);
+                if let Some((_, timestamp)) = timestamp {
+                    event.set_log_timestamp(timestamp);
                 }
                 
                 // Extract level with error handling
-                if let Some(level) = self.extract_level(&json_obj) {
-                    event.set_level(level);
-                }
+                let level = self.extract_level(&json_obj);
+                event.level = level.map(|(_, level)| level);
                 
-                // Extract message
-                let message = self.extract_message(&json_obj)
-                    .unwrap_or_else(|| {
-                        // If no message field found, use the entire JSON as message
-                        line.to_string()
-                    });
-                event.message = message;
+                // Extract message; if no message field found, use the entire JSON as message
+                let message = self.extract_message(&json_obj);
+                let message_key = message.as_ref().map(|(key, _)| *key);
+                event.message = message.map(|(_, message)| message)
+                    .unwrap_or(Cow::Borrowed(line));
+                let extracted_fields = [timestamp.map(|(key, _)| key), level.map(|(key, _)| key), message_key];
                 
                 // Flatten and store all other fields
-                let mut flattened_fields = HashMap::new();
-                self.flatten_object(&json_obj, "", &mut flattened_fields);
-                
-                // Remove the fields we've already extracted to canonical fields
-                let extracted_fields = ["ts", "time", "timestamp", "@timestamp", 
-                                      "level", "severity", "lvl", "log.level",
-                                      "msg", "message", "log.message"];
-                for field in &extracted_fields {
-                    flattened_fields.remove(*field);
+                let ValueRef::Object(members) = json_obj else {
+                    unreachable!()
+                };
+                for (key, value) in members {
+                    self.flatten_value(key, value, 1, &mut event.fields);
                 }
                 
-                // Convert HashMap<String, Value> to HashMap<String, serde_json::Value>
-                for (key, value) in flattened_fields {
-                    event.add_field(key, value);
+                // Remove the fields we've already extracted to canonical fields;
+                // nested paths are flattened to the same dotted keys
+                for field in extracted_fields.into_iter().flatten() {
+                    event.fields.remove(field);
                 }
                 
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::success_with_timing(event, 0.95, processing_time) // High confidence for valid JSON
+                ParseResultRef::success(event, 0.95) // High confidence for valid JSON
             }
             Ok(other_value) => {
                 // Valid JSON but not an object (e.g., array, primitive)
                 let actual_type = match other_value {
-                    Value::Array(_) => "array",
-                    Value::String(_) => "string", 
-                    Value::Number(_) => "number",
-                    Value::Bool(_) => "boolean",
-                    Value::Null => "null",
+                    ValueRef::Array(_) => "array",
+                    ValueRef::String(_) => "string", 
+                    ValueRef::Number(_) => "number",
+                    ValueRef::Bool(_) => "boolean",
+                    ValueRef::Null => "null",
                     _ => "unknown",
                 };
                 
                 let error = ParseError::JsonNotObject {
                     actual_type: actual_type.to_string(),
                 };
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::failure_with_context(
-                    line.to_string(),
-                    error,
-                    None,
-                    Some(processing_time),
-                )
+                ParseResultRef::failure(line, error)
             }
             Err(json_error) => {
                 // Invalid JSON - extract line and column information if available
@@ -222,14 +185,7 @@ impl LogParser for JsonParser {
                     line_number: line_num,
                     column,
                 };
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::failure_with_context(
-                    line.to_string(),
-                    error,
-                    None,
-                    Some(processing_time),
-                )
+                ParseResultRef::failure(line, error)
             }
         }
     }
@@ -242,4 +198,34 @@ impl LogParser for JsonParser {
     fn get_format_type(&self) -> FormatType {
         FormatType::Json
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn test_flattening_depth_and_array_indexing() {
+        let line = r#"{"msg":"GET /","http":{"request":{"method":"GET"},"status":503},"items":[{"id":"a1"}]}"#;
+        
+        let event = JsonParser::new().parse(line).event;
+        assert_eq!(event.fields.get("http.request.method"), Some(&json!("GET")));
+        assert_eq!(event.fields.get("http.status"), Some(&json!(503)));
+        assert_eq!(event.fields.get("items"), Some(&json!([{"id": "a1"}])));
+        
+        let indexed = JsonParser::new().with_flatten(FlattenConfig { index_arrays: true, ..FlattenConfig::default() });
+        let event = indexed.parse(line).event;
+        assert_eq!(event.fields.get("items[0].id"), Some(&json!("a1")));
+        assert_eq!(event.fields.get("http.request.method"), Some(&json!("GET")));
+        
+        let shallow = JsonParser::new().with_flatten(FlattenConfig { max_depth: Some(2), ..FlattenConfig::default() });
+        let event = shallow.parse(line).event;
+        assert_eq!(event.fields.get("http.request"), Some(&json!({"method": "GET"})));
+        assert_eq!(event.fields.get("items"), Some(&json!([{"id": "a1"}])));
+        
+        // Borrowed events flatten the same way
+        let owned = indexed.parse(line).event;
+        let borrowed = indexed.parse_ref(line).event.into_owned();
+        assert_eq!(borrowed.fields, owned.fields);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::models::*;
+use crate::error::ParseError;
+use crate::parse_result::ParseResult;
+use crate::parsers::LogParser;
+use crate::timestamp;
+use regex::Regex;
+use serde_json::Value;
+use std::time::Instant;
+
+/// Kubernetes klog (and glog) parser:
+/// `Lmmdd hh:mm:ss.uuuuuu threadid file:line] msg`, where L is the severity
+#[derive(Clone)]
+pub struct KlogParser {
+    header_regex: Regex,
+}
+
+impl KlogParser {
+    pub fn new() -> Self {
+        Self {
+            header_regex: Regex::new(
+                r"(?s)^([IWEF]\d{4} \d{2}:\d{2}:\d{2}\.\d{6})\s+(\d+) ([^\s:\]]+:\d+)\] ?(.*)$"
+            ).unwrap(),
+        }
+    }
+    
+    fn level(severity: u8) -> LogLevel {
+        match severity {
+            b'W' => LogLevel::Warn,
+            b'E' => LogLevel::Error,
+            b'F' => LogLevel::Fatal,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+impl Default for KlogParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogParser for KlogParser {
+    fn parse(&self, line: &str) -> ParseResult {
+        let start_time = Instant::now();
+        
+        let Some(captures) = self.header_regex.captures(line) else {
+            let error = ParseError::PatternMatchError {
+                input: line.to_string(),
+                attempted_patterns: vec!["Lmmdd hh:mm:ss.uuuuuu threadid file:line] msg".to_string()],
+            };
+            return ParseResult::failure(line.to_string(), error);
+        };
+        // The header has no year; it is inferred per source
+        let Some(recognized) = timestamp::recognize(&captures[1], None) else {
+            let error = ParseError::TimestampParseError {
+                input: captures[1].to_string(),
+                attempted_formats: vec!["klog".to_string()],
+            };
+            return ParseResult::failure(line.to_string(), error);
+        };
+        
+        let mut event = CanonicalEvent::new(captures[4].to_string(), line.to_string(), FormatType::Klog);
+        event.set_recognized_timestamp(recognized);
+        event.set_level(Self::level(line.as_bytes()[0]));
+        if let Ok(thread_id) = captures[2].parse::<u64>() {
+            event.add_field("thread_id".to_string(), Value::from(thread_id));
+        }
+        event.add_field("caller".to_string(), Value::String(captures[3].to_string()));
+        
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        ParseResult::success_with_timing(event, 0.9, processing_time).with_timestamp_format(Some(recognized.format))
+    }
+    
+    fn can_parse(&self, line: &str) -> bool {
+        line.starts_with(['I', 'W', 'E', 'F']) && self.header_regex.is_match(line)
+    }
+    
+    fn get_format_type(&self) -> FormatType {
+        FormatType::Klog
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::year_inference::YearContext;
+    use chrono::{Datelike, Timelike};
+    
+    #[test]
+    fn test_parse_klog_headers() {
+        let parser = KlogParser::new();
+        let line = "E0102 15:04:05.123456    4242 reflector.go:138] Failed to watch *v1.Pod: connection refused";
+        assert!(parser.can_parse(line));
+        
+        let result = parser.parse(line);
+        assert!(result.success);
+        let event = result.event;
+        assert_eq!(event.format_type, FormatType::Klog);
+        assert_eq!(event.level, Some(LogLevel::Error));
+        assert_eq!(event.message, "Failed to watch *v1.Pod: connection refused");
+        assert_eq!(event.fields["thread_id"], 4242);
+        assert_eq!(event.fields["caller"], "reflector.go:138");
+        assert_eq!(event.year_inferred, Some(true));
+        let timestamp = event.timestamp.unwrap();
+        assert_eq!((timestamp.month(), timestamp.day(), timestamp.hour(), timestamp.nanosecond()), (1, 2, 15, 123_456_000));
+        // Used on its own, the parser assumes the current year
+        assert_eq!(timestamp.year(), YearContext::now().resolve_year(1));
+        
+        assert!(!parser.can_parse("I0102 15:04:05 missing thread and caller"));
+        assert!(!parser.can_parse("Info 0102 15:04:05.123456 1 a.go:1] no"));
+    }
+}
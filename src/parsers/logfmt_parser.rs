@@ -1,8 +1,11 @@
 use crate::models::*;
 use crate::error::ParseError;
-use crate::parse_result::ParseResult;
+use crate::event_ref::{CanonicalEventRef, ValueRef};
+use crate::parse_result::{ParseResult, ParseResultRef};
 use crate::parsers::LogParser;
+use crate::field_mapping::{first_mapped, parse_timestamp_value, FieldMappings};
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -10,37 +13,70 @@ use std::time::Instant;
 #[derive(Clone)]
 pub struct LogfmtParser {
     key_value_regex: Regex,
+    field_mappings: FieldMappings,
+    min_pairs: usize,
 }
 
 impl LogfmtParser {
     pub fn new() -> Self {
+        Self::with_field_mappings(FieldMappings::default())
+    }
+    
+    /// Parser reading the timestamp, level and message from the mapped keys
+    pub fn with_field_mappings(field_mappings: FieldMappings) -> Self {
         Self {
             // Regex to match key=value pairs with optional quotes
             key_value_regex: Regex::new(r#"([a-zA-Z0-9_.-]+)=(?:"((?:[^"\\]|\\.)*)"|([^\s]+))"#).unwrap(),
+            field_mappings,
+            min_pairs: 3,
         }
     }
     
+    /// Accept lines with at least `min_pairs` key=value pairs. Detection needs 3,
+    /// so that prose with a stray `=` is not taken for logfmt; a forced format needs 1
+    pub fn with_min_pairs(mut self, min_pairs: usize) -> Self {
+        self.min_pairs = min_pairs.max(1);
+        self
+    }
+    
+    pub fn field_mappings(&self) -> &FieldMappings {
+        &self.field_mappings
+    }
+    
     /// Extract key=value pairs from a logfmt line
     pub fn extract_pairs(&self, line: &str) -> HashMap<String, String> {
+        self.extract_pairs_ref(line).into_iter()
+            .map(|(key, value)| (key.to_string(), value.into_owned()))
+            .collect()
+    }
+    
+    /// Extract key=value pairs borrowing from the line; only quoted values with
+    /// escape sequences are copied
+    pub fn extract_pairs_ref<'a>(&self, line: &'a str) -> HashMap<&'a str, Cow<'a, str>> {
         let mut pairs = HashMap::new();
         
         for cap in self.key_value_regex.captures_iter(line) {
-            let key = cap.get(1).unwrap().as_str().to_string();
+            let key = cap.get(1).unwrap().as_str();
             
             // Handle quoted and unquoted values
             let value = if let Some(quoted_value) = cap.get(2) {
                 // Quoted value - handle escaped quotes and other escape sequences
-                quoted_value.as_str()
-                    .replace(r#"\""#, r#"""#)
-                    .replace(r#"\\"#, r#"\"#)
-                    .replace(r#"\n"#, "\n")
-                    .replace(r#"\t"#, "\t")
+                let quoted_value = quoted_value.as_str();
+                if quoted_value.contains('\\') {
+                    Cow::Owned(quoted_value
+                        .replace(r#"\""#, r#"""#)
+                        .replace(r#"\\"#, r#"\"#)
+                        .replace(r#"\n"#, "\n")
+                        .replace(r#"\t"#, "\t"))
+                } else {
+                    Cow::Borrowed(quoted_value)
+                }
             } else if let Some(unquoted_value) = cap.get(3) {
                 // Unquoted value
-                unquoted_value.as_str().to_string()
+                Cow::Borrowed(unquoted_value.as_str())
             } else {
                 // Shouldn't happen with our regex, but handle gracefully
-                String::new()
+                Cow::Borrowed("")
             };
             
             pairs.insert(key, value);
@@ -51,45 +87,61 @@ impl LogfmtParser {
     
     /// Check if line has minimum threshold of key=value pairs for logfmt detection
     fn meets_logfmt_threshold(&self, line: &str) -> bool {
-        self.key_value_regex.find_iter(line).count() >= 3
+        self.key_value_regex.find_iter(line).count() >= self.min_pairs
     }
 }
 
 impl LogParser for LogfmtParser {
     fn parse(&self, line: &str) -> ParseResult {
         let start_time = Instant::now();
-        let pairs = self.extract_pairs(line);
+        let result = self.parse_ref(line).into_owned();
+        result.with_processing_time(start_time.elapsed().as_micros() as u64)
+    }
+    
+    fn parse_ref<'a>(&self, line: &'a str) -> ParseResultRef<'a> {
+        let pairs = self.extract_pairs_ref(line);
         
         // Check if we have enough pairs to be confident this is logfmt
-        if pairs.len() < 3 {
+        if pairs.len() < self.min_pairs {
             let error = ParseError::LogfmtInsufficientPairs {
                 found_pairs: pairs.len(),
-                required_pairs: 3,
+                required_pairs: self.min_pairs,
             };
-            
-            let processing_time = start_time.elapsed().as_micros() as u64;
-            return ParseResult::failure_with_context(
-                line.to_string(),
-                error,
-                None,
-                Some(processing_time),
-            );
+            return ParseResultRef::failure(line, error);
         }
         
-        let mut event = CanonicalEvent::new(
-            line.to_string(), // Use entire line as message for now
-            line.to_string(),
+        let confidence = if pairs.len() >= 5 { 0.9 } else { 0.7 };
+        let mut event = CanonicalEventRef::new(
+            line, // Use entire line as message unless a message key is mapped
+            line,
             FormatType::Logfmt,
         );
         
-        // Store all fields
-        for (key, value) in &pairs {
-            event.add_field(key.clone(), serde_json::Value::String(value.clone()));
+        // Keys are flat, so dotted mappings only match literally
+        let mappings = &self.field_mappings;
+        let timestamp = first_mapped(&mappings.timestamp, |key| {
+            pairs.get(key).and_then(|value| parse_timestamp_value(&ValueRef::String(value.clone()), mappings.epoch_unit))
+        });
+        let level = first_mapped(&mappings.level, |key| pairs.get(key).and_then(|value| LogLevel::from_str(value)));
+        let message = first_mapped(&mappings.message, |key| pairs.get(key).cloned());
+        
+        if let Some((_, timestamp)) = timestamp {
+            event.set_log_timestamp(timestamp);
+        }
+        event.level = level.map(|(_, level)| level);
+        let extracted = [timestamp.map(|(key, _)| key), level.map(|(key, _)| key), message.as_ref().map(|(key, _)| *key)];
+        if let Some((_, message)) = message {
+            event.message = message;
         }
         
-        let confidence = if pairs.len() >= 5 { 0.9 } else { 0.7 };
-        let processing_time = start_time.elapsed().as_micros() as u64;
-        ParseResult::success_with_timing(event, confidence, processing_time)
+        // Store all other fields
+        for (key, value) in pairs {
+            if !extracted.contains(&Some(key)) {
+                event.add_field(key, ValueRef::String(value));
+            }
+        }
+        
+        ParseResultRef::success(event, confidence)
     }
     
     fn can_parse(&self, line: &str) -> bool {
@@ -2,7 +2,7 @@ use crate::models::*;
 use crate::error::ParseError;
 use crate::parse_result::ParseResult;
 use crate::parsers::LogParser;
-use chrono::{DateTime, Utc};
+use crate::timestamp::{self, Recognized, TimestampFormat};
 use regex::Regex;
 use std::time::Instant;
 
@@ -10,7 +10,7 @@ use std::time::Instant;
 #[derive(Clone)]
 pub struct PatternParser {
     bracketed_pattern: Regex,
-    space_pattern: Regex,
+    level_message_pattern: Regex,
     android_logcat_pattern: Regex,
     syslog_pattern: Regex,
     #[allow(dead_code)]
@@ -27,8 +27,9 @@ impl PatternParser {
             bracketed_pattern: Regex::new(
                 r"^\[([^\]]+)\]\s*\[([^\]]+)\]\s*(.*)$"
             ).unwrap(),
-            space_pattern: Regex::new(
-                r"^(\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\s+(\w+)\s+(.*)$"
+            // What follows a recognized timestamp: "level message"
+            level_message_pattern: Regex::new(
+                r"^\s+(\w+)\s+(.*)$"
             ).unwrap(),
             // Android logcat: "03-17 16:13:38.811  1702  2395 D WindowManager: message"
             android_logcat_pattern: Regex::new(
@@ -50,83 +51,26 @@ impl PatternParser {
         }
     }
     
-    /// Parse timestamp from string using multiple format attempts
-    fn parse_timestamp(&self, timestamp_str: &str) -> Result<DateTime<Utc>, ParseError> {
-        let mut attempted_formats = Vec::new();
-        
-        // Try RFC3339 format first
-        attempted_formats.push("RFC3339".to_string());
-        if let Ok(dt) = DateTime::parse_from_rfc3339(timestamp_str) {
-            return Ok(dt.with_timezone(&Utc));
-        }
-        
-        // Try ISO8601 without timezone
-        attempted_formats.push("ISO8601 without timezone".to_string());
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try ISO8601 with milliseconds
-        attempted_formats.push("ISO8601 with milliseconds".to_string());
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.3f") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try space-separated format
-        attempted_formats.push("Space-separated format".to_string());
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try common log format with timezone
-        attempted_formats.push("Common log format".to_string());
-        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, "%d/%b/%Y:%H:%M:%S %z") {
-            return Ok(dt.with_timezone(&Utc));
-        }
-        
-        // Try Apache/Syslog style: "Sun Dec 04 04:47:44 2005"
-        attempted_formats.push("Apache/Syslog format".to_string());
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%a %b %d %H:%M:%S %Y") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try variant without day name: "Dec 04 04:47:44 2005"
-        attempted_formats.push("Syslog variant".to_string());
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%b %d %H:%M:%S %Y") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try syslog without year: "Dec  4 04:47:44" (assumes current year)
-        attempted_formats.push("Syslog without year".to_string());
-        let current_year = chrono::Utc::now().format("%Y").to_string();
-        let with_year = format!("{} {}", timestamp_str, current_year);
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&with_year, "%b %d %H:%M:%S %Y") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        // Handle single-digit day with double space: "Dec  4"
-        let normalized = timestamp_str.split_whitespace().collect::<Vec<_>>().join(" ");
-        let with_year = format!("{} {}", normalized, current_year);
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&with_year, "%b %d %H:%M:%S %Y") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        // Try Android logcat format: "03-17 16:13:38.811" (assumes current year)
-        attempted_formats.push("Android logcat format".to_string());
-        let android_with_year = format!("{}-{}", current_year, timestamp_str);
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&android_with_year, "%Y-%m-%d %H:%M:%S%.3f") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        // Try without milliseconds
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&android_with_year, "%Y-%m-%d %H:%M:%S") {
-            return Ok(DateTime::from_naive_utc_and_offset(dt, Utc));
-        }
-        
-        Err(ParseError::TimestampParseError {
+    /// Whether the line is in Android logcat format
+    pub fn is_logcat(&self, line: &str) -> bool {
+        self.android_logcat_pattern.is_match(line)
+    }
+    
+    /// Parse timestamp from string against the shared timestamp catalog
+    fn parse_timestamp(&self, timestamp_str: &str, learned: Option<TimestampFormat>) -> Result<Recognized, ParseError> {
+        timestamp::recognize(timestamp_str, learned).ok_or_else(|| ParseError::TimestampParseError {
             input: timestamp_str.to_string(),
-            attempted_formats,
+            attempted_formats: timestamp::format_names().map(str::to_string).collect(),
         })
     }
     
+    /// Split a "timestamp level message" line after its leading timestamp
+    fn split_space_line<'a>(&self, line: &'a str, learned: Option<TimestampFormat>) -> Option<(Recognized, &'a str, &'a str)> {
+        let (recognized, len) = timestamp::recognize_prefix(line, learned)?;
+        let captures = self.level_message_pattern.captures(&line[len..])?;
+        Some((recognized, captures.get(1)?.as_str(), captures.get(2)?.as_str()))
+    }
+    
     /// Parse log level from string with normalization
     fn parse_level(&self, level_str: &str) -> Result<LogLevel, ParseError> {
         let normalized = level_str.to_uppercase();
@@ -148,13 +92,13 @@ impl PatternParser {
     }
     
     /// Try parsing with bracketed pattern: [timestamp] [level] message
-    fn try_bracketed_pattern(&self, line: &str) -> Result<(DateTime<Utc>, LogLevel, String), ParseError> {
+    fn try_bracketed_pattern(&self, line: &str, learned: Option<TimestampFormat>) -> Result<(Recognized, LogLevel, String), ParseError> {
         if let Some(captures) = self.bracketed_pattern.captures(line) {
             let timestamp_str = captures.get(1).unwrap().as_str();
             let level_str = captures.get(2).unwrap().as_str();
             let message = captures.get(3).unwrap().as_str().to_string();
             
-            let timestamp = self.parse_timestamp(timestamp_str)?;
+            let timestamp = self.parse_timestamp(timestamp_str, learned)?;
             let level = self.parse_level(level_str)?;
             
             return Ok((timestamp, level, message));
@@ -167,16 +111,11 @@ impl PatternParser {
     }
     
     /// Try parsing with space-separated pattern: timestamp level message
-    fn try_space_pattern(&self, line: &str) -> Result<(DateTime<Utc>, LogLevel, String), ParseError> {
-        if let Some(captures) = self.space_pattern.captures(line) {
-            let timestamp_str = captures.get(1).unwrap().as_str();
-            let level_str = captures.get(2).unwrap().as_str();
-            let message = captures.get(3).unwrap().as_str().to_string();
-            
-            let timestamp = self.parse_timestamp(timestamp_str)?;
+    fn try_space_pattern(&self, line: &str, learned: Option<TimestampFormat>) -> Result<(Recognized, LogLevel, String), ParseError> {
+        if let Some((timestamp, level_str, message)) = self.split_space_line(line, learned) {
             let level = self.parse_level(level_str)?;
             
-            return Ok((timestamp, level, message));
+            return Ok((timestamp, level, message.to_string()));
         }
         
         Err(ParseError::PatternMatchError {
@@ -186,7 +125,7 @@ impl PatternParser {
     }
     
     /// Try parsing Android logcat format: "03-17 16:13:38.811  1702  2395 D WindowManager: message"
-    fn try_android_logcat_pattern(&self, line: &str) -> Result<(DateTime<Utc>, LogLevel, String, std::collections::HashMap<String, serde_json::Value>), ParseError> {
+    fn try_android_logcat_pattern(&self, line: &str, learned: Option<TimestampFormat>) -> Result<(Recognized, LogLevel, String, std::collections::HashMap<String, serde_json::Value>), ParseError> {
         if let Some(captures) = self.android_logcat_pattern.captures(line) {
             let timestamp_str = captures.get(1).unwrap().as_str();
             let pid = captures.get(2).unwrap().as_str();
@@ -195,7 +134,7 @@ impl PatternParser {
             let tag = captures.get(5).unwrap().as_str().trim();
             let message = captures.get(6).unwrap().as_str().to_string();
             
-            let timestamp = self.parse_timestamp(timestamp_str)?;
+            let timestamp = self.parse_timestamp(timestamp_str, learned)?;
             
             // Map single-letter Android log levels
             let level = match level_char {
@@ -228,7 +167,7 @@ impl PatternParser {
     }
     
     /// Try parsing Linux syslog format: "Jun 14 15:16:01 combo sshd(pam_unix)[19939]: message"
-    fn try_syslog_pattern(&self, line: &str) -> Result<(DateTime<Utc>, String, std::collections::HashMap<String, serde_json::Value>), ParseError> {
+    fn try_syslog_pattern(&self, line: &str, learned: Option<TimestampFormat>) -> Result<(Recognized, String, std::collections::HashMap<String, serde_json::Value>), ParseError> {
         if let Some(captures) = self.syslog_pattern.captures(line) {
             let month = captures.get(1).unwrap().as_str();
             let day = captures.get(2).unwrap().as_str();
@@ -237,10 +176,9 @@ impl PatternParser {
             let process = captures.get(5).unwrap().as_str();
             let message = captures.get(6).unwrap().as_str().to_string();
             
-            // Build timestamp string for parsing (assume current year)
-            let current_year = chrono::Utc::now().format("%Y").to_string();
-            let timestamp_str = format!("{} {} {} {}", month, day, time, current_year);
-            let timestamp = self.parse_timestamp(&timestamp_str)?;
+            // Build timestamp string for parsing (year is inferred)
+            let timestamp_str = format!("{} {} {}", month, day, time);
+            let timestamp = self.parse_timestamp(&timestamp_str, learned)?;
             
             // Build fields map with syslog-specific metadata
             let mut fields = std::collections::HashMap::new();
@@ -266,25 +204,29 @@ impl PatternParser {
 
 impl LogParser for PatternParser {
     fn parse(&self, line: &str) -> ParseResult {
+        self.parse_learned(line, None)
+    }
+    
+    fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
         let start_time = Instant::now();
         let mut attempted_patterns = Vec::new();
         
         // Try Android logcat pattern first (most specific)
-        match self.try_android_logcat_pattern(line) {
+        match self.try_android_logcat_pattern(line, learned) {
             Ok((timestamp, level, message, fields)) => {
                 let mut event = CanonicalEvent::new(
                     message,
                     line.to_string(),
                     FormatType::Pattern,
                 );
-                event.set_timestamp(timestamp);
+                event.set_recognized_timestamp(timestamp);
                 event.set_level(level);
                 for (key, value) in fields {
                     event.add_field(key, value);
                 }
                 
                 let processing_time = start_time.elapsed().as_micros() as u64;
-                return ParseResult::success_with_timing(event, 0.90, processing_time);
+                return ParseResult::success_with_timing(event, 0.90, processing_time).with_timestamp_format(Some(timestamp.format));
             }
             Err(_e) => {
                 attempted_patterns.push("android logcat pattern".to_string());
@@ -293,18 +235,18 @@ impl LogParser for PatternParser {
         }
         
         // Try bracketed pattern
-        match self.try_bracketed_pattern(line) {
+        match self.try_bracketed_pattern(line, learned) {
             Ok((timestamp, level, message)) => {
                 let mut event = CanonicalEvent::new(
                     message,
                     line.to_string(),
                     FormatType::Pattern,
                 );
-                event.set_timestamp(timestamp);
+                event.set_recognized_timestamp(timestamp);
                 event.set_level(level);
                 
                 let processing_time = start_time.elapsed().as_micros() as u64;
-                return ParseResult::success_with_timing(event, 0.85, processing_time);
+                return ParseResult::success_with_timing(event, 0.85, processing_time).with_timestamp_format(Some(timestamp.format));
             }
             Err(_e) => {
                 attempted_patterns.push("bracketed pattern".to_string());
@@ -313,18 +255,18 @@ impl LogParser for PatternParser {
         }
         
         // Try space-separated pattern
-        match self.try_space_pattern(line) {
+        match self.try_space_pattern(line, learned) {
             Ok((timestamp, level, message)) => {
                 let mut event = CanonicalEvent::new(
                     message,
                     line.to_string(),
                     FormatType::Pattern,
                 );
-                event.set_timestamp(timestamp);
+                event.set_recognized_timestamp(timestamp);
                 event.set_level(level);
                 
                 let processing_time = start_time.elapsed().as_micros() as u64;
-                return ParseResult::success_with_timing(event, 0.80, processing_time);
+                return ParseResult::success_with_timing(event, 0.80, processing_time).with_timestamp_format(Some(timestamp.format));
             }
             Err(_e) => {
                 attempted_patterns.push("space-separated pattern".to_string());
@@ -333,21 +275,21 @@ impl LogParser for PatternParser {
         }
         
         // Try syslog pattern (no log level in standard syslog)
-        match self.try_syslog_pattern(line) {
+        match self.try_syslog_pattern(line, learned) {
             Ok((timestamp, message, fields)) => {
                 let mut event = CanonicalEvent::new(
                     message,
                     line.to_string(),
                     FormatType::Pattern,
                 );
-                event.set_timestamp(timestamp);
+                event.set_recognized_timestamp(timestamp);
                 // Syslog doesn't have explicit log levels - leave as None
                 for (key, value) in fields {
                     event.add_field(key, value);
                 }
                 
                 let processing_time = start_time.elapsed().as_micros() as u64;
-                return ParseResult::success_with_timing(event, 0.75, processing_time);
+                return ParseResult::success_with_timing(event, 0.75, processing_time).with_timestamp_format(Some(timestamp.format));
             }
             Err(_e) => {
                 attempted_patterns.push("syslog pattern".to_string());
@@ -374,7 +316,7 @@ impl LogParser for PatternParser {
         // Quick heuristic checks
         self.android_logcat_pattern.is_match(line) || 
         self.bracketed_pattern.is_match(line) || 
-        self.space_pattern.is_match(line) ||
+        self.split_space_line(line, None).is_some() ||
         self.syslog_pattern.is_match(line)
     }
     
@@ -1,8 +1,12 @@
 use crate::models::*;
-use crate::parse_result::ParseResult;
+use crate::event_ref::{CanonicalEventRef, ValueRef};
+use crate::parse_result::{ParseResult, ParseResultRef};
 use crate::parsers::LogParser;
-use chrono::{DateTime, Utc};
+use crate::epoch::{parse_epoch_text, EpochUnit};
+use crate::timestamp::{self, Recognized, TimestampFormat};
 use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::time::Instant;
 
 /// Plain text parser for unrecognized log formats (fallback parser)
@@ -10,6 +14,9 @@ use std::time::Instant;
 pub struct PlainTextParser {
     // Optional timestamp inference patterns
     timestamp_inference_regex: Regex,
+    // Epoch timestamp opening the line
+    epoch_prefix_regex: Regex,
+    epoch_unit: EpochUnit,
     // Simple field extraction patterns
     field_extraction_regex: Regex,
 }
@@ -21,6 +28,9 @@ impl PlainTextParser {
             timestamp_inference_regex: Regex::new(
                 r"(\d{4}-\d{2}-\d{2}[T\s]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)"
             ).unwrap(),
+            // 10 to 19 digit epochs, optionally bracketed or with a fraction: `1700000000.123 INFO ...`
+            epoch_prefix_regex: Regex::new(r"^\[?(\d{10,19}(?:\.\d+)?)\]?(?:\s|$)").unwrap(),
+            epoch_unit: EpochUnit::Auto,
             // Look for key=value or key:value patterns for basic field extraction
             field_extraction_regex: Regex::new(
                 r"([a-zA-Z0-9_.-]+)[:=]([^\s,;]+)"
@@ -28,33 +38,33 @@ impl PlainTextParser {
         }
     }
     
-    /// Attempt to infer timestamp from plain text
-    fn infer_timestamp(&self, line: &str) -> Option<DateTime<Utc>> {
-        if let Some(captures) = self.timestamp_inference_regex.captures(line) {
-            let timestamp_str = captures.get(1).unwrap().as_str();
-            
-            // Try parsing the inferred timestamp
-            if let Ok(dt) = DateTime::parse_from_rfc3339(timestamp_str) {
-                return Some(dt.with_timezone(&Utc));
-            }
-            
-            // Try ISO8601 without timezone
-            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S") {
-                return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-            }
-            
-            // Try space-separated format
-            if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S") {
-                return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
-            }
+    /// Parser reading epochs at the start of lines in `epoch_unit`
+    pub fn with_epoch_unit(mut self, epoch_unit: EpochUnit) -> Self {
+        self.epoch_unit = epoch_unit;
+        self
+    }
+    
+    /// Attempt to infer a catalog timestamp from plain text
+    fn infer_timestamp(&self, line: &str, learned: Option<TimestampFormat>) -> Option<Recognized> {
+        // Any catalog timestamp opening the line, bracketed or not
+        let opening = line.trim_start();
+        if let Some((recognized, _)) = timestamp::recognize_prefix(opening.strip_prefix('[').unwrap_or(opening), learned) {
+            return Some(recognized);
         }
         
-        None
+        let captures = self.timestamp_inference_regex.captures(line)?;
+        timestamp::recognize(captures.get(1).unwrap().as_str(), learned)
     }
     
-    /// Extract basic fields using regex patterns
-    fn extract_fields(&self, line: &str) -> std::collections::HashMap<String, serde_json::Value> {
-        let mut fields = std::collections::HashMap::new();
+    /// Epoch timestamp opening the line
+    fn infer_epoch(&self, line: &str) -> Option<LogTimestamp> {
+        let captures = self.epoch_prefix_regex.captures(line.trim_start())?;
+        parse_epoch_text(&captures[1], self.epoch_unit).map(LogTimestamp::Explicit)
+    }
+    
+    /// Extract basic fields using regex patterns, borrowing keys and text values
+    fn extract_fields<'a>(&self, line: &'a str) -> HashMap<Cow<'a, str>, ValueRef<'a>> {
+        let mut fields = HashMap::new();
         
         for captures in self.field_extraction_regex.captures_iter(line) {
             let key = captures.get(1).unwrap().as_str();
@@ -62,18 +72,18 @@ impl PlainTextParser {
             
             // Try to infer value type
             let json_value = if let Ok(num) = value.parse::<i64>() {
-                serde_json::Value::Number(serde_json::Number::from(num))
+                ValueRef::Number(serde_json::Number::from(num))
             } else if let Ok(float) = value.parse::<f64>() {
-                serde_json::Value::Number(serde_json::Number::from_f64(float).unwrap_or(serde_json::Number::from(0)))
+                ValueRef::Number(serde_json::Number::from_f64(float).unwrap_or(serde_json::Number::from(0)))
             } else if value.eq_ignore_ascii_case("true") {
-                serde_json::Value::Bool(true)
+                ValueRef::Bool(true)
             } else if value.eq_ignore_ascii_case("false") {
-                serde_json::Value::Bool(false)
+                ValueRef::Bool(false)
             } else {
-                serde_json::Value::String(value.to_string())
+                ValueRef::from(value)
             };
             
-            fields.insert(key.to_string(), json_value);
+            fields.insert(Cow::Borrowed(key), json_value);
         }
         
         fields
@@ -104,30 +114,40 @@ impl PlainTextParser {
 
 impl LogParser for PlainTextParser {
     fn parse(&self, line: &str) -> ParseResult {
+        self.parse_learned(line, None)
+    }
+    
+    fn parse_ref<'a>(&self, line: &'a str) -> ParseResultRef<'a> {
+        self.parse_learned_ref(line, None)
+    }
+    
+    fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
         let start_time = Instant::now();
-        
+        let result = self.parse_learned_ref(line, learned).into_owned();
+        result.with_processing_time(start_time.elapsed().as_micros() as u64)
+    }
+    
+    fn parse_learned_ref<'a>(&self, line: &'a str, learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
         // Plain text parser always succeeds - it's the fallback
-        let mut event = CanonicalEvent::new(
-            line.to_string(), // Entire line becomes the message
-            line.to_string(),
+        let mut event = CanonicalEventRef::new(
+            line, // Entire line becomes the message
+            line,
             FormatType::PlainText,
         );
         
         // Try to infer timestamp
-        if let Some(timestamp) = self.infer_timestamp(line) {
-            event.set_timestamp(timestamp);
+        let recognized = self.infer_timestamp(line, learned);
+        if let Some(recognized) = recognized {
+            event.set_recognized_timestamp(recognized);
+        } else if let Some(timestamp) = self.infer_epoch(line) {
+            event.set_log_timestamp(timestamp);
         }
         
         // Try to infer log level
-        if let Some(level) = self.infer_level(line) {
-            event.set_level(level);
-        }
+        event.level = self.infer_level(line);
         
         // Extract any basic fields we can find
-        let fields = self.extract_fields(line);
-        for (key, value) in fields {
-            event.add_field(key, value);
-        }
+        event.fields = self.extract_fields(line);
         
         // Set confidence based on how much we could infer
         let mut confidence = 0.1; // Base confidence for plain text
@@ -141,8 +161,7 @@ impl LogParser for PlainTextParser {
             confidence += 0.1;
         }
         
-        let processing_time = start_time.elapsed().as_micros() as u64;
-        ParseResult::success_with_timing(event, confidence, processing_time)
+        ParseResultRef::success(event, confidence).with_timestamp_format(recognized.map(|recognized| recognized.format))
     }
     
     fn can_parse(&self, _line: &str) -> bool {
@@ -215,6 +234,17 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_plain_text_parse_ref_borrows_line() {
+        let parser = PlainTextParser::new();
+        let line = "2025-12-29T10:21:03Z WARN user=admin count=5 ok=true";
+        
+        let result = parser.parse_ref(line);
+        assert!(matches!(result.event.message, Cow::Borrowed(_)));
+        assert_eq!(result.event.fields.get("user"), Some(&ValueRef::from("admin")));
+        assert_eq!(result.into_owned().event, parser.parse(line).event);
+    }
+    
     #[test]
     fn test_plain_text_always_can_parse() {
         let parser = PlainTextParser::new();
@@ -1,13 +1,16 @@
 use crate::models::*;
 use crate::error::ParseError;
-use crate::parse_result::ParseResult;
+use crate::parse_result::{ParseResult, ParseResultRef};
 use crate::parsers::LogParser;
 use crate::profiles::*;
+use crate::timestamp::TimestampFormat;
+use chrono_tz::Tz;
 use std::sync::Arc;
 
 /// Profile-based parser that uses user-defined parsing configurations
 pub struct ProfileParser {
     profile: Arc<dyn Profile>,
+    default_timezone: Option<Tz>,
 }
 
 impl ProfileParser {
@@ -16,6 +19,7 @@ impl ProfileParser {
         let profile = RegexProfile::new(config)?;
         Ok(Self {
             profile: Arc::new(profile),
+            default_timezone: None,
         })
     }
     
@@ -24,6 +28,7 @@ impl ProfileParser {
         let profile = CsvProfile::new(config)?;
         Ok(Self {
             profile: Arc::new(profile),
+            default_timezone: None,
         })
     }
     
@@ -32,28 +37,55 @@ impl ProfileParser {
         let profile = ApacheProfile::new();
         Self {
             profile: Arc::new(profile),
+            default_timezone: None,
         }
     }
     
+    /// Create a profile parser for an Apache `LogFormat` string or preset name
+    pub fn new_apache_format(format: &str) -> Result<Self, ParseError> {
+        Ok(Self::from_profile(Arc::new(ApacheProfile::with_format(format)?)))
+    }
+    
     /// Create a new profile parser with an Nginx profile
     pub fn new_nginx() -> Self {
         let profile = NginxProfile::new();
         Self {
             profile: Arc::new(profile),
+            default_timezone: None,
         }
     }
     
+    /// Create a profile parser for an nginx `log_format` definition or preset name
+    pub fn new_nginx_format(definition: &str) -> Result<Self, ParseError> {
+        Ok(Self::from_profile(Arc::new(NginxProfile::with_format(definition)?)))
+    }
+    
     /// Create a new profile parser with a Syslog profile
     pub fn new_syslog() -> Self {
         let profile = SyslogProfile::new();
         Self {
             profile: Arc::new(profile),
+            default_timezone: None,
         }
     }
     
     /// Create a profile parser from any profile implementation
     pub fn from_profile(profile: Arc<dyn Profile>) -> Self {
-        Self { profile }
+        Self {
+            profile,
+            default_timezone: None,
+        }
+    }
+    
+    /// Interpret this profile's timestamps without an offset in `tz`
+    pub fn with_default_timezone(mut self, tz: Tz) -> Self {
+        self.default_timezone = Some(tz);
+        self
+    }
+    
+    /// Timezone for timestamps without an offset, if the profile sets one
+    pub fn default_timezone(&self) -> Option<Tz> {
+        self.default_timezone
     }
     
     /// Get the underlying profile
@@ -69,7 +101,19 @@ impl ProfileParser {
 
 impl LogParser for ProfileParser {
     fn parse(&self, line: &str) -> ParseResult {
-        self.profile.parse(line)
+        self.parse_learned(line, None)
+    }
+    
+    fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
+        let result = self.profile.parse_learned(line, learned);
+        if result.success {
+            return result;
+        }
+        result.with_attempted_format(self.get_format_type())
+    }
+    
+    fn parse_learned_ref<'a>(&self, line: &'a str, learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
+        self.parse_learned(line, learned).into()
     }
     
     fn can_parse(&self, line: &str) -> bool {
@@ -101,6 +145,7 @@ mod tests {
             level_field: Some("level".to_string()),
             message_field: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let parser = ProfileParser::new_regex(config).unwrap();
@@ -129,6 +174,7 @@ mod tests {
             level_column: Some("level".to_string()),
             message_column: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let parser = ProfileParser::new_csv(config).unwrap();
@@ -195,6 +241,7 @@ mod tests {
             level_field: None,
             message_field: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let parser_result = ProfileParser::new_regex(config);
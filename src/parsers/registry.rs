@@ -0,0 +1,428 @@
+use crate::error::ParseError;
+use crate::field_mapping::FieldMappings;
+use crate::field_path::FlattenConfig;
+use crate::models::{FormatType, ParserName, ProfileType};
+use crate::parse_result::{ParseResult, ParseResultRef};
+use crate::timestamp::TimestampFormat;
+use crate::parsers::{CriParser, DockerJsonParser, JsonParser, KlogParser, LogParser, LogfmtParser, PatternParser, PlainTextParser, ProfileParser, Rfc5424Parser};
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Cheap check whether a line could be in a parser's format
+type Detector = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A parser with the name, priority and detection hook it is registered under
+#[derive(Clone)]
+pub struct RegisteredParser {
+    name: ParserName,
+    format_type: FormatType,
+    priority: i32,
+    builtin: bool,
+    parser: Arc<dyn LogParser + Send + Sync>,
+    detector: Option<Detector>,
+}
+
+impl RegisteredParser {
+    /// Custom parser whose events are reported as `FormatType::Custom(name)`.
+    /// Parsers with a priority above 0 are tried before the built-in formats,
+    /// the rest after them and before the plain-text fallback
+    pub fn new(name: impl Into<ParserName>, priority: i32, parser: Box<dyn LogParser + Send + Sync>) -> Self {
+        let name = name.into();
+        Self {
+            format_type: FormatType::Custom(name.clone()),
+            name,
+            priority,
+            builtin: false,
+            parser: Arc::from(parser),
+            detector: None,
+        }
+    }
+    
+    fn builtin(name: &str, format_type: FormatType, parser: impl LogParser + Send + Sync + 'static) -> Self {
+        Self {
+            name: name.into(),
+            format_type,
+            priority: 0,
+            builtin: true,
+            parser: Arc::new(parser),
+            detector: None,
+        }
+    }
+    
+    /// Only offer the parser lines `detect` accepts; without a hook the
+    /// parser's `can_parse` decides
+    pub fn with_detector(mut self, detect: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.detector = Some(Arc::new(detect));
+        self
+    }
+    
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    pub fn format_type(&self) -> FormatType {
+        self.format_type.clone()
+    }
+    
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+    
+    pub fn is_builtin(&self) -> bool {
+        self.builtin
+    }
+    
+    pub fn parser(&self) -> &(dyn LogParser + Send + Sync) {
+        self.parser.as_ref()
+    }
+    
+    /// Whether the line looks like this parser's format
+    pub fn detects(&self, line: &str) -> bool {
+        match &self.detector {
+            Some(detect) => detect(line),
+            None => self.parser.can_parse(line),
+        }
+    }
+    
+    pub fn parse(&self, line: &str) -> ParseResult {
+        self.parse_learned(line, None)
+    }
+    
+    pub fn parse_ref<'a>(&self, line: &'a str) -> ParseResultRef<'a> {
+        self.parse_learned_ref(line, None)
+    }
+    
+    /// Parse trying the timestamp format `learned` first, as `LogParser::parse_learned`
+    pub fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
+        let mut result = self.parser.parse_learned(line, learned);
+        if !result.success {
+            return result.with_attempted_format(self.format_type.clone());
+        }
+        if !self.builtin {
+            result.event.format_type = self.format_type.clone();
+        }
+        result
+    }
+    
+    pub fn parse_learned_ref<'a>(&self, line: &'a str, learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
+        let mut result = self.parser.parse_learned_ref(line, learned);
+        if !result.success {
+            return result.with_attempted_format(self.format_type.clone());
+        }
+        if !self.builtin {
+            result.event.format_type = self.format_type.clone();
+        }
+        result
+    }
+    
+    /// Place in the trial order: custom parsers above 0 by priority, the
+    /// built-ins in registration order, the other custom parsers by priority,
+    /// then plain text
+    fn rank(&self) -> (u8, Reverse<i32>) {
+        match (self.builtin, &self.format_type) {
+            (true, FormatType::PlainText) => (3, Reverse(0)),
+            (true, _) => (1, Reverse(0)),
+            (false, _) if self.priority > 0 => (0, Reverse(self.priority)),
+            (false, _) => (2, Reverse(self.priority)),
+        }
+    }
+}
+
+/// The parsers lines are tried against, in order: the built-in formats plus
+/// any registered by library users
+#[derive(Clone)]
+pub struct ParserRegistry {
+    parsers: Vec<RegisteredParser>,
+    /// Keys the built-in JSON and logfmt parsers read canonical fields from
+    field_mappings: FieldMappings,
+    /// How the built-in JSON parser flattens nested objects and arrays
+    flatten: FlattenConfig,
+    /// Logfmt for forced formats, taking lines with a single pair
+    forced_logfmt: RegisteredParser,
+}
+
+impl ParserRegistry {
+    /// Registry of the built-in parsers in detection order: Docker json-file,
+    /// CRI, klog, JSON, RFC 5424 syslog, the Apache, nginx and syslog profiles,
+    /// timestamp/level patterns, logfmt and the plain-text fallback
+    pub fn new() -> Self {
+        let field_mappings = FieldMappings::default();
+        let flatten = FlattenConfig::default();
+        Self {
+            parsers: vec![
+                // Envelopes go before JSON, which would take them for the event
+                RegisteredParser::builtin("docker", FormatType::DockerJson, DockerJsonParser::new()),
+                RegisteredParser::builtin("cri", FormatType::Cri, CriParser::new()),
+                RegisteredParser::builtin("klog", FormatType::Klog, KlogParser::new()),
+                Self::json_parser(&field_mappings, &flatten),
+                // Before logfmt, which would accept its structured data
+                RegisteredParser::builtin("rfc5424", FormatType::Rfc5424, Rfc5424Parser::new()),
+                // Before the looser timestamp/level patterns, which also match their lines
+                RegisteredParser::builtin("apache", FormatType::Profile(ProfileType::Apache), ProfileParser::new_apache()),
+                RegisteredParser::builtin("nginx", FormatType::Profile(ProfileType::Nginx), ProfileParser::new_nginx()),
+                RegisteredParser::builtin("syslog", FormatType::Profile(ProfileType::Syslog), ProfileParser::new_syslog()),
+                RegisteredParser::builtin("timestamp_level", FormatType::TimestampLevel, PatternParser::new()),
+                Self::logfmt_parser(&field_mappings),
+                Self::plain_parser(&field_mappings),
+            ],
+            forced_logfmt: Self::forced_logfmt_parser(&field_mappings),
+            field_mappings,
+            flatten,
+        }
+    }
+    
+    fn json_parser(field_mappings: &FieldMappings, flatten: &FlattenConfig) -> RegisteredParser {
+        let parser = JsonParser::with_field_mappings(field_mappings.clone()).with_flatten(flatten.clone());
+        // A full JSON parse is the detection; only lines opening an object are offered
+        RegisteredParser::builtin("json", FormatType::Json, parser)
+            .with_detector(|line| line.trim_start().starts_with('{'))
+    }
+    
+    fn logfmt_parser(field_mappings: &FieldMappings) -> RegisteredParser {
+        RegisteredParser::builtin("logfmt", FormatType::Logfmt, LogfmtParser::with_field_mappings(field_mappings.clone()))
+    }
+    
+    fn forced_logfmt_parser(field_mappings: &FieldMappings) -> RegisteredParser {
+        RegisteredParser::builtin("logfmt", FormatType::Logfmt, LogfmtParser::with_field_mappings(field_mappings.clone()).with_min_pairs(1))
+    }
+    
+    fn plain_parser(field_mappings: &FieldMappings) -> RegisteredParser {
+        RegisteredParser::builtin("plain", FormatType::PlainText, PlainTextParser::new().with_epoch_unit(field_mappings.epoch_unit))
+    }
+    
+    /// This registry with the built-in JSON and logfmt parsers reading canonical
+    /// fields from `field_mappings`, and plain text its epoch unit; registered
+    /// parsers are kept
+    pub fn with_field_mappings(mut self, field_mappings: FieldMappings) -> Self {
+        self.field_mappings = field_mappings;
+        self.rebuild_builtins();
+        self
+    }
+    
+    /// This registry with the built-in JSON parser flattening nested objects
+    /// and arrays as `flatten` says
+    pub fn with_flatten(mut self, flatten: FlattenConfig) -> Self {
+        self.flatten = flatten;
+        self.rebuild_builtins();
+        self
+    }
+    
+    fn rebuild_builtins(&mut self) {
+        for parser in &mut self.parsers {
+            *parser = match (parser.builtin, &parser.format_type) {
+                (true, FormatType::Json) => Self::json_parser(&self.field_mappings, &self.flatten),
+                (true, FormatType::Logfmt) => Self::logfmt_parser(&self.field_mappings),
+                (true, FormatType::PlainText) => Self::plain_parser(&self.field_mappings),
+                _ => continue,
+            };
+        }
+        self.forced_logfmt = Self::forced_logfmt_parser(&self.field_mappings);
+    }
+    
+    pub fn field_mappings(&self) -> &FieldMappings {
+        &self.field_mappings
+    }
+    
+    pub fn flatten(&self) -> &FlattenConfig {
+        &self.flatten
+    }
+    
+    /// Add a parser; names are unique, ignoring case
+    pub fn register(&mut self, parser: RegisteredParser) -> Result<(), ParseError> {
+        if parser.name.trim().is_empty() {
+            return Err(ParseError::ConfigurationError {
+                parameter: "parser_registry".to_string(),
+                error_message: "Parser name must not be empty".to_string(),
+            });
+        }
+        if self.get(&parser.name).is_some() {
+            return Err(ParseError::ConfigurationError {
+                parameter: "parser_registry".to_string(),
+                error_message: format!("A parser named '{}' is already registered", parser.name),
+            });
+        }
+        
+        self.parsers.push(parser);
+        self.parsers.sort_by_key(RegisteredParser::rank);
+        Ok(())
+    }
+    
+    /// Parser registered under `name`, ignoring case
+    pub fn get(&self, name: &str) -> Option<&RegisteredParser> {
+        self.parsers.iter().find(|parser| parser.name.eq_ignore_ascii_case(name))
+    }
+    
+    /// Parser producing events of `format_type`
+    pub fn for_format(&self, format_type: FormatType) -> Option<&RegisteredParser> {
+        let format_type = match format_type {
+            FormatType::Pattern => FormatType::TimestampLevel,
+            other => other,
+        };
+        self.parsers.iter().find(|parser| parser.format_type == format_type)
+    }
+    
+    /// Parser for lines forced to be in `format_type`: as `for_format`, except
+    /// that logfmt takes any line with a key=value pair, not the 3 detection wants
+    pub fn for_forced_format(&self, format_type: FormatType) -> Option<&RegisteredParser> {
+        match format_type {
+            FormatType::Logfmt => Some(&self.forced_logfmt),
+            other => self.for_format(other),
+        }
+    }
+    
+    /// Parsers in the order lines are tried against them
+    pub fn parsers(&self) -> &[RegisteredParser] {
+        &self.parsers
+    }
+    
+    /// Registered names in trial order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.parsers.iter().map(RegisteredParser::name)
+    }
+    
+    /// Custom parsers tried before the built-in formats
+    pub fn custom_before_builtins(&self) -> impl Iterator<Item = &RegisteredParser> {
+        self.parsers.iter().filter(|parser| !parser.builtin && parser.priority > 0)
+    }
+    
+    /// Custom parsers tried after the built-in formats, before plain text
+    pub fn custom_after_builtins(&self) -> impl Iterator<Item = &RegisteredParser> {
+        self.parsers.iter().filter(|parser| !parser.builtin && parser.priority <= 0)
+    }
+    
+    /// Parse with the parser for `format_type`; formats without one are parsed as plain text
+    pub fn parse_as(&self, format_type: FormatType, line: &str) -> ParseResult {
+        self.parse_as_learned(format_type, line, None)
+    }
+    
+    /// `parse_as`, trying the timestamp format `learned` first
+    pub fn parse_as_learned(&self, format_type: FormatType, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
+        self.for_format(format_type).unwrap_or_else(|| self.plain_text()).parse_learned(line, learned)
+    }
+    
+    /// `parse_as_learned` into an event borrowing from the line
+    pub fn parse_as_learned_ref<'a>(&self, format_type: FormatType, line: &'a str, learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
+        self.for_format(format_type).unwrap_or_else(|| self.plain_text()).parse_learned_ref(line, learned)
+    }
+    
+    /// Parse with the first parser, in trial order, that detects the line and
+    /// parses it; plain text takes whatever is left
+    pub fn parse_first(&self, line: &str) -> (FormatType, ParseResult) {
+        for parser in &self.parsers {
+            if parser.detects(line) {
+                let result = parser.parse(line);
+                if result.success {
+                    return (parser.format_type(), result);
+                }
+            }
+        }
+        (FormatType::PlainText, self.plain_text().parse(line))
+    }
+    
+    /// Why a line left to plain text is in none of the formats: the error of the
+    /// first parser, in trial order, that detects the line but cannot parse it,
+    /// else a pattern mismatch naming every format tried
+    pub fn unmatched_error(&self, line: &str) -> ParseError {
+        self.parsers.iter()
+            .filter(|parser| parser.format_type != FormatType::PlainText && parser.detects(line))
+            .find_map(|parser| parser.parse(line).error)
+            .unwrap_or_else(|| ParseError::PatternMatchError {
+                input: line.to_string(),
+                attempted_patterns: self.parsers.iter()
+                    .filter(|parser| parser.format_type != FormatType::PlainText)
+                    .map(|parser| parser.name().to_string())
+                    .collect(),
+            })
+    }
+    
+    /// The plain-text fallback, registered last
+    pub fn plain_text(&self) -> &RegisteredParser {
+        self.for_format(FormatType::PlainText).expect("plain-text parser is always registered")
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CanonicalEvent;
+    
+    /// Parses `k8s|<namespace>|<message>` lines
+    struct PipeParser;
+    
+    impl LogParser for PipeParser {
+        fn parse(&self, line: &str) -> ParseResult {
+            match line.strip_prefix("k8s|").and_then(|rest| rest.split_once('|')) {
+                Some((namespace, message)) => {
+                    let mut event = CanonicalEvent::new(message.to_string(), line.to_string(), FormatType::PlainText);
+                    event.add_field("namespace".to_string(), namespace);
+                    ParseResult::success(event, 0.9)
+                }
+                None => ParseResult::failure(line.to_string(), ParseError::PatternMatchError {
+                    input: line.to_string(),
+                    attempted_patterns: vec!["k8s|namespace|message".to_string()],
+                }),
+            }
+        }
+        
+        fn can_parse(&self, line: &str) -> bool {
+            line.starts_with("k8s|")
+        }
+        
+        fn get_format_type(&self) -> FormatType {
+            FormatType::PlainText
+        }
+    }
+    
+    #[test]
+    fn test_custom_parsers_take_their_place_in_trial_order() {
+        let mut registry = ParserRegistry::new();
+        registry.register(RegisteredParser::new("late", -5, Box::new(PipeParser))).unwrap();
+        registry.register(RegisteredParser::new("pipe", 10, Box::new(PipeParser))).unwrap();
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["pipe", "docker", "cri", "klog", "json", "rfc5424", "apache", "nginx", "syslog", "timestamp_level", "logfmt", "late", "plain"]
+        );
+        
+        let (format_type, result) = registry.parse_first("k8s|prod|pod restarted");
+        assert_eq!(format_type, FormatType::Custom("pipe".into()));
+        assert_eq!(result.event.format_type, FormatType::Custom("pipe".into()));
+        assert_eq!(result.event.message, "pod restarted");
+        
+        let (format_type, _) = registry.parse_first(r#"{"msg":"hello"}"#);
+        assert_eq!(format_type, FormatType::Json);
+        assert_eq!(registry.get("PIPE").unwrap().format_type(), FormatType::Custom("pipe".into()));
+        assert_eq!(registry.parse_as(FormatType::Custom("late".into()), "k8s|dev|up").event.message, "up");
+        
+        let duplicate = registry.register(RegisteredParser::new("Json", 1, Box::new(PipeParser)));
+        assert!(matches!(duplicate, Err(ParseError::ConfigurationError { .. })));
+    }
+    
+    #[test]
+    fn test_detector_gates_parser() {
+        let mut registry = ParserRegistry::new();
+        registry.register(RegisteredParser::new("pipe", 10, Box::new(PipeParser))
+            .with_detector(|line| line.ends_with('!'))).unwrap();
+        
+        assert_eq!(registry.parse_first("k8s|prod|pod restarted").0, FormatType::PlainText);
+        assert_eq!(registry.parse_first("k8s|prod|pod restarted!").0, FormatType::Custom("pipe".into()));
+    }
+    
+    #[test]
+    fn test_names_chosen_at_runtime_round_trip() {
+        let name = ["k8s", "pipe"].join("_");
+        let mut registry = ParserRegistry::new();
+        registry.register(RegisteredParser::new(name, 10, Box::new(PipeParser))).unwrap();
+        
+        let event = registry.parse_first("k8s|prod|up").1.event;
+        let json = serde_json::to_string(&event).unwrap();
+        let read_back: CanonicalEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(read_back.format_type, FormatType::Custom("k8s_pipe".into()));
+        assert_eq!(read_back.format_type.to_string(), "k8s_pipe");
+    }
+}
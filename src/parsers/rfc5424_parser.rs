@@ -0,0 +1,290 @@
+use crate::models::*;
+use crate::error::ParseError;
+use crate::parse_result::ParseResult;
+use crate::parsers::LogParser;
+use crate::profiles::SyslogProfile;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::time::Instant;
+
+/// NILVALUE used for absent header fields and structured data
+const NIL: &str = "-";
+
+/// RFC 5424 syslog parser:
+/// `<pri>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]`
+#[derive(Clone)]
+pub struct Rfc5424Parser {
+    header_regex: Regex,
+    syslog: SyslogProfile,
+}
+
+impl Rfc5424Parser {
+    pub fn new() -> Self {
+        Self {
+            // PRI, VERSION and the five space-separated header fields; the rest is SD and MSG
+            header_regex: Regex::new(r"^<(\d{1,3})>([1-9]\d{0,2}) (\S+) (\S+) (\S+) (\S+) (\S+) (.*)$").unwrap(),
+            syslog: SyslogProfile::new(),
+        }
+    }
+    
+    /// Parse STRUCTURED-DATA into `{sd-id: {param: value}}`, returning the remaining input
+    pub fn parse_structured_data<'a>(&self, input: &'a str) -> Result<(Map<String, Value>, &'a str), String> {
+        let mut elements = Map::new();
+        
+        if let Some(rest) = input.strip_prefix(NIL) {
+            return Ok((elements, rest));
+        }
+        
+        let mut rest = input;
+        if !rest.starts_with('[') {
+            return Err("expected '[' or '-'".to_string());
+        }
+        
+        while let Some(element) = rest.strip_prefix('[') {
+            let id_end = element.find([' ', ']']).ok_or("unterminated SD-ELEMENT")?;
+            let sd_id = &element[..id_end];
+            if sd_id.is_empty() {
+                return Err("empty SD-ID".to_string());
+            }
+            
+            let mut params = match elements.remove(sd_id) {
+                Some(Value::Object(existing)) => existing,
+                _ => Map::new(),
+            };
+            let mut cursor = &element[id_end..];
+            
+            loop {
+                if let Some(after) = cursor.strip_prefix(']') {
+                    cursor = after;
+                    break;
+                }
+                cursor = cursor.strip_prefix(' ')
+                    .ok_or_else(|| format!("expected ' ' or ']' in SD-ELEMENT '{}'", sd_id))?;
+                
+                let name_end = cursor.find('=')
+                    .ok_or_else(|| format!("missing '=' in SD-ELEMENT '{}'", sd_id))?;
+                let name = &cursor[..name_end];
+                let value_start = cursor[name_end + 1..].strip_prefix('"')
+                    .ok_or_else(|| format!("unquoted value for '{}' in SD-ELEMENT '{}'", name, sd_id))?;
+                
+                let (value, after) = Self::read_param_value(value_start)
+                    .ok_or_else(|| format!("unterminated value for '{}' in SD-ELEMENT '{}'", name, sd_id))?;
+                params.insert(name.to_string(), Value::String(value));
+                cursor = after;
+            }
+            
+            elements.insert(sd_id.to_string(), Value::Object(params));
+            rest = cursor;
+        }
+        
+        Ok((elements, rest))
+    }
+    
+    /// Read a PARAM-VALUE up to its closing quote, unescaping `\"`, `\\` and `\]`
+    fn read_param_value(input: &str) -> Option<(String, &str)> {
+        let mut value = String::new();
+        let mut chars = input.char_indices();
+        
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Some((value, &input[i + 1..])),
+                '\\' => match chars.clone().next() {
+                    Some((_, escaped @ ('"' | '\\' | ']'))) => {
+                        value.push(escaped);
+                        chars.next();
+                    }
+                    // Any other backslash is kept as-is
+                    _ => value.push('\\'),
+                },
+                _ => value.push(c),
+            }
+        }
+        None
+    }
+    
+    fn parse_timestamp(timestamp_str: &str) -> Result<Option<DateTime<Utc>>, ParseError> {
+        if timestamp_str == NIL {
+            return Ok(None);
+        }
+        DateTime::parse_from_rfc3339(timestamp_str)
+            .map(|dt| Some(dt.with_timezone(&Utc)))
+            .map_err(|_| ParseError::TimestampParseError {
+                input: timestamp_str.to_string(),
+                attempted_formats: vec!["RFC3339".to_string()],
+            })
+    }
+    
+    fn failure(line: &str, error: ParseError, start_time: Instant) -> ParseResult {
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        ParseResult::failure_with_context(
+            line.to_string(),
+            error,
+            None,
+            Some(processing_time),
+        )
+    }
+}
+
+impl Default for Rfc5424Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogParser for Rfc5424Parser {
+    fn parse(&self, line: &str) -> ParseResult {
+        let start_time = Instant::now();
+        
+        let captures = match self.header_regex.captures(line) {
+            Some(captures) => captures,
+            None => {
+                let error = ParseError::PatternMatchError {
+                    input: line.to_string(),
+                    attempted_patterns: vec![self.header_regex.as_str().to_string()],
+                };
+                return Self::failure(line, error, start_time);
+            }
+        };
+        
+        let timestamp = match Self::parse_timestamp(&captures[3]) {
+            Ok(timestamp) => timestamp,
+            Err(error) => return Self::failure(line, error, start_time),
+        };
+        
+        let (structured_data, rest) = match self.parse_structured_data(captures.get(8).unwrap().as_str()) {
+            Ok(parsed) => parsed,
+            Err(error_message) => {
+                let error = ParseError::FieldExtractionError {
+                    field_name: "structured_data".to_string(),
+                    error_message,
+                };
+                return Self::failure(line, error, start_time);
+            }
+        };
+        
+        // MSG is separated from STRUCTURED-DATA by a single space and may carry a UTF-8 BOM
+        let message = match rest.strip_prefix(' ') {
+            Some(msg) => msg.trim_start_matches('\u{feff}').to_string(),
+            None if rest.is_empty() => String::new(),
+            None => {
+                let error = ParseError::FieldExtractionError {
+                    field_name: "structured_data".to_string(),
+                    error_message: format!("unexpected text after STRUCTURED-DATA: '{}'", rest),
+                };
+                return Self::failure(line, error, start_time);
+            }
+        };
+        
+        let mut event = CanonicalEvent::new(message, line.to_string(), FormatType::Rfc5424);
+        
+        let (facility, level) = self.syslog.parse_syslog_priority(&captures[1]);
+        if let Some(facility) = facility {
+            event.add_field("facility".to_string(), Value::String(facility));
+        }
+        if let Some(level) = level {
+            event.set_level(level);
+        }
+        if let Some(timestamp) = timestamp {
+            event.set_log_timestamp(LogTimestamp::Explicit(timestamp));
+        }
+        
+        if let Ok(version) = captures[2].parse::<u64>() {
+            event.add_field("version".to_string(), Value::from(version));
+        }
+        
+        // Header fields holding the NILVALUE are omitted
+        let header_fields = [("hostname", 4), ("app_name", 5), ("procid", 6), ("msgid", 7)];
+        for (name, group) in header_fields {
+            let value = &captures[group];
+            if value != NIL {
+                event.add_field(name.to_string(), Value::String(value.to_string()));
+            }
+        }
+        
+        if !structured_data.is_empty() {
+            event.add_field("structured_data".to_string(), Value::Object(structured_data));
+        }
+        
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        ParseResult::success_with_timing(event, 0.95, processing_time)
+    }
+    
+    fn can_parse(&self, line: &str) -> bool {
+        self.header_regex.is_match(line)
+    }
+    
+    fn get_format_type(&self) -> FormatType {
+        FormatType::Rfc5424
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    #[test]
+    fn test_parse_full_header_and_structured_data() {
+        let parser = Rfc5424Parser::new();
+        let line = r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog 1234 ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"][examplePriority@32473 class="high"] An application event"#;
+        
+        let result = parser.parse(line);
+        assert!(result.success);
+        
+        let event = result.event;
+        assert_eq!(event.format_type, FormatType::Rfc5424);
+        assert_eq!(event.message, "An application event");
+        assert_eq!(event.level, Some(LogLevel::Info));
+        assert_eq!(event.timestamp, Some(Utc.with_ymd_and_hms(2003, 10, 11, 22, 14, 15).unwrap() + chrono::Duration::milliseconds(3)));
+        assert_eq!(event.fields["facility"], "local4");
+        assert_eq!(event.fields["version"], 1);
+        assert_eq!(event.fields["hostname"], "mymachine.example.com");
+        assert_eq!(event.fields["app_name"], "evntslog");
+        assert_eq!(event.fields["procid"], "1234");
+        assert_eq!(event.fields["msgid"], "ID47");
+        assert_eq!(event.fields["structured_data"]["exampleSDID@32473"]["eventID"], "1011");
+        assert_eq!(event.fields["structured_data"]["examplePriority@32473"]["class"], "high");
+    }
+    
+    #[test]
+    fn test_nil_values_and_missing_message() {
+        let parser = Rfc5424Parser::new();
+        let result = parser.parse("<34>1 - - su - - -");
+        assert!(result.success);
+        
+        let event = result.event;
+        assert!(event.timestamp.is_none());
+        assert_eq!(event.level, Some(LogLevel::Fatal));
+        assert_eq!(event.fields["app_name"], "su");
+        assert!(!event.fields.contains_key("hostname"));
+        assert!(!event.fields.contains_key("structured_data"));
+        assert_eq!(event.message, "");
+    }
+    
+    #[test]
+    fn test_escaped_param_values_and_bom() {
+        let parser = Rfc5424Parser::new();
+        let line = "<13>1 2025-01-01T10:00:00+02:00 host app - - [meta q=\"a \\\"b\\\" \\]c\\\\ \\d\"] \u{feff}hello";
+        
+        let result = parser.parse(line);
+        assert!(result.success);
+        assert_eq!(result.event.fields["structured_data"]["meta"]["q"], "a \"b\" ]c\\ \\d");
+        assert_eq!(result.event.message, "hello");
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap()));
+    }
+    
+    #[test]
+    fn test_rejects_malformed_lines() {
+        let parser = Rfc5424Parser::new();
+        
+        // RFC 3164 has no version after the priority
+        assert!(!parser.can_parse("<34>Oct 11 22:14:15 mymachine su: 'su root' failed"));
+        
+        let result = parser.parse(r#"<34>1 2025-01-01T00:00:00Z host app - - [meta q="unterminated] msg"#);
+        assert!(!result.success);
+        
+        let result = parser.parse("<34>1 yesterday host app - - - msg");
+        assert!(!result.success);
+    }
+}
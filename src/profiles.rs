@@ -1,10 +1,14 @@
 use crate::models::*;
 use crate::error::ParseError;
 use crate::parse_result::ParseResult;
-use chrono::{DateTime, Utc, Datelike};
+use crate::year_inference::{assume_year, PLACEHOLDER_YEAR};
+use crate::log_format::CompiledLogFormat;
+use crate::timestamp::{self, TimestampFormat};
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use std::time::Instant;
 
 /// Profile trait for user-defined parsing configurations
@@ -12,6 +16,12 @@ pub trait Profile: Send + Sync {
     /// Parse a log line using this profile
     fn parse(&self, line: &str) -> ParseResult;
     
+    /// Parse trying `learned`, the timestamp format the line's source last
+    /// matched, first; profiles reading no catalog timestamps ignore it
+    fn parse_learned(&self, line: &str, _learned: Option<TimestampFormat>) -> ParseResult {
+        self.parse(line)
+    }
+    
     /// Check if this profile can parse the given line
     fn can_parse(&self, line: &str) -> bool;
     
@@ -25,13 +35,52 @@ pub trait Profile: Send + Sync {
 /// Configuration for regex-based profiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegexProfileConfig {
+    #[serde(default)]
     pub name: String,
     pub pattern: String,
+    #[serde(default)]
     pub field_mappings: HashMap<String, usize>, // field_name -> capture_group_index
     pub timestamp_field: Option<String>,
     pub level_field: Option<String>,
     pub message_field: Option<String>,
     pub timestamp_format: Option<String>,
+    /// IANA timezone for timestamps without an offset (overrides `TangoConfig::default_timezone`)
+    #[serde(default)]
+    pub default_timezone: Option<String>,
+}
+
+/// Classify a timestamp parsed without an offset; a literal trailing `Z` in the format means UTC
+fn naive_log_timestamp(naive_dt: chrono::NaiveDateTime, format: &str) -> LogTimestamp {
+    if format.ends_with('Z') {
+        LogTimestamp::Explicit(naive_dt.and_utc())
+    } else {
+        LogTimestamp::Naive(naive_dt)
+    }
+}
+
+/// Set the timestamp in a profile's field: its custom `timestamp_format` first,
+/// then the timestamp catalog, trying `learned` first; returns the catalog
+/// format it was recognized in
+fn set_profile_timestamp(
+    event: &mut CanonicalEvent,
+    timestamp_str: &str,
+    custom_format: Option<&str>,
+    learned: Option<TimestampFormat>,
+) -> Option<TimestampFormat> {
+    if let Some(format) = custom_format {
+        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, format) {
+            event.set_log_timestamp(LogTimestamp::Explicit(dt.with_timezone(&Utc)));
+            return None;
+        }
+        if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, format) {
+            event.set_log_timestamp(naive_log_timestamp(naive_dt, format));
+            return None;
+        }
+    }
+    
+    let recognized = timestamp::recognize(timestamp_str, learned)?;
+    event.set_recognized_timestamp(recognized);
+    Some(recognized.format)
 }
 
 /// Regex-based profile parser
@@ -59,43 +108,9 @@ impl RegexProfile {
         Ok(profile)
     }
     
-    fn extract_timestamp(&self, _captures: &regex::Captures, fields: &HashMap<String, String>) -> Option<DateTime<Utc>> {
-        if let Some(timestamp_field) = &self.config.timestamp_field {
-            if let Some(timestamp_str) = fields.get(timestamp_field) {
-                return self.parse_timestamp_string(timestamp_str);
-            }
-        }
-        None
-    }
-    
-    fn parse_timestamp_string(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
-        // Try custom format first if specified
-        if let Some(format) = &self.config.timestamp_format {
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, format) {
-                return Some(DateTime::from_naive_utc_and_offset(naive_dt, Utc));
-            }
-        }
-        
-        // Try common formats
-        let formats = [
-            "%Y-%m-%dT%H:%M:%S%.fZ",           // ISO8601 with fractional seconds
-            "%Y-%m-%dT%H:%M:%SZ",              // ISO8601
-            "%Y-%m-%d %H:%M:%S%.f",            // Common log format with fractional seconds
-            "%Y-%m-%d %H:%M:%S",               // Common log format
-            "%d/%b/%Y:%H:%M:%S %z",            // Apache Common Log Format
-            "%b %d %H:%M:%S",                  // Syslog format
-        ];
-        
-        for format in &formats {
-            if let Ok(dt) = DateTime::parse_from_str(timestamp_str, format) {
-                return Some(dt.with_timezone(&Utc));
-            }
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, format) {
-                return Some(DateTime::from_naive_utc_and_offset(naive_dt, Utc));
-            }
-        }
-        
-        None
+    fn extract_timestamp(&self, event: &mut CanonicalEvent, fields: &HashMap<String, String>, learned: Option<TimestampFormat>) -> Option<TimestampFormat> {
+        let timestamp_str = fields.get(self.config.timestamp_field.as_ref()?)?;
+        set_profile_timestamp(event, timestamp_str, self.config.timestamp_format.as_deref(), learned)
     }
     
     fn extract_level(&self, fields: &HashMap<String, String>) -> Option<LogLevel> {
@@ -121,6 +136,10 @@ impl RegexProfile {
 
 impl Profile for RegexProfile {
     fn parse(&self, line: &str) -> ParseResult {
+        self.parse_learned(line, None)
+    }
+    
+    fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
         let start_time = Instant::now();
         
         match self.compiled_regex.captures(line) {
@@ -140,9 +159,7 @@ impl Profile for RegexProfile {
                 }
                 
                 // Extract timestamp
-                if let Some(timestamp) = self.extract_timestamp(&captures, &extracted_fields) {
-                    event.set_timestamp(timestamp);
-                }
+                let timestamp_format = self.extract_timestamp(&mut event, &extracted_fields, learned);
                 
                 // Extract level
                 if let Some(level) = self.extract_level(&extracted_fields) {
@@ -165,7 +182,7 @@ impl Profile for RegexProfile {
                 }
                 
                 let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::success_with_timing(event, 0.9, processing_time)
+                ParseResult::success_with_timing(event, 0.9, processing_time).with_timestamp_format(timestamp_format)
             }
             None => {
                 let error = ParseError::PatternMatchError {
@@ -234,14 +251,24 @@ impl Profile for RegexProfile {
 /// Configuration for CSV-based profiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CsvProfileConfig {
+    #[serde(default)]
     pub name: String,
+    #[serde(default = "default_csv_delimiter")]
     pub delimiter: char,
+    #[serde(default)]
     pub has_header: bool,
     pub column_mappings: HashMap<String, usize>, // field_name -> column_index
     pub timestamp_column: Option<String>,
     pub level_column: Option<String>,
     pub message_column: Option<String>,
     pub timestamp_format: Option<String>,
+    /// IANA timezone for timestamps without an offset (overrides `TangoConfig::default_timezone`)
+    #[serde(default)]
+    pub default_timezone: Option<String>,
+}
+
+fn default_csv_delimiter() -> char {
+    ','
 }
 
 /// CSV-based profile parser
@@ -292,45 +319,10 @@ impl CsvProfile {
         fields
     }
     
-    fn extract_timestamp(&self, fields: &[String]) -> Option<DateTime<Utc>> {
-        if let Some(timestamp_column) = &self.config.timestamp_column {
-            if let Some(&column_index) = self.config.column_mappings.get(timestamp_column) {
-                if let Some(timestamp_str) = fields.get(column_index) {
-                    return self.parse_timestamp_string(timestamp_str);
-                }
-            }
-        }
-        None
-    }
-    
-    fn parse_timestamp_string(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
-        // Try custom format first if specified
-        if let Some(format) = &self.config.timestamp_format {
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, format) {
-                return Some(DateTime::from_naive_utc_and_offset(naive_dt, Utc));
-            }
-        }
-        
-        // Try common formats (same as RegexProfile)
-        let formats = [
-            "%Y-%m-%dT%H:%M:%S%.fZ",
-            "%Y-%m-%dT%H:%M:%SZ",
-            "%Y-%m-%d %H:%M:%S%.f",
-            "%Y-%m-%d %H:%M:%S",
-            "%d/%b/%Y:%H:%M:%S %z",
-            "%b %d %H:%M:%S",
-        ];
-        
-        for format in &formats {
-            if let Ok(dt) = DateTime::parse_from_str(timestamp_str, format) {
-                return Some(dt.with_timezone(&Utc));
-            }
-            if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(timestamp_str, format) {
-                return Some(DateTime::from_naive_utc_and_offset(naive_dt, Utc));
-            }
-        }
-        
-        None
+    fn extract_timestamp(&self, event: &mut CanonicalEvent, fields: &[String], learned: Option<TimestampFormat>) -> Option<TimestampFormat> {
+        let column_index = *self.config.column_mappings.get(self.config.timestamp_column.as_ref()?)?;
+        let timestamp_str = fields.get(column_index)?;
+        set_profile_timestamp(event, timestamp_str, self.config.timestamp_format.as_deref(), learned)
     }
     
     fn extract_level(&self, fields: &[String]) -> Option<LogLevel> {
@@ -360,6 +352,10 @@ impl CsvProfile {
 
 impl Profile for CsvProfile {
     fn parse(&self, line: &str) -> ParseResult {
+        self.parse_learned(line, None)
+    }
+    
+    fn parse_learned(&self, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
         let start_time = Instant::now();
         
         let fields = self.parse_csv_line(line);
@@ -371,9 +367,7 @@ impl Profile for CsvProfile {
         );
         
         // Extract timestamp
-        if let Some(timestamp) = self.extract_timestamp(&fields) {
-            event.set_timestamp(timestamp);
-        }
+        let timestamp_format = self.extract_timestamp(&mut event, &fields, learned);
         
         // Extract level
         if let Some(level) = self.extract_level(&fields) {
@@ -398,7 +392,7 @@ impl Profile for CsvProfile {
         }
         
         let processing_time = start_time.elapsed().as_micros() as u64;
-        ParseResult::success_with_timing(event, 0.85, processing_time)
+        ParseResult::success_with_timing(event, 0.85, processing_time).with_timestamp_format(timestamp_format)
     }
     
     fn can_parse(&self, line: &str) -> bool {
@@ -443,103 +437,70 @@ impl Profile for CsvProfile {
     }
 }
 
-/// Apache Common Log Format profile
-pub struct ApacheProfile;
+/// Parse a line with the first access log format that matches it
+fn parse_access_log(formats: &[CompiledLogFormat], line: &str, profile_type: ProfileType) -> ParseResult {
+    let start_time = Instant::now();
+    
+    match formats.iter().find_map(|format| format.parse(line, profile_type)) {
+        Some(event) => {
+            let processing_time = start_time.elapsed().as_micros() as u64;
+            ParseResult::success_with_timing(event, 0.9, processing_time)
+        }
+        None => {
+            let error = ParseError::PatternMatchError {
+                input: line.to_string(),
+                attempted_patterns: formats.iter().map(|format| format.pattern().to_string()).collect(),
+            };
+            
+            let processing_time = start_time.elapsed().as_micros() as u64;
+            ParseResult::failure_with_context(
+                line.to_string(),
+                error,
+                None,
+                Some(processing_time),
+            )
+        }
+    }
+}
+
+/// Apache access log profile, driven by a `LogFormat` string
+#[derive(Clone)]
+pub struct ApacheProfile {
+    formats: Vec<CompiledLogFormat>,
+}
 
 impl ApacheProfile {
+    /// Combined Log Format, falling back to Common Log Format
     pub fn new() -> Self {
-        Self
+        // Compiled once; every classifier holds a copy
+        static DEFAULT: OnceLock<ApacheProfile> = OnceLock::new();
+        DEFAULT.get_or_init(|| {
+            let formats = ["combined", "common"].iter()
+                .map(|preset| CompiledLogFormat::apache(preset).expect("built-in preset compiles"))
+                .collect();
+            Self { formats }
+        }).clone()
     }
     
-    fn get_apache_regex() -> &'static str {
-        // Apache Common Log Format: host ident authuser [timestamp] "request" status size
-        r#"^(\S+) (\S+) (\S+) \[([^\]]+)\] "([^"]*)" (\d+) (\S+)"#
+    /// Profile for a `LogFormat` string or preset name (`common`, `combined`, `vhost_combined`)
+    pub fn with_format(format: &str) -> Result<Self, ParseError> {
+        Ok(Self { formats: vec![CompiledLogFormat::apache(format)?] })
     }
-    
-    fn parse_apache_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
-        // Apache timestamp format: "10/Oct/2000:13:55:36 -0700"
-        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, "%d/%b/%Y:%H:%M:%S %z") {
-            return Some(dt.with_timezone(&Utc));
-        }
-        None
+}
+
+impl Default for ApacheProfile {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Profile for ApacheProfile {
     fn parse(&self, line: &str) -> ParseResult {
-        let start_time = Instant::now();
-        
-        let regex = Regex::new(Self::get_apache_regex()).unwrap();
-        
-        match regex.captures(line) {
-            Some(captures) => {
-                let mut event = CanonicalEvent::new(
-                    String::new(), // Will be set below
-                    line.to_string(),
-                    FormatType::Profile(ProfileType::Apache),
-                );
-                
-                // Extract fields according to Apache Common Log Format
-                if let Some(client_ip) = captures.get(1) {
-                    event.add_field("client_ip".to_string(), serde_json::Value::String(client_ip.as_str().to_string()));
-                }
-                
-                if let Some(timestamp_match) = captures.get(4) {
-                    if let Some(timestamp) = self.parse_apache_timestamp(timestamp_match.as_str()) {
-                        event.set_timestamp(timestamp);
-                    }
-                }
-                
-                if let Some(request) = captures.get(5) {
-                    event.message = request.as_str().to_string();
-                    event.add_field("request".to_string(), serde_json::Value::String(request.as_str().to_string()));
-                }
-                
-                if let Some(status) = captures.get(6) {
-                    if let Ok(status_code) = status.as_str().parse::<u16>() {
-                        event.add_field("status".to_string(), serde_json::Value::Number(status_code.into()));
-                        
-                        // Set log level based on status code
-                        let level = match status_code {
-                            200..=299 => LogLevel::Info,
-                            300..=399 => LogLevel::Info,
-                            400..=499 => LogLevel::Warn,
-                            500..=599 => LogLevel::Error,
-                            _ => LogLevel::Info,
-                        };
-                        event.set_level(level);
-                    }
-                }
-                
-                if let Some(size) = captures.get(7) {
-                    if let Ok(size_bytes) = size.as_str().parse::<u64>() {
-                        event.add_field("size".to_string(), serde_json::Value::Number(size_bytes.into()));
-                    }
-                }
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::success_with_timing(event, 0.9, processing_time)
-            }
-            None => {
-                let error = ParseError::PatternMatchError {
-                    input: line.to_string(),
-                    attempted_patterns: vec![Self::get_apache_regex().to_string()],
-                };
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::failure_with_context(
-                    line.to_string(),
-                    error,
-                    None,
-                    Some(processing_time),
-                )
-            }
-        }
+        parse_access_log(&self.formats, line, ProfileType::Apache)
     }
     
     fn can_parse(&self, line: &str) -> bool {
-        let regex = Regex::new(Self::get_apache_regex()).unwrap();
-        regex.is_match(line)
+        self.formats.iter().any(|format| format.is_match(line))
     }
     
     fn get_profile_type(&self) -> ProfileType {
@@ -547,116 +508,45 @@ impl Profile for ApacheProfile {
     }
     
     fn validate(&self) -> Result<(), ParseError> {
-        // Apache profile is always valid
+        // Formats are validated when compiled
         Ok(())
     }
 }
 
-/// Nginx access log profile
-pub struct NginxProfile;
+/// Nginx access log profile, driven by a `log_format` definition
+#[derive(Clone)]
+pub struct NginxProfile {
+    formats: Vec<CompiledLogFormat>,
+}
 
 impl NginxProfile {
+    /// nginx's predefined `combined` format
     pub fn new() -> Self {
-        Self
+        static DEFAULT: OnceLock<NginxProfile> = OnceLock::new();
+        DEFAULT.get_or_init(|| {
+            Self { formats: vec![CompiledLogFormat::nginx("combined").expect("built-in preset compiles")] }
+        }).clone()
     }
     
-    fn get_nginx_regex() -> &'static str {
-        // Nginx default log format: host - - [timestamp] "request" status size "referer" "user_agent"
-        r#"^(\S+) - - \[([^\]]+)\] "([^"]*)" (\d+) (\S+) "([^"]*)" "([^"]*)""#
+    /// Profile for a `log_format` string, a full `log_format` directive or a preset name (`combined`, `main`)
+    pub fn with_format(definition: &str) -> Result<Self, ParseError> {
+        Ok(Self { formats: vec![CompiledLogFormat::nginx(definition)?] })
     }
-    
-    fn parse_nginx_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
-        // Nginx timestamp format: "10/Oct/2000:13:55:36 +0000"
-        if let Ok(dt) = DateTime::parse_from_str(timestamp_str, "%d/%b/%Y:%H:%M:%S %z") {
-            return Some(dt.with_timezone(&Utc));
-        }
-        None
+}
+
+impl Default for NginxProfile {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Profile for NginxProfile {
     fn parse(&self, line: &str) -> ParseResult {
-        let start_time = Instant::now();
-        
-        let regex = Regex::new(Self::get_nginx_regex()).unwrap();
-        
-        match regex.captures(line) {
-            Some(captures) => {
-                let mut event = CanonicalEvent::new(
-                    String::new(), // Will be set below
-                    line.to_string(),
-                    FormatType::Profile(ProfileType::Nginx),
-                );
-                
-                // Extract fields according to Nginx log format
-                if let Some(client_ip) = captures.get(1) {
-                    event.add_field("client_ip".to_string(), serde_json::Value::String(client_ip.as_str().to_string()));
-                }
-                
-                if let Some(timestamp_match) = captures.get(2) {
-                    if let Some(timestamp) = self.parse_nginx_timestamp(timestamp_match.as_str()) {
-                        event.set_timestamp(timestamp);
-                    }
-                }
-                
-                if let Some(request) = captures.get(3) {
-                    event.message = request.as_str().to_string();
-                    event.add_field("request".to_string(), serde_json::Value::String(request.as_str().to_string()));
-                }
-                
-                if let Some(status) = captures.get(4) {
-                    if let Ok(status_code) = status.as_str().parse::<u16>() {
-                        event.add_field("status".to_string(), serde_json::Value::Number(status_code.into()));
-                        
-                        // Set log level based on status code
-                        let level = match status_code {
-                            200..=299 => LogLevel::Info,
-                            300..=399 => LogLevel::Info,
-                            400..=499 => LogLevel::Warn,
-                            500..=599 => LogLevel::Error,
-                            _ => LogLevel::Info,
-                        };
-                        event.set_level(level);
-                    }
-                }
-                
-                if let Some(size) = captures.get(5) {
-                    if let Ok(size_bytes) = size.as_str().parse::<u64>() {
-                        event.add_field("size".to_string(), serde_json::Value::Number(size_bytes.into()));
-                    }
-                }
-                
-                if let Some(referer) = captures.get(6) {
-                    event.add_field("referer".to_string(), serde_json::Value::String(referer.as_str().to_string()));
-                }
-                
-                if let Some(user_agent) = captures.get(7) {
-                    event.add_field("user_agent".to_string(), serde_json::Value::String(user_agent.as_str().to_string()));
-                }
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::success_with_timing(event, 0.9, processing_time)
-            }
-            None => {
-                let error = ParseError::PatternMatchError {
-                    input: line.to_string(),
-                    attempted_patterns: vec![Self::get_nginx_regex().to_string()],
-                };
-                
-                let processing_time = start_time.elapsed().as_micros() as u64;
-                ParseResult::failure_with_context(
-                    line.to_string(),
-                    error,
-                    None,
-                    Some(processing_time),
-                )
-            }
-        }
+        parse_access_log(&self.formats, line, ProfileType::Nginx)
     }
     
     fn can_parse(&self, line: &str) -> bool {
-        let regex = Regex::new(Self::get_nginx_regex()).unwrap();
-        regex.is_match(line)
+        self.formats.iter().any(|format| format.is_match(line))
     }
     
     fn get_profile_type(&self) -> ProfileType {
@@ -664,17 +554,23 @@ impl Profile for NginxProfile {
     }
     
     fn validate(&self) -> Result<(), ParseError> {
-        // Nginx profile is always valid
+        // Formats are validated when compiled
         Ok(())
     }
 }
 
 /// Syslog profile (RFC3164 format)
-pub struct SyslogProfile;
+#[derive(Clone)]
+pub struct SyslogProfile {
+    regex: Regex,
+}
 
 impl SyslogProfile {
     pub fn new() -> Self {
-        Self
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        Self {
+            regex: REGEX.get_or_init(|| Regex::new(Self::get_syslog_regex()).unwrap()).clone(),
+        }
     }
     
     fn get_syslog_regex() -> &'static str {
@@ -682,19 +578,19 @@ impl SyslogProfile {
         r#"^<(\d+)>(\w{3} \d{1,2} \d{2}:\d{2}:\d{2}) (\S+) ([^:]+): (.*)$"#
     }
     
-    fn parse_syslog_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+    fn parse_syslog_timestamp(&self, timestamp_str: &str) -> Option<LogTimestamp> {
         // Syslog timestamp format: "Oct 10 13:55:36"
-        // Note: This doesn't include year, so we assume current year
-        let current_year = chrono::Utc::now().year();
-        let full_timestamp = format!("{} {}", current_year, timestamp_str);
+        // Note: This doesn't include year; it is inferred (see `year_inference`)
+        let full_timestamp = format!("{} {}", PLACEHOLDER_YEAR, timestamp_str);
         
         if let Ok(naive_dt) = chrono::NaiveDateTime::parse_from_str(&full_timestamp, "%Y %b %d %H:%M:%S") {
-            return Some(DateTime::from_naive_utc_and_offset(naive_dt, Utc));
+            return Some(LogTimestamp::Naive(assume_year(naive_dt)));
         }
         None
     }
     
-    fn parse_syslog_priority(&self, priority_str: &str) -> (Option<String>, Option<LogLevel>) {
+    /// Map a PRI value to its facility name and severity level (shared with RFC 5424)
+    pub(crate) fn parse_syslog_priority(&self, priority_str: &str) -> (Option<String>, Option<LogLevel>) {
         if let Ok(priority) = priority_str.parse::<u8>() {
             let facility = priority >> 3;
             let severity = priority & 7;
@@ -746,9 +642,7 @@ impl Profile for SyslogProfile {
     fn parse(&self, line: &str) -> ParseResult {
         let start_time = Instant::now();
         
-        let regex = Regex::new(Self::get_syslog_regex()).unwrap();
-        
-        match regex.captures(line) {
+        match self.regex.captures(line) {
             Some(captures) => {
                 let mut event = CanonicalEvent::new(
                     String::new(), // Will be set below
@@ -772,7 +666,8 @@ impl Profile for SyslogProfile {
                 // Extract timestamp
                 if let Some(timestamp_match) = captures.get(2) {
                     if let Some(timestamp) = self.parse_syslog_timestamp(timestamp_match.as_str()) {
-                        event.set_timestamp(timestamp);
+                        event.set_log_timestamp(timestamp);
+                        event.year_inferred = Some(true);
                     }
                 }
                 
@@ -812,8 +707,7 @@ impl Profile for SyslogProfile {
     }
     
     fn can_parse(&self, line: &str) -> bool {
-        let regex = Regex::new(Self::get_syslog_regex()).unwrap();
-        regex.is_match(line)
+        self.regex.is_match(line)
     }
     
     fn get_profile_type(&self) -> ProfileType {
@@ -846,6 +740,7 @@ mod tests {
             level_field: Some("level".to_string()),
             message_field: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let profile = RegexProfile::new(config);
@@ -867,6 +762,7 @@ mod tests {
             level_field: Some("level".to_string()),
             message_field: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let profile = RegexProfile::new(config).unwrap();
@@ -894,6 +790,7 @@ mod tests {
             level_column: Some("level".to_string()),
             message_column: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let profile = CsvProfile::new(config).unwrap();
@@ -982,6 +879,7 @@ mod property_tests {
                 level_field: Some("level".to_string()),
                 message_field: Some("message".to_string()),
                 timestamp_format: None,
+                default_timezone: None,
             }
         }
     }
@@ -1002,6 +900,7 @@ mod property_tests {
                 level_column: Some("level".to_string()),
                 message_column: Some("message".to_string()),
                 timestamp_format: None,
+                default_timezone: None,
             }
         }
     }
@@ -1172,6 +1071,7 @@ mod validation_tests {
             level_field: None,
             message_field: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let result = RegexProfile::new(config);
@@ -1198,6 +1098,7 @@ mod validation_tests {
             level_field: None,
             message_field: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let result = RegexProfile::new(config);
@@ -1224,6 +1125,7 @@ mod validation_tests {
             level_field: None,
             message_field: None,
             timestamp_format: Some("%invalid_format%".to_string()), // Invalid timestamp format
+            default_timezone: None,
         };
         
         let result = RegexProfile::new(config);
@@ -1248,6 +1150,7 @@ mod validation_tests {
             level_column: None,
             message_column: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let result = CsvProfile::new(config);
@@ -1275,6 +1178,7 @@ mod validation_tests {
             level_column: None,
             message_column: None,
             timestamp_format: Some("%bad_format%".to_string()), // Invalid timestamp format
+            default_timezone: None,
         };
         
         let result = CsvProfile::new(config);
@@ -1301,6 +1205,7 @@ mod validation_tests {
             level_field: None,
             message_field: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let parser_result = ProfileParser::new_regex(config);
@@ -1327,6 +1232,7 @@ mod validation_tests {
             level_column: None,
             message_column: None,
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let parser_result = ProfileParser::new_csv(config);
@@ -1371,6 +1277,7 @@ mod validation_tests {
             level_field: Some("level".to_string()),
             message_field: Some("message".to_string()),
             timestamp_format: None, // Don't test timestamp format validation here
+            default_timezone: None,
         };
         
         let result = RegexProfile::new(config);
@@ -1399,6 +1306,7 @@ mod validation_tests {
             level_column: Some("level".to_string()),
             message_column: Some("message".to_string()),
             timestamp_format: None, // Don't test timestamp format validation here
+            default_timezone: None,
         };
         
         let result = CsvProfile::new(config);
@@ -0,0 +1,291 @@
+use crate::error::ParseError;
+use crate::models::FormatType;
+use crate::parse_result::{ParseResult, ParseResultRef};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Where lines that fail to parse, match no format, or parse below a
+/// confidence threshold are written instead of the regular output
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RejectConfig {
+    /// NDJSON file receiving one record per rejected line; nothing is
+    /// rejected when unset
+    pub path: Option<PathBuf>,
+    
+    /// Also reject lines that parsed with a confidence below this (0.0 - 1.0)
+    pub min_confidence: Option<f64>,
+}
+
+impl RejectConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+    
+    pub fn validate(&self) -> Result<(), ParseError> {
+        match self.min_confidence {
+            Some(threshold) if !(0.0..=1.0).contains(&threshold) => Err(ParseError::ConfigurationError {
+                parameter: "rejects.min_confidence".to_string(),
+                error_message: format!("Confidence threshold {} is outside 0.0 - 1.0", threshold),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One rejected line in the rejects file
+#[derive(Serialize)]
+struct RejectRecord<'a> {
+    source: Option<&'a str>,
+    line: Option<usize>,
+    offset: Option<u64>,
+    reason: &'static str,
+    confidence: f64,
+    /// Format the line parsed as, for lines rejected on confidence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    attempted_formats: Vec<String>,
+    error: Option<&'a ParseError>,
+    raw: &'a str,
+}
+
+impl<'a> RejectRecord<'a> {
+    /// Record without source position; lines rejected on confidence name the
+    /// format they parsed as, which counts as attempted
+    fn new(
+        reason: &'static str,
+        success: bool,
+        confidence: f64,
+        error: Option<&'a ParseError>,
+        raw: &'a str,
+        format_type: FormatType,
+        attempted_formats: &[FormatType],
+    ) -> Self {
+        let mut attempted: Vec<String> = attempted_formats.iter().map(ToString::to_string).collect();
+        if success && attempted.is_empty() {
+            attempted.push(format_type.to_string());
+        }
+        Self {
+            source: None,
+            line: None,
+            offset: None,
+            reason,
+            confidence,
+            format: success.then(|| format_type.to_string()),
+            attempted_formats: attempted,
+            error,
+            raw,
+        }
+    }
+}
+
+/// Diverts failed and low-confidence parse results to a dead-letter NDJSON file
+pub struct RejectWriter {
+    output: Option<Box<dyn Write>>,
+    path: Option<PathBuf>,
+    min_confidence: f64,
+    /// Formats tried before the plain-text fallback; plain-text lines count as unmatched when set
+    fallback_formats: Vec<FormatType>,
+    rejected: usize,
+}
+
+impl RejectWriter {
+    /// Writer for `config`; rejects nothing when no path is configured
+    pub fn from_config(config: &RejectConfig) -> io::Result<Self> {
+        let output = match &config.path {
+            Some(path) => Some(Box::new(BufWriter::new(File::create(path)?)) as Box<dyn Write>),
+            None => None,
+        };
+        Ok(Self {
+            output,
+            path: config.path.clone(),
+            min_confidence: config.min_confidence.unwrap_or(0.0),
+            fallback_formats: Vec::new(),
+            rejected: 0,
+        })
+    }
+    
+    /// Writer rejecting into `output`
+    pub fn new(output: Box<dyn Write>, min_confidence: f64) -> Self {
+        Self {
+            output: Some(output),
+            path: None,
+            min_confidence,
+            fallback_formats: Vec::new(),
+            rejected: 0,
+        }
+    }
+    
+    /// Reject lines that fell back to plain text after detection tried `formats`
+    pub fn with_fallback_formats(mut self, formats: Vec<FormatType>) -> Self {
+        self.fallback_formats = formats;
+        self
+    }
+    
+    /// Why a result with this outcome is diverted, if it is
+    fn reason(&self, success: bool, confidence: f64, format_type: &FormatType) -> Option<&'static str> {
+        if self.output.is_none() {
+            None
+        } else if !success {
+            Some("parse_error")
+        } else if *format_type == FormatType::PlainText && !self.fallback_formats.is_empty() {
+            Some("unmatched")
+        } else if confidence < self.min_confidence {
+            Some("low_confidence")
+        } else {
+            None
+        }
+    }
+    
+    /// Whether a result with this outcome is diverted
+    pub fn rejects(&self, success: bool, confidence: f64, format_type: &FormatType) -> bool {
+        self.reason(success, confidence, format_type).is_some()
+    }
+    
+    /// Write the result to the rejects file if it is rejected; returns whether it was
+    pub fn reject(&mut self, result: &ParseResult) -> io::Result<bool> {
+        let Some(reason) = self.reason(result.success, result.confidence, &result.event.format_type) else {
+            return Ok(false);
+        };
+        let attempted = self.attempted_formats(reason, &result.attempted_formats);
+        let source = &result.event.source;
+        self.write(&RejectRecord {
+            source: source.file.as_deref().or(source.stream.as_deref()),
+            line: source.line.or(result.line_number),
+            offset: source.offset,
+            ..RejectRecord::new(reason, result.success, result.confidence, result.error.as_ref().or(result.fallback_error.as_ref()), &result.event.raw, result.event.format_type.clone(), attempted)
+        })?;
+        Ok(true)
+    }
+    
+    /// `reject` for a result borrowing from its line
+    pub fn reject_ref(&mut self, result: &ParseResultRef) -> io::Result<bool> {
+        let Some(reason) = self.reason(result.success, result.confidence, &result.event.format_type) else {
+            return Ok(false);
+        };
+        let attempted = self.attempted_formats(reason, &result.attempted_formats);
+        let source = &result.event.source;
+        self.write(&RejectRecord {
+            source: source.file.as_deref().or(source.stream.as_deref()),
+            line: source.line.or(result.line_number),
+            offset: source.offset,
+            ..RejectRecord::new(reason, result.success, result.confidence, result.error.as_ref().or(result.fallback_error.as_ref()), &result.event.raw, result.event.format_type.clone(), attempted)
+        })?;
+        Ok(true)
+    }
+    
+    /// Unmatched lines were tried as every detectable format
+    fn attempted_formats<'a>(&'a self, reason: &str, attempted: &'a [FormatType]) -> &'a [FormatType] {
+        if reason == "unmatched" {
+            &self.fallback_formats
+        } else {
+            attempted
+        }
+    }
+    
+    fn write(&mut self, record: &RejectRecord) -> io::Result<()> {
+        if let Some(output) = self.output.as_mut() {
+            serde_json::to_writer(&mut *output, record)?;
+            output.write_all(b"\n")?;
+            self.rejected += 1;
+        }
+        Ok(())
+    }
+    
+    /// Number of lines written to the rejects file
+    pub fn rejected(&self) -> usize {
+        self.rejected
+    }
+    
+    /// The rejects file, when rejecting to one
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+    
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.output.as_mut() {
+            Some(output) => output.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::ParserRegistry;
+    
+    #[test]
+    fn test_rejects_failures_and_low_confidence_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rejects.ndjson");
+        let config = RejectConfig { path: Some(path.clone()), min_confidence: Some(0.5) };
+        let mut rejects = RejectWriter::from_config(&config).unwrap();
+        let registry = ParserRegistry::new();
+        
+        let mut broken = registry.parse_as(FormatType::Json, r#"{"msg": "unterminated"#);
+        broken.event.source.file = Some("app.log".to_string());
+        broken.event.source.line = Some(7);
+        assert!(rejects.reject(&broken).unwrap());
+        assert!(rejects.reject(&registry.parse_as(FormatType::PlainText, "just words")).unwrap());
+        assert!(!rejects.reject(&registry.parse_as(FormatType::Json, r#"{"msg":"ok"}"#)).unwrap());
+        rejects.flush().unwrap();
+        assert_eq!(rejects.rejected(), 2);
+        
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records[0]["source"], "app.log");
+        assert_eq!(records[0]["line"], 7);
+        assert_eq!(records[0]["reason"], "parse_error");
+        assert_eq!(records[0]["attempted_formats"], serde_json::json!(["Json"]));
+        assert!(records[0]["error"]["JsonSyntaxError"].is_object());
+        assert_eq!(records[1]["reason"], "low_confidence");
+        assert_eq!(records[1]["format"], "PlainText");
+        assert!(records[1]["error"].is_null());
+        
+        let invalid = RejectConfig { path: None, min_confidence: Some(1.5) };
+        assert!(invalid.validate().is_err());
+    }
+    
+    #[test]
+    fn test_plain_text_fallback_is_unmatched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rejects.ndjson");
+        let config = RejectConfig { path: Some(path.clone()), min_confidence: None };
+        let registry = ParserRegistry::new();
+        let plain = registry.parse_as(FormatType::PlainText, "just words");
+        
+        let mut rejects = RejectWriter::from_config(&config).unwrap();
+        assert!(!rejects.reject(&plain).unwrap());
+        
+        let mut rejects = rejects.with_fallback_formats(vec![FormatType::Json, FormatType::Logfmt]);
+        assert!(rejects.reject(&plain).unwrap());
+        rejects.flush().unwrap();
+        
+        let record: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&path).unwrap().trim()).unwrap();
+        assert_eq!(record["reason"], "unmatched");
+        assert_eq!(record["attempted_formats"], serde_json::json!(["Json", "Logfmt"]));
+    }
+    
+    #[test]
+    fn test_unmatched_lines_record_why_detection_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rejects.ndjson");
+        let mut config = crate::tango_parser::TangoConfig::default();
+        config.rejects.path = Some(path.clone());
+        let mut parser = crate::tango_parser::TangoParser::with_config(config.clone());
+        let mut rejects = RejectWriter::from_config(&config.rejects).unwrap().with_fallback_formats(parser.fallback_formats());
+        
+        assert!(rejects.reject(&parser.parse_line(r#"{"broken": "#)).unwrap());
+        assert!(rejects.reject(&parser.parse_line("just words")).unwrap());
+        rejects.flush().unwrap();
+        
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records[0]["reason"], "unmatched");
+        assert!(records[0]["error"]["JsonSyntaxError"].is_object());
+        assert!(records[1]["error"]["PatternMatchError"].is_object());
+    }
+}
@@ -1,23 +1,18 @@
 use crate::parse_result::ParseResult;
 use crate::statistics::{ParsingStatistics, StatisticsMonitor};
-use crate::parsers::{JsonParser, LogfmtParser, PatternParser, PlainTextParser, LogParser};
+use crate::parsers::ParserRegistry;
 
 /// Resilient parsing engine that demonstrates error handling and continuation
 pub struct ResilientParser {
-    json_parser: JsonParser,
-    logfmt_parser: LogfmtParser,
-    pattern_parser: PatternParser,
-    plain_text_parser: PlainTextParser,
+    /// Parsers making up the fallback chain, in priority order
+    registry: ParserRegistry,
     statistics_monitor: StatisticsMonitor,
 }
 
 impl ResilientParser {
     pub fn new() -> Self {
         Self {
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
-            plain_text_parser: PlainTextParser::new(),
+            registry: ParserRegistry::new(),
             statistics_monitor: StatisticsMonitor::new(),
         }
     }
@@ -25,14 +20,17 @@ impl ResilientParser {
     /// Create a new resilient parser with monitoring settings
     pub fn with_monitoring(monitoring_enabled: bool, debug_output_enabled: bool, report_interval: usize) -> Self {
         Self {
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
-            plain_text_parser: PlainTextParser::new(),
+            registry: ParserRegistry::new(),
             statistics_monitor: StatisticsMonitor::with_settings(monitoring_enabled, debug_output_enabled, report_interval),
         }
     }
     
+    /// Fall back through the parsers in `registry` instead of the built-ins alone
+    pub fn with_registry(mut self, registry: ParserRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+    
     /// Parse multiple lines with comprehensive error handling and continuation
     pub fn parse_lines(&mut self, lines: Vec<String>) -> Vec<ParseResult> {
         let mut results = Vec::new();
@@ -43,9 +41,9 @@ impl ResilientParser {
             // Update statistics with monitoring
             if result.success {
                 if let Some(processing_time) = result.processing_time_micros {
-                    self.statistics_monitor.record_success(result.event.format_type, processing_time);
+                    self.statistics_monitor.record_success(result.event.format_type.clone(), processing_time);
                 } else {
-                    self.statistics_monitor.record_success(result.event.format_type, 0);
+                    self.statistics_monitor.record_success(result.event.format_type.clone(), 0);
                 }
             } else {
                 if let Some(error) = &result.error {
@@ -65,36 +63,9 @@ impl ResilientParser {
     
     /// Parse a single line using the fallback chain with comprehensive error handling
     pub fn parse_line_with_fallback(&mut self, line: &str, line_number: Option<usize>) -> ParseResult {
-        // Stage 1: Try JSON parsing first
-        if line.trim_start().starts_with('{') {
-            let json_result = self.json_parser.parse(line);
-            if json_result.success {
-                return json_result.with_line_number(line_number.unwrap_or(0));
-            }
-            // Continue to next stage on failure - don't return error yet
-        }
-        
-        // Stage 2: Try logfmt parsing
-        if self.logfmt_parser.can_parse(line) {
-            let logfmt_result = self.logfmt_parser.parse(line);
-            if logfmt_result.success {
-                return logfmt_result.with_line_number(line_number.unwrap_or(0));
-            }
-            // Continue to next stage on failure
-        }
-        
-        // Stage 3: Try timestamp+level pattern parsing
-        if self.pattern_parser.can_parse(line) {
-            let pattern_result = self.pattern_parser.parse(line);
-            if pattern_result.success {
-                return pattern_result.with_line_number(line_number.unwrap_or(0));
-            }
-            // Continue to next stage on failure
-        }
-        
-        // Stage 4: Fall back to plain text (always succeeds)
-        let plain_result = self.plain_text_parser.parse(line);
-        plain_result.with_line_number(line_number.unwrap_or(0))
+        // Each registered parser in priority order, continuing past failures; plain text always succeeds
+        let (_, result) = self.registry.parse_first(line);
+        result.with_line_number(line_number.unwrap_or(0))
     }
     
     /// Get parsing statistics
@@ -291,6 +262,16 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_year_less_timestamps_assume_the_current_year() {
+        use chrono::Datelike;
+        let mut parser = ResilientParser::new();
+        let result = parser.parse_line_with_fallback("Jan  5 10:00:00 host app[1]: hello", Some(1));
+        let timestamp = result.event.timestamp.unwrap();
+        let expected = crate::year_inference::YearContext::now().resolve_year(1);
+        assert_eq!((timestamp.year(), timestamp.month(), timestamp.day()), (expected, 1, 5));
+    }
+    
     #[test]
     fn test_resilient_parser_error_recovery() {
         let mut parser = ResilientParser::new();
@@ -318,7 +299,7 @@ mod tests {
         
         // Verify that we have a mix of different format types due to fallback behavior
         let format_types: std::collections::HashSet<_> = results.iter()
-            .map(|r| r.event.format_type)
+            .map(|r| r.event.format_type.clone())
             .collect();
         
         // Should have at least plain text and some structured formats
@@ -97,6 +97,35 @@ impl ParsingStatistics {
         }
     }
     
+    /// Add the counts and timings of statistics gathered separately
+    pub fn merge(&mut self, other: &ParsingStatistics) {
+        if other.total_lines == 0 {
+            return;
+        }
+        self.total_lines += other.total_lines;
+        self.successful_parses += other.successful_parses;
+        self.failed_parses += other.failed_parses;
+        self.plain_text_fallbacks += other.plain_text_fallbacks;
+        for (format_type, count) in &other.format_distribution {
+            *self.format_distribution.entry(format_type.clone()).or_insert(0) += count;
+        }
+        for (error_type, count) in &other.error_distribution {
+            *self.error_distribution.entry(error_type.clone()).or_insert(0) += count;
+        }
+        
+        let times = &mut self.processing_time_micros;
+        let other_times = &other.processing_time_micros;
+        times.total_time = times.total_time.saturating_add(other_times.total_time);
+        if times.min_time == 0 || (other_times.min_time > 0 && other_times.min_time < times.min_time) {
+            times.min_time = other_times.min_time;
+        }
+        times.max_time = times.max_time.max(other_times.max_time);
+        times.avg_time = times.total_time as f64 / self.total_lines as f64;
+        
+        self.memory_stats.peak_memory_bytes = self.memory_stats.peak_memory_bytes.max(other.memory_stats.peak_memory_bytes);
+        self.memory_stats.total_allocations += other.memory_stats.total_allocations;
+    }
+    
     fn update_processing_time(&mut self, time_micros: u64) {
         self.processing_time_micros.total_time = self.processing_time_micros.total_time.saturating_add(time_micros);
         
@@ -181,10 +210,10 @@ impl StatisticsMonitor {
     
     /// Record a successful parse with optional monitoring output
     pub fn record_success(&mut self, format_type: FormatType, processing_time_micros: u64) {
-        self.stats.record_success(format_type, processing_time_micros);
+        self.stats.record_success(format_type.clone(), processing_time_micros);
         
         if self.debug_output_enabled {
-            println!("DEBUG: Successful parse - Format: {:?}, Time: {}μs", format_type, processing_time_micros);
+            println!("DEBUG: Successful parse - Format: {}, Time: {}μs", format_type, processing_time_micros);
         }
         
         self.check_and_report();
@@ -212,6 +241,12 @@ impl StatisticsMonitor {
         self.check_and_report();
     }
     
+    /// Add statistics gathered by another monitor
+    pub fn merge(&mut self, other: &ParsingStatistics) {
+        self.stats.merge(other);
+        self.check_and_report();
+    }
+    
     /// Get the current statistics
     pub fn get_statistics(&self) -> &ParsingStatistics {
         &self.stats
@@ -242,7 +277,7 @@ impl StatisticsMonitor {
         report.push_str("\n--- Format Distribution ---\n");
         for (format_type, count) in &stats.format_distribution {
             let percentage = (*count as f64 / stats.total_lines as f64) * 100.0;
-            report.push_str(&format!("{:?}: {} ({:.2}%)\n", format_type, count, percentage));
+            report.push_str(&format!("{}: {} ({:.2}%)\n", format_type, count, percentage));
         }
         
         report.push_str("\n--- Error Distribution ---\n");
@@ -341,7 +376,7 @@ impl StatisticsMonitor {
         self.stats.format_distribution
             .iter()
             .max_by_key(|(_, &count)| count)
-            .map(|(&format_type, _)| format_type)
+            .map(|(format_type, _)| format_type.clone())
     }
     
     /// Get the most common error type
@@ -569,10 +604,10 @@ mod property_tests {
         
         // Record success events
         for (format_type, processing_time) in success_events.iter().take(50) { // Limit to prevent excessive test time
-            monitor.record_success(*format_type, *processing_time);
+            monitor.record_success(format_type.clone(), *processing_time);
             expected_total_lines += 1;
             expected_successful_parses += 1;
-            *expected_format_distribution.entry(*format_type).or_insert(0) += 1;
+            *expected_format_distribution.entry(format_type.clone()).or_insert(0) += 1;
         }
         
         // Record error events
@@ -1,8 +1,10 @@
 use crate::models::*;
 use crate::parse_result::ParseResult;
-use crate::parsers::{LogParser, JsonParser, LogfmtParser, PatternParser, PlainTextParser};
-use crate::classifier::{TangoFormatClassifier, FormatClassifier};
+use crate::parsers::{LogParser, ParserRegistry};
+use crate::classifier::TangoFormatClassifier;
 use crate::statistics::{ParsingStatistics, StatisticsMonitor};
+use crate::multiline::{LineAssembler, LineGroup, attach_continuation};
+use crate::format_lock::{FormatLockConfig, FormatLocks};
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read};
 use regex::Regex;
@@ -10,6 +12,7 @@ use serde::{Deserialize, Serialize};
 
 /// Configuration for streaming parser performance optimizations
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StreamingConfig {
     /// Batch size for processing log lines
     pub batch_size: usize,
@@ -105,12 +108,7 @@ impl RegexCache {
 pub struct ParsingStructures {
     /// Cached regex patterns
     regex_cache: RegexCache,
-    /// Reusable parser instances
-    json_parser: JsonParser,
-    logfmt_parser: LogfmtParser,
-    pattern_parser: PatternParser,
-    plain_text_parser: PlainTextParser,
-    /// Format classifier with caching
+    /// Format classifier with caching; its registry holds the parser instances
     classifier: TangoFormatClassifier,
 }
 
@@ -118,23 +116,26 @@ impl ParsingStructures {
     pub fn new(max_regex_cache_size: usize) -> Self {
         Self {
             regex_cache: RegexCache::new(max_regex_cache_size),
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
-            plain_text_parser: PlainTextParser::new(),
             classifier: TangoFormatClassifier::new(),
         }
     }
     
+    /// Detect and parse with the parsers in `registry`
+    pub fn with_registry(mut self, registry: ParserRegistry) -> Self {
+        self.classifier.set_registry(registry);
+        self
+    }
+    
     /// Get the appropriate parser for a format type
     pub fn get_parser(&self, format_type: FormatType) -> &dyn LogParser {
-        match format_type {
-            FormatType::Json => &self.json_parser,
-            FormatType::Logfmt => &self.logfmt_parser,
-            FormatType::TimestampLevel | FormatType::Pattern => &self.pattern_parser,
-            FormatType::PlainText => &self.plain_text_parser,
-            FormatType::Profile(_) => &self.plain_text_parser, // Fallback for profiles
-        }
+        let registry = self.classifier.registry();
+        // Formats without a registered parser (configured profiles) fall back to plain text
+        registry.for_format(format_type).unwrap_or_else(|| registry.plain_text()).parser()
+    }
+    
+    /// Parse a line as the given format with the classifier's registered parsers
+    pub fn parse_as(&self, format_type: FormatType, line: &str) -> ParseResult {
+        self.classifier.registry().parse_as(format_type, line)
     }
     
     /// Get mutable access to regex cache
@@ -163,6 +164,10 @@ pub struct StreamingParser {
     statistics_monitor: StatisticsMonitor,
     /// Current memory usage estimate
     current_memory_usage: usize,
+    /// Multi-line assembly rules applied before parsing
+    line_assembler: LineAssembler,
+    /// Per-source format locks (disabled unless `with_format_lock` is used)
+    format_locks: FormatLocks,
 }
 
 impl StreamingParser {
@@ -177,26 +182,47 @@ impl StreamingParser {
             parsing_structures: ParsingStructures::new(config.max_regex_cache_size),
             statistics_monitor: StatisticsMonitor::new(),
             current_memory_usage: 0,
+            line_assembler: LineAssembler::default(),
+            format_locks: FormatLocks::new(FormatLockConfig { enabled: false, ..FormatLockConfig::default() }),
             config,
         }
     }
     
+    /// Lock each source onto the format its first lines share
+    pub fn with_format_lock(mut self, config: FormatLockConfig) -> Self {
+        self.format_locks = FormatLocks::new(config);
+        self
+    }
+    
+    /// Detect and parse with the parsers in `registry`
+    pub fn with_parser_registry(mut self, registry: ParserRegistry) -> Self {
+        self.parsing_structures.classifier.set_registry(registry);
+        self
+    }
+    
+    /// Use the given multi-line rules when grouping lines into events
+    pub fn with_line_assembler(mut self, line_assembler: LineAssembler) -> Self {
+        self.line_assembler = line_assembler;
+        self
+    }
+    
     /// Parse a stream of log lines with performance optimizations
     pub fn parse_stream<R: Read>(&mut self, reader: R, source: &str) -> Result<Vec<ParseResult>, std::io::Error> {
         let mut buf_reader = BufReader::with_capacity(self.config.buffer_size, reader);
         let mut results = Vec::new();
         let mut batch = Vec::with_capacity(self.config.batch_size);
-        let mut line_number = 1;
+        let mut assembler = self.line_assembler.clone();
         
         loop {
             let mut line = String::new();
             let bytes_read = buf_reader.read_line(&mut line)?;
             
             if bytes_read == 0 {
-                // End of stream - process remaining batch
+                // End of stream - close the last group and process remaining batch
+                assembler.flush();
+                batch.extend(std::iter::from_fn(|| assembler.next_group()));
                 if !batch.is_empty() {
-                    let start_line = line_number - batch.len();
-                    let batch_results = self.process_batch(batch, source, start_line);
+                    let batch_results = self.process_batch(batch, source);
                     results.extend(batch_results);
                 }
                 break;
@@ -213,15 +239,14 @@ impl StreamingParser {
             // Update memory usage estimate
             self.current_memory_usage += line.len();
             
-            batch.push(line);
-            line_number += 1;
+            assembler.push_line(line);
+            batch.extend(std::iter::from_fn(|| assembler.next_group()));
             
             // Process batch when it reaches configured size or memory limit
             if batch.len() >= self.config.batch_size || 
                self.current_memory_usage >= self.config.memory_limit_bytes {
-                let start_line = line_number - batch.len();
                 let batch_to_process = std::mem::replace(&mut batch, Vec::with_capacity(self.config.batch_size));
-                let batch_results = self.process_batch(batch_to_process, source, start_line);
+                let batch_results = self.process_batch(batch_to_process, source);
                 results.extend(batch_results);
                 
                 // Reset memory usage counter
@@ -235,13 +260,13 @@ impl StreamingParser {
         Ok(results)
     }
     
-    /// Process a batch of log lines with optimized parsing
-    fn process_batch(&mut self, lines: Vec<String>, source: &str, start_line_number: usize) -> Vec<ParseResult> {
-        let mut results = Vec::with_capacity(lines.len());
+    /// Process a batch of assembled line groups with optimized parsing
+    fn process_batch(&mut self, groups: Vec<LineGroup>, source: &str) -> Vec<ParseResult> {
+        let mut results = Vec::with_capacity(groups.len());
         
-        for (i, line) in lines.iter().enumerate() {
-            let line_number = start_line_number + i;
-            let result = self.parse_line_optimized(line, source, line_number);
+        for group in &groups {
+            let mut result = self.parse_line_optimized(&group.lines[0], source, group.line_number);
+            attach_continuation(&mut result.event, group);
             results.push(result);
         }
         
@@ -252,16 +277,24 @@ impl StreamingParser {
     fn parse_line_optimized(&mut self, line: &str, source: &str, line_number: usize) -> ParseResult {
         let start_time = std::time::Instant::now();
         
-        // Use regular format detection for mixed-format streams
-        // Note: Caching by source is not appropriate for mixed-format log files
-        let format_type = self.parsing_structures.classifier()
-            .detect_format(line, source);
-        
-        // Get the appropriate parser (reused instances)
-        let parser = self.parsing_structures.get_parser(format_type);
+        // Parse with the source's locked format; a miss falls back to full detection
+        let locked = self.format_locks.locked_format(source)
+            .map(|format_type| self.parsing_structures.parse_as(format_type, line));
+        if let Some(result) = &locked {
+            self.format_locks.record_locked_parse(source, result.success);
+        }
         
-        // Parse the line
-        let mut result = parser.parse(line);
+        let mut result = match locked {
+            Some(result) if result.success => result,
+            _ => {
+                let (format_type, confidence) = self.parsing_structures.classifier()
+                    .detect_format_with_confidence(line, source);
+                if self.format_locks.is_enabled() {
+                    self.format_locks.record_detection(source, format_type.clone(), confidence);
+                }
+                self.parsing_structures.parse_as(format_type, line)
+            }
+        };
         
         // Set line number
         result = result.with_line_number(line_number);
@@ -271,7 +304,7 @@ impl StreamingParser {
         result.processing_time_micros = Some(processing_time);
         
         if result.success {
-            self.statistics_monitor.record_success(result.event.format_type, processing_time);
+            self.statistics_monitor.record_success(result.event.format_type.clone(), processing_time);
         } else {
             if let Some(error) = &result.error {
                 self.statistics_monitor.record_failure(error, processing_time);
@@ -296,6 +329,11 @@ impl StreamingParser {
         self.parsing_structures.classifier.cache_stats()
     }
     
+    /// Per-source format lock decisions
+    pub fn format_locks(&self) -> &FormatLocks {
+        &self.format_locks
+    }
+    
     /// Get current memory usage estimate
     pub fn get_memory_usage(&self) -> usize {
         self.current_memory_usage
@@ -305,6 +343,7 @@ impl StreamingParser {
     pub fn reset(&mut self) {
         self.parsing_structures.regex_cache.clear();
         self.parsing_structures.classifier.clear_cache();
+        self.format_locks.clear();
         self.statistics_monitor.reset();
         self.current_memory_usage = 0;
     }
@@ -500,6 +500,7 @@ pub fn test_configuration_and_profiles() -> Result<(), String> {
         level_field: Some("level".to_string()),
         message_field: Some("message".to_string()),
         timestamp_format: None,
+        default_timezone: None,
     };
     
     let profile_config = ProfileConfig::Regex(regex_config);
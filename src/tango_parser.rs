@@ -1,18 +1,34 @@
 use crate::models::*;
 use crate::error::ParseError;
-use crate::parse_result::ParseResult;
-use crate::parsers::{LogParser, JsonParser, LogfmtParser, PatternParser, PlainTextParser, ProfileParser};
+use crate::parse_result::{ParseResult, ParseResultRef};
+use crate::parsers::{CriParser, LogParser, ParserRegistry, PatternParser, ProfileParser, RegisteredParser};
 use crate::classifier::{TangoFormatClassifier, FormatClassifier};
 use crate::statistics::{ParsingStatistics, StatisticsMonitor};
 use crate::streaming_parser::{StreamingParser, StreamingConfig};
 use crate::parallel_parser::{ParallelParser, ParallelConfig};
 use crate::profiles::*;
+use crate::input::LogInput;
+use crate::timezone::{localize, parse_timezone};
+use crate::year_inference::YearContext;
+use crate::timestamp::TimestampFormat;
+use crate::container::ContainerPath;
+use crate::format_lock::{FormatLockConfig, FormatLocks};
+use crate::rejects::RejectConfig;
+use crate::field_mapping::{FieldMappings, SourceFieldMappings};
+use crate::field_path::FlattenConfig;
+use crate::multiline::{GroupedLines, LineAssembler, LineGroup, LineGroupRef, MultilineConfig, SourceLine, attach_continuation, attach_continuation_ref};
+use crate::event_ref::{SourceRef, ValueRef};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read};
+use std::time::SystemTime;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for the main Tango parser
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TangoConfig {
     /// Enable format caching for performance optimization
     pub enable_format_caching: bool,
@@ -40,18 +56,99 @@ pub struct TangoConfig {
     /// User-defined parsing profiles
     pub profiles: HashMap<String, ProfileConfig>,
     
+    /// Source-to-profile bindings, checked in order before name matching
+    pub bindings: Vec<SourceBinding>,
+    
+    /// Rules for assembling multi-line events (stack traces, continuations)
+    pub multiline: MultilineConfig,
+    
+    /// IANA timezone for timestamps without an offset (default: UTC)
+    pub default_timezone: Option<String>,
+    
+    /// Year of the first line for timestamps without one (syslog, logcat);
+    /// inferred from the file mtime when unset
+    pub year: Option<i32>,
+    
     /// Default source identifier for logs without explicit source
     pub default_source: String,
+    
+    /// Host recorded for events whose line does not name one
+    pub host: Option<String>,
+    
+    /// Lock each source onto the format its first lines share; takes
+    /// precedence over `enable_format_caching` when enabled
+    pub format_lock: FormatLockConfig,
+    
+    /// Parse every line as this format instead of detecting it: a registered
+    /// parser (json, logfmt, rfc5424, cri, docker, klog, plain, ...), a built-in
+    /// profile (apache, nginx, syslog), logcat, or csv for the one CSV profile
+    /// in `profiles`.
+    /// Lines that do not match are parse failures
+    pub format: Option<String>,
+    
+    /// Parse every line with this profile from `profiles`, ignoring bindings
+    /// and detection; lines that do not match are parse failures
+    pub profile: Option<String>,
+    
+    /// Dead-letter file for lines that fail to parse or parse with low confidence
+    pub rejects: RejectConfig,
+    
+    /// Keys JSON and logfmt lines' timestamp, level and message are read from
+    pub field_mappings: FieldMappings,
+    
+    /// Per-source changes to `field_mappings`, first matching pattern wins
+    pub source_field_mappings: Vec<SourceFieldMappings>,
+    
+    /// How nested JSON objects and arrays are flattened into fields
+    pub flatten: FlattenConfig,
+}
+
+/// Binds sources matching a glob pattern to a named profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceBinding {
+    /// Glob pattern matched against the source identifier (e.g. "/var/log/nginx/*.log")
+    pub pattern: String,
+    /// Name of a profile defined in `TangoConfig::profiles`
+    pub profile: String,
 }
 
 /// Profile configuration enum for different profile types
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ProfileConfig {
     Regex(RegexProfileConfig),
     Csv(CsvProfileConfig),
-    Apache,
-    Nginx,
-    Syslog,
+    Apache {
+        /// `LogFormat` string or preset name; combined with a common fallback when unset
+        #[serde(default)]
+        format: Option<String>,
+        #[serde(default)]
+        default_timezone: Option<String>,
+    },
+    Nginx {
+        /// `log_format` string, directive or preset name; `combined` when unset
+        #[serde(default)]
+        format: Option<String>,
+        #[serde(default)]
+        default_timezone: Option<String>,
+    },
+    Syslog {
+        #[serde(default)]
+        default_timezone: Option<String>,
+    },
+}
+
+impl ProfileConfig {
+    /// IANA timezone for this profile's timestamps without an offset
+    pub fn default_timezone(&self) -> Option<&str> {
+        match self {
+            ProfileConfig::Regex(config) => config.default_timezone.as_deref(),
+            ProfileConfig::Csv(config) => config.default_timezone.as_deref(),
+            ProfileConfig::Apache { default_timezone, .. }
+            | ProfileConfig::Nginx { default_timezone, .. }
+            | ProfileConfig::Syslog { default_timezone } => default_timezone.as_deref(),
+        }
+    }
 }
 
 impl Default for TangoConfig {
@@ -67,7 +164,19 @@ impl Default for TangoConfig {
             parallel_config: ParallelConfig::default(),
             enable_statistics: true,
             profiles: HashMap::new(),
+            bindings: Vec::new(),
+            multiline: MultilineConfig::default(),
+            default_timezone: None,
+            year: None,
             default_source: "unknown".to_string(),
+            host: None,
+            format_lock: FormatLockConfig::default(),
+            format: None,
+            profile: None,
+            rejects: RejectConfig::default(),
+            field_mappings: FieldMappings::default(),
+            source_field_mappings: Vec::new(),
+            flatten: FlattenConfig::default(),
         }
     }
 }
@@ -77,18 +186,37 @@ pub struct TangoParser {
     /// Configuration
     config: TangoConfig,
     
-    /// Format classifier for automatic format detection
+    /// Format classifier for automatic format detection; its registry holds
+    /// the parser for each format type
     classifier: TangoFormatClassifier,
     
-    /// Individual parsers for each format type
-    json_parser: JsonParser,
-    logfmt_parser: LogfmtParser,
-    pattern_parser: PatternParser,
-    plain_text_parser: PlainTextParser,
-    
     /// User-defined profile parsers
     profile_parsers: HashMap<String, ProfileParser>,
     
+    /// Registered parser every line goes to when `format` names one
+    forced_parser: Option<RegisteredParser>,
+    
+    /// Profile every source uses when `profile`, or a profile `format`, is set
+    forced_profile: Option<ProfileParser>,
+    
+    /// Compiled source bindings (pattern, profile name)
+    source_bindings: Vec<(glob::Pattern, String)>,
+    
+    /// Compiled multi-line rules, cloned for each input
+    line_assembler: LineAssembler,
+    
+    /// Per-source year inference for year-less timestamps
+    year_contexts: HashMap<String, YearContext>,
+    
+    /// Timestamp format each source's lines last matched, tried first for its next line
+    timestamp_formats: HashMap<String, TimestampFormat>,
+    
+    /// Zone for naive timestamps from sources without a profile zone
+    default_timezone: Tz,
+    
+    /// Per-source format locks learned from each source's first lines
+    format_locks: FormatLocks,
+    
     /// Statistics monitor for performance tracking
     statistics_monitor: Option<StatisticsMonitor>,
     
@@ -125,9 +253,17 @@ impl TangoParser {
             None
         };
         
+        let line_assembler = LineAssembler::new(&config.multiline)
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Disabling multi-line assembly: {}", e);
+                LineAssembler::new(&MultilineConfig::disabled()).unwrap()
+            });
+        
         // Create streaming parser if enabled
         let streaming_parser = if config.enable_streaming {
-            Some(StreamingParser::with_config(config.streaming_config.clone()))
+            Some(StreamingParser::with_config(config.streaming_config.clone())
+                .with_line_assembler(line_assembler.clone())
+                .with_format_lock(config.format_lock.clone()))
         } else {
             None
         };
@@ -152,38 +288,224 @@ impl TangoParser {
             }
         }
         
-        Self {
+        let source_bindings = Self::compile_bindings(&config.bindings)
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Ignoring source bindings: {}", e);
+                Vec::new()
+            });
+        
+        let default_timezone = Self::compile_default_timezone(&config)
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Using UTC for naive timestamps: {}", e);
+                Tz::UTC
+            });
+        
+        let format_locks = FormatLocks::new(config.format_lock.clone());
+        
+        let (forced_parser, forced_profile) = Self::compile_forced_format(&config, classifier.registry())
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Detecting formats instead of forcing one: {}", e);
+                (None, None)
+            });
+        
+        let source_field_mappings = Self::compile_field_mappings(&config)
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Using the default field mappings: {}", e);
+                Vec::new()
+            });
+        
+        let mut parser = Self {
             config,
             classifier,
-            json_parser: JsonParser::new(),
-            logfmt_parser: LogfmtParser::new(),
-            pattern_parser: PatternParser::new(),
-            plain_text_parser: PlainTextParser::new(),
             profile_parsers,
+            forced_parser,
+            forced_profile,
+            source_bindings,
+            line_assembler,
+            year_contexts: HashMap::new(),
+            timestamp_formats: HashMap::new(),
+            default_timezone,
+            format_locks,
             statistics_monitor,
             streaming_parser,
             parallel_parser,
-        }
+        };
+        parser.classifier.set_source_field_mappings(source_field_mappings);
+        parser.set_parser_registry(ParserRegistry::new());
+        parser
     }
     
     /// Create a profile parser from configuration
-    fn create_profile_parser(config: &ProfileConfig) -> Result<ProfileParser, ParseError> {
-        match config {
+    pub(crate) fn create_profile_parser(config: &ProfileConfig) -> Result<ProfileParser, ParseError> {
+        let parser = match config {
             ProfileConfig::Regex(regex_config) => {
-                ProfileParser::new_regex(regex_config.clone())
+                ProfileParser::new_regex(regex_config.clone())?
             }
             ProfileConfig::Csv(csv_config) => {
-                ProfileParser::new_csv(csv_config.clone())
+                ProfileParser::new_csv(csv_config.clone())?
+            }
+            ProfileConfig::Apache { format: Some(format), .. } => {
+                ProfileParser::new_apache_format(format)?
             }
-            ProfileConfig::Apache => {
-                Ok(ProfileParser::new_apache())
+            ProfileConfig::Apache { format: None, .. } => {
+                ProfileParser::new_apache()
             }
-            ProfileConfig::Nginx => {
-                Ok(ProfileParser::new_nginx())
+            ProfileConfig::Nginx { format: Some(format), .. } => {
+                ProfileParser::new_nginx_format(format)?
             }
-            ProfileConfig::Syslog => {
-                Ok(ProfileParser::new_syslog())
+            ProfileConfig::Nginx { format: None, .. } => {
+                ProfileParser::new_nginx()
             }
+            ProfileConfig::Syslog { .. } => {
+                ProfileParser::new_syslog()
+            }
+        };
+        
+        match config.default_timezone() {
+            Some(name) => Ok(parser.with_default_timezone(parse_timezone(name, "default_timezone")?)),
+            None => Ok(parser),
+        }
+    }
+    
+    /// Resolve the configured default timezone (UTC when unset)
+    fn compile_default_timezone(config: &TangoConfig) -> Result<Tz, ParseError> {
+        match &config.default_timezone {
+            Some(name) => parse_timezone(name, "default_timezone"),
+            None => Ok(Tz::UTC),
+        }
+    }
+    
+    /// Compile source bindings into glob patterns
+    fn compile_bindings(bindings: &[SourceBinding]) -> Result<Vec<(glob::Pattern, String)>, ParseError> {
+        bindings.iter()
+            .enumerate()
+            .map(|(i, binding)| {
+                glob::Pattern::new(&binding.pattern)
+                    .map(|pattern| (pattern, binding.profile.clone()))
+                    .map_err(|e| ParseError::ConfigurationError {
+                        parameter: format!("bindings[{}].pattern", i),
+                        error_message: format!("Invalid glob '{}': {}", binding.pattern, e),
+                    })
+            })
+            .collect()
+    }
+    
+    /// Field mappings for each source pattern, with the global mappings they change
+    fn compile_field_mappings(config: &TangoConfig) -> Result<Vec<(glob::Pattern, FieldMappings)>, ParseError> {
+        config.field_mappings.validate("field_mappings")?;
+        config.source_field_mappings.iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let pattern = glob::Pattern::new(&rule.pattern)
+                    .map_err(|e| ParseError::ConfigurationError {
+                        parameter: format!("source_field_mappings[{}].pattern", i),
+                        error_message: format!("Invalid glob '{}': {}", rule.pattern, e),
+                    })?;
+                let field_mappings = config.field_mappings.overridden_by(rule);
+                field_mappings.validate(&format!("source_field_mappings[{}]", i))?;
+                Ok((pattern, field_mappings))
+            })
+            .collect()
+    }
+    
+    /// Check that `format` and `profile` name a known format and a configured
+    /// profile, with only the built-in parsers registered
+    pub fn validate_forced_format(config: &TangoConfig) -> Result<(), ParseError> {
+        Self::compile_forced_format(config, &ParserRegistry::new()).map(|_| ())
+    }
+    
+    /// Parser or profile forced by `format` or `profile`, if either is set
+    fn compile_forced_format(
+        config: &TangoConfig,
+        registry: &ParserRegistry,
+    ) -> Result<(Option<RegisteredParser>, Option<ProfileParser>), ParseError> {
+        if let Some(name) = &config.profile {
+            if config.format.is_some() {
+                return Err(ParseError::ConfigurationError {
+                    parameter: "profile".to_string(),
+                    error_message: "A format and a profile cannot both be forced".to_string(),
+                });
+            }
+            let profile = config.profiles.get(name).ok_or_else(|| ParseError::ConfigurationError {
+                parameter: "profile".to_string(),
+                error_message: format!("Unknown profile '{}'", name),
+            })?;
+            return Ok((None, Some(Self::create_profile_parser(profile)?)));
+        }
+        
+        let Some(name) = &config.format else {
+            return Ok((None, None));
+        };
+        let profile = match name.to_ascii_lowercase().as_str() {
+            "apache" => ProfileParser::new_apache(),
+            "nginx" => ProfileParser::new_nginx(),
+            "syslog" => ProfileParser::new_syslog(),
+            "csv" => {
+                let mut csv_profiles = config.profiles.values()
+                    .filter(|profile| matches!(profile, ProfileConfig::Csv(_)));
+                match (csv_profiles.next(), csv_profiles.next()) {
+                    (Some(profile), None) => Self::create_profile_parser(profile)?,
+                    _ => return Err(ParseError::ConfigurationError {
+                        parameter: "format".to_string(),
+                        error_message: "Forcing csv needs exactly one csv profile in the config; name it with profile instead".to_string(),
+                    }),
+                }
+            }
+            "logcat" => {
+                // The timestamp/level parser, offered logcat lines only
+                let logcat = PatternParser::new();
+                let parser = registry.for_format(FormatType::TimestampLevel)
+                    .expect("timestamp/level parser is always registered")
+                    .clone()
+                    .with_detector(move |line| logcat.is_logcat(line));
+                return Ok((Some(parser), None));
+            }
+            "pattern" => return Ok((registry.for_format(FormatType::TimestampLevel).cloned(), None)),
+            _ => match registry.get(name) {
+                Some(parser) => return Ok((Some(parser.clone()), None)),
+                None => {
+                    let known: Vec<&str> = ["csv", "logcat"].into_iter()
+                        .chain(registry.names())
+                        .collect();
+                    return Err(ParseError::ConfigurationError {
+                        parameter: "format".to_string(),
+                        error_message: format!("Unknown format '{}' (expected one of: {})", name, known.join(", ")),
+                    });
+                }
+            },
+        };
+        Ok((None, Some(profile)))
+    }
+    
+    /// For rejects: why a line detection left to plain text matched no format
+    fn unmatched_error(&self, success: bool, format_type: &FormatType, line: &str, source: &str) -> Option<ParseError> {
+        let unmatched = success && *format_type == FormatType::PlainText && self.config.rejects.is_enabled();
+        unmatched.then(|| self.classifier.registry_for(source).unmatched_error(line))
+    }
+    
+    /// Parse a line with a forced parser; lines its detector rejects fail
+    /// instead of falling back to another format
+    fn parse_forced(parser: &RegisteredParser, line: &str, learned: Option<TimestampFormat>) -> ParseResult {
+        if parser.detects(line) {
+            return parser.parse_learned(line, learned);
+        }
+        ParseResult::failure(line.to_string(), Self::forced_mismatch(parser, line))
+            .with_attempted_format(parser.format_type())
+    }
+    
+    /// `parse_forced` into an event borrowing from the line
+    fn parse_forced_ref<'a>(parser: &RegisteredParser, line: &'a str, learned: Option<TimestampFormat>) -> ParseResultRef<'a> {
+        if parser.detects(line) {
+            return parser.parse_learned_ref(line, learned);
+        }
+        ParseResultRef::failure(line, Self::forced_mismatch(parser, line))
+            .with_attempted_format(parser.format_type())
+    }
+    
+    fn forced_mismatch(parser: &RegisteredParser, line: &str) -> ParseError {
+        ParseError::PatternMatchError {
+            input: line.to_string(),
+            attempted_patterns: vec![parser.name().to_string()],
         }
     }
     
@@ -197,39 +519,329 @@ impl TangoParser {
     pub fn parse_line_with_source(&mut self, line: &str, source: &str) -> ParseResult {
         let start_time = std::time::Instant::now();
         
-        // Check if there's a specific profile for this source
-        if let Some(profile_parser) = self.get_profile_parser_for_source(source) {
-            let result = profile_parser.parse(line);
-            self.record_statistics(&result, start_time.elapsed().as_micros() as u64);
-            return result;
+        // Check if there's a forced format or a specific profile for this source
+        let learned = self.learned_timestamp_format(source);
+        let mut result = match (self.forced_parser_for(source), self.get_profile_parser_for_source(source)) {
+            (Some(parser), _) => Self::parse_forced(parser, line, learned),
+            (None, Some(profile_parser)) => profile_parser.parse_learned(line, learned),
+            // Use automatic format detection
+            (None, None) => {
+                let mut result = self.parse_detected(line, source, |parser, format_type| parser.parse_as(format_type, line, source), |result| result.success);
+                result.fallback_error = self.unmatched_error(result.success, &result.event.format_type, line, source);
+                result
+            }
+        };
+        if result.success && wraps_payload(&result.event.format_type) {
+            self.unwrap_payload(&mut result, source);
         }
+        self.learn_timestamp_format(source, result.timestamp_format);
+        self.complete_timestamp(&mut result.event, source);
         
-        // Use automatic format detection
-        let format_type = if self.config.enable_format_caching {
-            self.classifier.detect_format_with_caching(line, source)
-        } else {
-            self.classifier.detect_format(line, source)
-        };
+        // Record statistics if enabled
+        let processing_time = start_time.elapsed().as_micros() as u64;
+        self.record_statistics(&result, processing_time);
         
-        // Get the appropriate parser and parse the line
-        let result = match format_type {
-            FormatType::Json => self.json_parser.parse(line),
-            FormatType::Logfmt => self.logfmt_parser.parse(line),
-            FormatType::TimestampLevel | FormatType::Pattern => self.pattern_parser.parse(line),
-            FormatType::Profile(_profile_type) => {
-                // This shouldn't happen with auto-detection, but handle gracefully
-                self.plain_text_parser.parse(line)
+        result
+    }
+    
+    /// Parse a single log line into an event borrowing from it; detection, format
+    /// locks and timestamp completion work as in `parse_line_with_source`
+    pub fn parse_line_ref<'a>(&mut self, line: &'a str, source: &str) -> ParseResultRef<'a> {
+        let start_time = std::time::Instant::now();
+        
+        let learned = self.learned_timestamp_format(source);
+        let mut result = match (self.forced_parser_for(source), self.get_profile_parser_for_source(source)) {
+            (Some(parser), _) => Self::parse_forced_ref(parser, line, learned),
+            (None, Some(profile_parser)) => profile_parser.parse_learned_ref(line, learned),
+            (None, None) => {
+                let mut result = self.parse_detected(line, source, |parser, format_type| parser.parse_as_ref(format_type, line, source), |result| result.success);
+                result.fallback_error = self.unmatched_error(result.success, &result.event.format_type, line, source);
+                result
             }
-            FormatType::PlainText => self.plain_text_parser.parse(line),
         };
+        if result.success && wraps_payload(&result.event.format_type) {
+            // The payload is reparsed into owned text
+            let mut owned = result.into_owned();
+            self.unwrap_payload(&mut owned, source);
+            result = ParseResultRef::from(owned);
+        }
+        self.learn_timestamp_format(source, result.timestamp_format);
+        let event = &mut result.event;
+        if let Some(timestamp) = event.timestamp {
+            event.timestamp = Some(self.completed_timestamp(timestamp, event.year_inferred, event.offset_assumed, source));
+        }
         
-        // Record statistics if enabled
         let processing_time = start_time.elapsed().as_micros() as u64;
-        self.record_statistics(&result, processing_time);
+        self.record_outcome(result.success, result.event.format_type.clone(), result.error.as_ref(), processing_time);
+        
+        result
+    }
+    
+    /// Parse the application's line a container runtime wrapped (CRI, Docker
+    /// json-file) with detection, taking its message, level and fields. The
+    /// runtime's timestamp and stream are kept, and pod, namespace and
+    /// container are filled in from the source's path
+    fn unwrap_payload(&self, result: &mut ParseResult, source: &str) {
+        let event = &mut result.event;
+        if let Some(container) = ContainerPath::parse(source) {
+            for (name, value) in container.fields() {
+                event.fields.entry(name.to_string()).or_insert_with(|| value.into());
+            }
+        }
+        if event.message.trim().is_empty() {
+            return;
+        }
+        
+        let format_type = self.classifier.detect_format(&event.message, source);
+        let payload = self.parse_as(format_type.clone(), &event.message, source);
+        if !payload.success || wraps_payload(&format_type) {
+            return;
+        }
+        let timestamp_format = payload.timestamp_format;
+        let payload = payload.event;
+        event.message = payload.message;
+        event.level = payload.level.or(event.level);
+        if event.timestamp.is_none() {
+            event.timestamp = payload.timestamp;
+            event.year_inferred = payload.year_inferred;
+            event.offset_assumed = payload.offset_assumed;
+            result.timestamp_format = timestamp_format;
+        }
+        for (key, value) in payload.fields {
+            event.fields.entry(key).or_insert(value);
+        }
+        event.add_field("payload_format".to_string(), format_type.to_string());
+    }
+    
+    /// Detect the line's format, through the source's format lock when enabled,
+    /// and parse it with `parse`
+    fn parse_detected<R>(
+        &mut self,
+        line: &str,
+        source: &str,
+        parse: impl Fn(&Self, FormatType) -> R,
+        succeeded: impl Fn(&R) -> bool,
+    ) -> R {
+        if !self.format_locks.is_enabled() {
+            let format_type = if self.config.enable_format_caching {
+                self.classifier.detect_format_with_caching(line, source)
+            } else {
+                self.classifier.detect_format(line, source)
+            };
+            return parse(self, format_type);
+        }
         
+        // Parse with the source's locked format, falling back to full detection
+        // while sampling, for mixed sources and on a miss
+        if let Some(format_type) = self.format_locks.locked_format(source) {
+            let result = parse(self, format_type);
+            let success = succeeded(&result);
+            self.format_locks.record_locked_parse(source, success);
+            if success {
+                return result;
+            }
+        }
+        
+        let (format_type, confidence) = self.classifier.detect_format_with_confidence(line, source);
+        self.format_locks.record_detection(source, format_type.clone(), confidence);
+        parse(self, format_type)
+    }
+    
+    /// Parse a line with the parser for a detected format and the source's field mappings
+    fn parse_as(&self, format_type: FormatType, line: &str, source: &str) -> ParseResult {
+        let learned = self.learned_timestamp_format(source);
+        self.classifier.registry_for(source).parse_as_learned(format_type, line, learned)
+    }
+    
+    /// `parse_as` into an event borrowing from the line
+    fn parse_as_ref<'a>(&self, format_type: FormatType, line: &'a str, source: &str) -> ParseResultRef<'a> {
+        let learned = self.learned_timestamp_format(source);
+        self.classifier.registry_for(source).parse_as_learned_ref(format_type, line, learned)
+    }
+    
+    /// The forced parser; forced JSON, logfmt and plain text use the source's field mappings
+    fn forced_parser_for(&self, source: &str) -> Option<&RegisteredParser> {
+        let forced = self.forced_parser.as_ref()?;
+        match forced.format_type() {
+            FormatType::Json | FormatType::Logfmt | FormatType::PlainText => self.classifier.registry_for(source).for_forced_format(forced.format_type()),
+            _ => Some(forced),
+        }
+    }
+    
+    /// Per-source format lock decisions made by line-by-line parsing
+    pub fn format_locks(&self) -> &FormatLocks {
+        &self.format_locks
+    }
+    
+    /// Fold in format locks learned by another parser from later lines of the same sources
+    pub fn merge_format_locks(&mut self, later: &FormatLocks) {
+        self.format_locks.merge(later);
+    }
+    
+    /// Name of the timestamp format the source's lines last matched
+    pub fn timestamp_format(&self, source: &str) -> Option<&'static str> {
+        self.timestamp_formats.get(source).map(|format| format.name())
+    }
+    
+    /// Timestamp format the source's lines last matched, tried first on its next line
+    fn learned_timestamp_format(&self, source: &str) -> Option<TimestampFormat> {
+        self.timestamp_formats.get(source).copied()
+    }
+    
+    /// Remember the format a source's line matched so its next lines try it first
+    fn learn_timestamp_format(&mut self, source: &str, matched: Option<TimestampFormat>) {
+        if let Some(format) = matched {
+            if self.timestamp_formats.get(source) != Some(&format) {
+                self.timestamp_formats.insert(source.to_string(), format);
+            }
+        }
+    }
+    
+    /// Start year inference for a source last written at `modified`;
+    /// an explicit `TangoConfig::year` takes precedence
+    pub fn set_source_modified(&mut self, source: &str, modified: Option<SystemTime>) {
+        let context = match (self.config.year, modified) {
+            (Some(year), _) => YearContext::explicit(year),
+            (None, Some(modified)) => YearContext::from_modified(modified),
+            (None, None) => YearContext::now(),
+        };
+        self.year_contexts.insert(source.to_string(), context);
+    }
+    
+    /// Complete timestamps the log left partial: infer a missing year from the
+    /// source's context, then interpret naive times in the source's timezone
+    fn complete_timestamp(&mut self, event: &mut CanonicalEvent, source: &str) {
+        if let Some(timestamp) = event.timestamp {
+            event.timestamp = Some(self.completed_timestamp(timestamp, event.year_inferred, event.offset_assumed, source));
+        }
+    }
+    
+    /// `complete_timestamp` for an event's timestamp and flags
+    fn completed_timestamp(
+        &mut self,
+        mut timestamp: DateTime<Utc>,
+        year_inferred: Option<bool>,
+        offset_assumed: Option<bool>,
+        source: &str,
+    ) -> DateTime<Utc> {
+        if year_inferred == Some(true) {
+            let year = self.config.year;
+            let context = self.year_contexts.entry(source.to_string())
+                .or_insert_with(|| year.map(YearContext::explicit).unwrap_or_else(YearContext::now));
+            timestamp = context.apply(timestamp);
+        }
+        if offset_assumed == Some(true) {
+            timestamp = localize(timestamp.naive_utc(), self.timezone_for_source(source));
+        }
+        timestamp
+    }
+    
+    /// Timezone for naive timestamps from a source: its profile's zone, else the configured default
+    fn timezone_for_source(&self, source: &str) -> Tz {
+        self.get_profile_parser_for_source(source)
+            .and_then(|parser| parser.default_timezone())
+            .unwrap_or(self.default_timezone)
+    }
+    
+    /// Parse a group of lines assembled into one event
+    pub fn parse_group_with_source(&mut self, group: &LineGroup, source: &str) -> ParseResult {
+        let mut result = match CriParser::reassemble(&group.lines) {
+            Some(line) => {
+                let mut result = self.parse_line_with_source(&line, source);
+                result.event.raw = group.lines.join("\n");
+                result
+            }
+            None => {
+                let mut result = self.parse_line_with_source(&group.lines[0], source);
+                attach_continuation(&mut result.event, group);
+                result
+            }
+        };
+        result.event.source.offset = group.offset;
+        result.event.source.host = result.event.fields.get("hostname")
+            .and_then(|host| host.as_str())
+            .map(str::to_string)
+            .or_else(|| self.config.host.clone());
+        result.with_line_number(group.line_number)
+    }
+    
+    /// Parse a group of lines read from an input, tagging the event with its source
+    pub fn parse_group_from_input(&mut self, group: &LineGroup, input: &LogInput) -> ParseResult {
+        if !self.year_contexts.contains_key(input.source()) {
+            self.set_source_modified(input.source(), input.modified());
+        }
+        let mut result = self.parse_group_with_source(group, input.source());
+        let source = &mut result.event.source;
+        *source = SourceMetadata {
+            line: Some(group.line_number),
+            offset: source.offset,
+            host: source.host.take(),
+            ..input.source_metadata()
+        };
         result
     }
     
+    /// `parse_group_with_source` for a group of borrowed lines
+    pub fn parse_group_ref<'a>(&mut self, group: &LineGroupRef<'a>, source: &str) -> ParseResultRef<'a> {
+        let mut result = match CriParser::reassemble(&group.lines) {
+            // The joined line is parsed into owned text
+            Some(line) => {
+                let mut result = self.parse_line_with_source(&line, source);
+                result.event.raw = group.lines.join("\n");
+                ParseResultRef::from(result)
+            }
+            None => {
+                let mut result = self.parse_line_ref(group.lines[0], source);
+                attach_continuation_ref(&mut result.event, group);
+                result
+            }
+        };
+        result.event.source.offset = group.offset;
+        result.event.source.host = match result.event.fields.get("hostname") {
+            Some(ValueRef::String(host)) => Some(host.clone()),
+            _ => self.config.host.clone().map(Cow::Owned),
+        };
+        result.with_line_number(group.line_number)
+    }
+    
+    /// `parse_group_from_input` for a group of lines borrowed from the input
+    pub fn parse_group_ref_from_input<'a>(&mut self, group: &LineGroupRef<'a>, input: &'a LogInput) -> ParseResultRef<'a> {
+        if !self.year_contexts.contains_key(input.source()) {
+            self.set_source_modified(input.source(), input.modified());
+        }
+        let mut result = self.parse_group_ref(group, input.source());
+        let source = &mut result.event.source;
+        *source = SourceRef {
+            line: Some(group.line_number),
+            offset: source.offset,
+            host: source.host.take(),
+            ..input.source_ref()
+        };
+        result
+    }
+    
+    /// Fresh line assembler using the configured multi-line rules
+    pub fn line_assembler(&self) -> LineAssembler {
+        self.line_assembler.clone()
+    }
+    
+    /// Group lines from an iterator into events using the configured multi-line rules
+    pub fn group_lines<I, L>(&self, lines: I) -> GroupedLines<I>
+    where
+        I: Iterator<Item = std::io::Result<L>>,
+        L: Into<SourceLine>,
+    {
+        GroupedLines::new(lines, self.line_assembler())
+    }
+    
+    /// `group_lines` for lines borrowed from the input
+    pub fn group_line_refs<'a, I>(&self, lines: I) -> GroupedLines<I, &'a str>
+    where
+        I: Iterator<Item = std::io::Result<SourceLine<&'a str>>>,
+    {
+        GroupedLines::new(lines, self.line_assembler.for_lines())
+    }
+    
     /// Parse multiple log lines
     pub fn parse_lines<I>(&mut self, lines: I) -> Vec<ParseResult>
     where
@@ -252,16 +864,22 @@ impl TangoParser {
     
     /// Parse from a reader (file, stream, etc.) using streaming processing
     pub fn parse_reader<R: Read>(&mut self, reader: R, source: &str) -> Result<Vec<ParseResult>, std::io::Error> {
-        if let Some(ref mut streaming_parser) = self.streaming_parser {
-            streaming_parser.parse_stream(reader, source)
+        // A forced format bypasses the streaming parser's detection
+        let forced = self.forced_parser.is_some() || self.forced_profile.is_some();
+        if let Some(streaming_parser) = self.streaming_parser.as_mut().filter(|_| !forced) {
+            let mut results = streaming_parser.parse_stream(reader, source)?;
+            for result in &mut results {
+                self.complete_timestamp(&mut result.event, source);
+            }
+            Ok(results)
         } else {
-            // Fallback to simple line-by-line parsing
+            // Fallback to simple group-by-group parsing
             let buf_reader = BufReader::new(reader);
             let mut results = Vec::new();
             
-            for line_result in buf_reader.lines() {
-                let line = line_result?;
-                results.push(self.parse_line_with_source(&line, source));
+            for group in self.group_lines(buf_reader.lines()) {
+                let group = group?;
+                results.push(self.parse_group_with_source(&group, source));
             }
             
             Ok(results)
@@ -273,9 +891,21 @@ impl TangoParser {
         &mut self,
         readers_with_sources: Vec<(R, String)>
     ) -> Result<Vec<Vec<ParseResult>>, std::io::Error> {
-        if let Some(ref parallel_parser) = self.parallel_parser {
+        let forced = self.forced_parser.is_some() || self.forced_profile.is_some();
+        if let Some(parallel_parser) = self.parallel_parser.as_ref().filter(|_| !forced) {
+            let sources: Vec<String> = readers_with_sources.iter()
+                .map(|(_, source)| source.clone())
+                .collect();
             let parallel_results = parallel_parser.parse_streams_parallel(readers_with_sources)?;
-            Ok(parallel_results.into_iter().map(|result| result.results).collect())
+            
+            let mut all_results = Vec::new();
+            for (mut result, source) in parallel_results.into_iter().zip(sources) {
+                for parsed in &mut result.results {
+                    self.complete_timestamp(&mut parsed.event, &source);
+                }
+                all_results.push(result.results);
+            }
+            Ok(all_results)
         } else {
             // Fallback to sequential processing
             let mut all_results = Vec::new();
@@ -289,6 +919,20 @@ impl TangoParser {
     
     /// Get the profile parser for a specific source (if configured)
     fn get_profile_parser_for_source(&self, source: &str) -> Option<&ProfileParser> {
+        // A forced profile applies to every source
+        if let Some(parser) = &self.forced_profile {
+            return Some(parser);
+        }
+        
+        // Explicit bindings take precedence, first match wins
+        for (pattern, profile_name) in &self.source_bindings {
+            if pattern.matches(source) {
+                if let Some(parser) = self.profile_parsers.get(profile_name) {
+                    return Some(parser);
+                }
+            }
+        }
+        
         // Check for exact source match first
         if let Some(parser) = self.profile_parsers.get(source) {
             return Some(parser);
@@ -324,11 +968,16 @@ impl TangoParser {
     
     /// Record parsing statistics
     fn record_statistics(&mut self, result: &ParseResult, processing_time_micros: u64) {
+        self.record_outcome(result.success, result.event.format_type.clone(), result.error.as_ref(), processing_time_micros);
+    }
+    
+    /// Record a parse in the statistics monitor, if enabled
+    fn record_outcome(&mut self, success: bool, format_type: FormatType, error: Option<&ParseError>, processing_time_micros: u64) {
         if let Some(ref mut monitor) = self.statistics_monitor {
-            if result.success {
-                monitor.record_success(result.event.format_type, processing_time_micros);
+            if success {
+                monitor.record_success(format_type, processing_time_micros);
             } else {
-                if let Some(ref error) = result.error {
+                if let Some(error) = error {
                     monitor.record_failure(error, processing_time_micros);
                 } else {
                     // Create a generic error for failed parsing without specific error
@@ -347,6 +996,13 @@ impl TangoParser {
         self.statistics_monitor.as_ref().map(|monitor| monitor.get_statistics())
     }
     
+    /// Add the statistics another parser gathered on lines of the same inputs, if enabled
+    pub fn merge_statistics(&mut self, other: &ParsingStatistics) {
+        if let Some(ref mut monitor) = self.statistics_monitor {
+            monitor.merge(other);
+        }
+    }
+    
     /// Get format classifier statistics
     pub fn get_classifier_stats(&self) -> crate::classifier::CacheStats {
         self.classifier.cache_stats()
@@ -355,6 +1011,50 @@ impl TangoParser {
     /// Clear format cache
     pub fn clear_format_cache(&mut self) {
         self.classifier.clear_cache();
+        self.format_locks.clear();
+    }
+    
+    /// Detect and parse with the parsers in `registry`
+    pub fn with_parser_registry(mut self, registry: ParserRegistry) -> Self {
+        self.set_parser_registry(registry);
+        self
+    }
+    
+    /// Register a custom parser for detection and parsing; names must be unique
+    pub fn register_parser(&mut self, parser: RegisteredParser) -> Result<(), ParseError> {
+        let mut registry = self.parser_registry().clone();
+        registry.register(parser)?;
+        self.set_parser_registry(registry);
+        Ok(())
+    }
+    
+    /// Parsers lines are detected and parsed with, built-in and registered
+    pub fn parser_registry(&self) -> &ParserRegistry {
+        self.classifier.registry()
+    }
+    
+    /// Formats a line is tried as before it falls back to plain text; empty
+    /// when a format or profile is forced, as nothing falls back then
+    pub fn fallback_formats(&self) -> Vec<FormatType> {
+        if self.forced_parser.is_some() || self.forced_profile.is_some() {
+            return Vec::new();
+        }
+        self.classifier.detectable_formats()
+    }
+    
+    /// Hand the registry to the classifier and the streaming and parallel pipelines
+    fn set_parser_registry(&mut self, registry: ParserRegistry) {
+        let registry = registry.with_field_mappings(self.config.field_mappings.clone())
+            .with_flatten(self.config.flatten.clone());
+        self.streaming_parser = self.streaming_parser.take()
+            .map(|parser| parser.with_parser_registry(registry.clone()));
+        self.parallel_parser = self.parallel_parser.take()
+            .map(|parser| parser.with_parser_registry(registry.clone()));
+        if let Ok((forced_parser, forced_profile)) = Self::compile_forced_format(&self.config, &registry) {
+            self.forced_parser = forced_parser;
+            self.forced_profile = forced_profile;
+        }
+        self.classifier.set_registry(registry);
     }
     
     /// Add a new profile parser
@@ -396,11 +1096,24 @@ impl TangoParser {
                     error_message: format!("Invalid profile configuration: {}", e),
                 })?;
         }
+        let source_bindings = Self::compile_bindings(&new_config.bindings)?;
+        let line_assembler = LineAssembler::new(&new_config.multiline)?;
+        let default_timezone = Self::compile_default_timezone(&new_config)?;
+        Self::compile_forced_format(&new_config, self.parser_registry())?;
+        let source_field_mappings = Self::compile_field_mappings(&new_config)?;
+        new_config.flatten.validate()?;
         
         // Update configuration
         self.config = new_config;
+        self.source_bindings = source_bindings;
+        self.line_assembler = line_assembler;
+        self.default_timezone = default_timezone;
+        self.year_contexts.clear();
+        self.timestamp_formats.clear();
+        self.format_locks = FormatLocks::new(self.config.format_lock.clone());
         
-        // Recreate components that depend on configuration
+        // Recreate components that depend on configuration, keeping registered parsers
+        let registry = self.parser_registry().clone();
         self.classifier = if self.config.enable_format_caching {
             TangoFormatClassifier::with_cache_settings(
                 self.config.cache_max_entries,
@@ -436,7 +1149,9 @@ impl TangoParser {
         
         // Update streaming parser
         if self.config.enable_streaming {
-            self.streaming_parser = Some(StreamingParser::with_config(self.config.streaming_config.clone()));
+            self.streaming_parser = Some(StreamingParser::with_config(self.config.streaming_config.clone())
+                .with_line_assembler(self.line_assembler.clone())
+                .with_format_lock(self.config.format_lock.clone()));
         } else {
             self.streaming_parser = None;
         }
@@ -447,6 +1162,8 @@ impl TangoParser {
         } else {
             self.parallel_parser = None;
         }
+        self.classifier.set_source_field_mappings(source_field_mappings);
+        self.set_parser_registry(registry);
         
         Ok(())
     }
@@ -462,6 +1179,35 @@ impl TangoParser {
                 })?;
         }
         
+        // Validate source bindings
+        Self::compile_bindings(&self.config.bindings)?;
+        for (i, binding) in self.config.bindings.iter().enumerate() {
+            if !self.config.profiles.contains_key(&binding.profile) {
+                return Err(ParseError::ConfigurationError {
+                    parameter: format!("bindings[{}].profile", i),
+                    error_message: format!("Unknown profile '{}'", binding.profile),
+                });
+            }
+        }
+        
+        // Validate multi-line rules
+        LineAssembler::new(&self.config.multiline)?;
+        
+        // Validate the default timezone
+        Self::compile_default_timezone(&self.config)?;
+        
+        // Validate the forced format or profile
+        Self::compile_forced_format(&self.config, self.parser_registry())?;
+        
+        // Validate field mappings
+        Self::compile_field_mappings(&self.config)?;
+        
+        // Validate JSON flattening
+        self.config.flatten.validate()?;
+        
+        // Validate the rejects threshold
+        self.config.rejects.validate()?;
+        
         // Validate cache settings
         if self.config.cache_max_entries == 0 {
             return Err(ParseError::ConfigurationError {
@@ -510,9 +1256,16 @@ impl Default for TangoParser {
     }
 }
 
+/// Container runtime formats whose message is the application's own line
+fn wraps_payload(format_type: &FormatType) -> bool {
+    matches!(format_type, FormatType::Cri | FormatType::DockerJson)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::epoch::EpochUnit;
+    use chrono::{Datelike, TimeZone, Utc};
     use std::io::Cursor;
     
     #[test]
@@ -611,6 +1364,169 @@ Plain text third log
         assert!(results[2].success);
     }
     
+    #[test]
+    fn test_year_inference_across_rollover() {
+        let log_data = "Dec 31 23:59:58 host app[1]: last of the year
+Jan  1 00:00:02 host app[1]: first of the next
+";
+        
+        for enable_streaming in [true, false] {
+            let mut config = TangoConfig::default();
+            config.enable_streaming = enable_streaming;
+            config.year = Some(2023);
+            let mut parser = TangoParser::with_config(config);
+            
+            let results = parser.parse_reader(Cursor::new(log_data), "auth.log").unwrap();
+            let years: Vec<i32> = results.iter()
+                .map(|r| r.event.timestamp.unwrap().year())
+                .collect();
+            assert_eq!(years, vec![2023, 2024]);
+            assert_eq!(results[0].event.year_inferred, Some(true));
+        }
+        
+        // Without an explicit year, the file mtime keeps dates out of the future
+        let mut parser = TangoParser::new();
+        let modified = Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap();
+        parser.set_source_modified("auth.log", Some(modified.into()));
+        let result = parser.parse_line_with_source("Dec 31 23:59:58 host app[1]: late", "auth.log");
+        assert_eq!(result.event.timestamp.unwrap().year(), 2024);
+        
+        // Leap days survive until the source's year is known
+        let mut config = TangoConfig::default();
+        config.year = Some(2024);
+        let mut parser = TangoParser::with_config(config);
+        let expected = Some(Utc.with_ymd_and_hms(2024, 2, 29, 10, 0, 0).unwrap());
+        for (line, source) in [
+            ("Feb 29 10:00:00 host app[1]: leap", "auth.log"),
+            ("<13>Feb 29 10:00:00 host app[1]: leap", "remote.log"),
+            ("02-29 10:00:00.000  1234  1234 I Tag: leap", "logcat.log"),
+        ] {
+            assert_eq!(parser.parse_line_with_source(line, source).event.timestamp, expected, "{}", line);
+        }
+    }
+    
+    #[test]
+    fn test_default_timezone_for_naive_timestamps() {
+        let mut config = TangoConfig::default();
+        config.default_timezone = Some("America/Chicago".to_string());
+        config.profiles.insert("edge".to_string(), ProfileConfig::Syslog {
+            default_timezone: Some("Europe/Berlin".to_string()),
+        });
+        let mut parser = TangoParser::with_config(config);
+        
+        let result = parser.parse_line_with_source("2025-01-15 09:00:00 INFO started", "app.log");
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap()));
+        assert_eq!(result.event.offset_assumed, Some(true));
+        
+        // Explicit offsets are left alone
+        let result = parser.parse_line_with_source("2025-01-15T09:00:00+01:00 INFO started", "other.log");
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap()));
+        assert_eq!(result.event.offset_assumed, Some(false));
+        
+        // A profile's own zone wins over the global default
+        parser.set_source_modified("edge", Some(Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap().into()));
+        let result = parser.parse_line_with_source("<13>Jan 15 09:00:00 host app: hello", "edge");
+        assert_eq!(result.event.timestamp, Some(Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap()));
+    }
+    
+    #[test]
+    fn test_invalid_default_timezone() {
+        let mut config = TangoConfig::default();
+        config.default_timezone = Some("Nowhere/Special".to_string());
+        match TangoParser::with_config(config).validate_config() {
+            Err(ParseError::ConfigurationError { parameter, .. }) => assert_eq!(parameter, "default_timezone"),
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_multiline_stack_trace_grouping() {
+        let log_data = "[2025-12-30T10:21:03Z] [ERROR] Request failed
+java.lang.NullPointerException: null
+\tat com.example.Handler.handle(Handler.java:42)
+\tat com.example.Server.run(Server.java:7)
+[2025-12-30T10:21:04Z] [INFO] Recovered
+";
+        
+        for enable_streaming in [true, false] {
+            let mut config = TangoConfig::default();
+            config.enable_streaming = enable_streaming;
+            config.multiline.timestamp_anchor = true;
+            let mut parser = TangoParser::with_config(config);
+            
+            let results = parser.parse_reader(Cursor::new(log_data), "app.log").unwrap();
+            assert_eq!(results.len(), 2);
+            
+            let event = &results[0].event;
+            assert_eq!(event.level, Some(LogLevel::Error));
+            assert_eq!(event.raw.lines().count(), 4);
+            let trace = event.fields.get("stack_trace").and_then(|v| v.as_array()).unwrap();
+            assert_eq!(trace.len(), 3);
+            assert_eq!(results[1].line_number, Some(5));
+            assert!(!results[1].event.fields.contains_key("stack_trace"));
+        }
+    }
+    
+    #[test]
+    fn test_format_lock_falls_back_on_miss() {
+        let mut config = TangoConfig::default();
+        config.format_lock.sample_lines = 3;
+        let mut parser = TangoParser::with_config(config);
+        
+        for _ in 0..4 {
+            let result = parser.parse_line_with_source(r#"{"level":"info","msg":"ok"}"#, "app.log");
+            assert_eq!(result.event.format_type, FormatType::Json);
+        }
+        assert_eq!(parser.format_locks().locked_format("app.log"), Some(FormatType::Json));
+        
+        let result = parser.parse_line_with_source("level=warn msg=retry service=api", "app.log");
+        assert_eq!(result.event.format_type, FormatType::Logfmt);
+        assert_eq!(parser.format_locks().locked_format("app.log"), Some(FormatType::Json));
+        
+        let (_, lock) = parser.format_locks().sources()[0];
+        assert_eq!(lock.locked_at, Some(3));
+        assert_eq!(lock.lines, 5);
+        assert_eq!(lock.misses, 1);
+    }
+    
+    #[test]
+    fn test_source_metadata_from_input() {
+        let dir = std::env::temp_dir().join(format!("tango-source-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let syslog = dir.join("syslog");
+        std::fs::write(&syslog, "Jan 15 10:30:00 web-01 sshd[123]: Accepted password\n\nJan 15 10:30:02 web-02 sshd[124]: Failed password\n").unwrap();
+        let app = dir.join("app.log");
+        std::fs::write(&app, "{\"level\":\"info\",\"msg\":\"started\"}\n").unwrap();
+        
+        let mut config = TangoConfig::default();
+        config.host = Some("collector".to_string());
+        let mut parser = TangoParser::with_config(config);
+        let mut parse_all = |path: &std::path::Path| -> Vec<SourceMetadata> {
+            let input = LogInput::file(path);
+            let groups: Vec<LineGroup> = parser.group_lines(input.lines().unwrap())
+                .map(|group| group.unwrap())
+                .filter(|group| !group.is_blank())
+                .collect();
+            groups.iter().map(|group| parser.parse_group_from_input(group, &input).event.source).collect()
+        };
+        
+        let sources = parse_all(&syslog);
+        let file = Some(syslog.to_string_lossy().to_string());
+        assert_eq!(sources[1], SourceMetadata {
+            file: file.clone(),
+            stream: None,
+            host: Some("web-02".to_string()),
+            offset: Some(53),
+            line: Some(3),
+        });
+        assert_eq!((sources[0].offset, sources[0].line), (Some(0), Some(1)));
+        
+        // Lines without a host take the configured one
+        let sources = parse_all(&app);
+        assert_eq!(sources[0].host.as_deref(), Some("collector"));
+        assert_eq!(sources[0].file, Some(app.to_string_lossy().to_string()));
+    }
+    
     #[test]
     fn test_profile_management() {
         let mut parser = TangoParser::new();
@@ -629,6 +1545,7 @@ Plain text third log
             level_field: Some("level".to_string()),
             message_field: Some("message".to_string()),
             timestamp_format: None,
+            default_timezone: None,
         };
         
         let profile_config = ProfileConfig::Regex(regex_config);
@@ -675,6 +1592,240 @@ Plain text third log
         assert!(stats.successful_parses > 0);
     }
     
+    /// Parses `service|message` lines
+    struct PipeParser;
+    
+    impl LogParser for PipeParser {
+        fn parse(&self, line: &str) -> ParseResult {
+            match line.split_once('|') {
+                Some((service, message)) => {
+                    let mut event = CanonicalEvent::new(message.to_string(), line.to_string(), FormatType::PlainText);
+                    event.add_field("service".to_string(), service);
+                    ParseResult::success(event, 0.9)
+                }
+                None => ParseResult::failure(line.to_string(), ParseError::PatternMatchError {
+                    input: line.to_string(),
+                    attempted_patterns: vec!["service|message".to_string()],
+                }),
+            }
+        }
+        
+        fn can_parse(&self, line: &str) -> bool {
+            line.contains('|') && !line.contains(' ')
+        }
+        
+        fn get_format_type(&self) -> FormatType {
+            FormatType::PlainText
+        }
+    }
+    
+    #[test]
+    fn test_registered_parser_detected_and_counted() {
+        let mut parser = TangoParser::new();
+        parser.register_parser(RegisteredParser::new("pipe", 10, Box::new(PipeParser))).unwrap();
+        // Registered parsers survive reconfiguration
+        parser.update_config(parser.get_config().clone()).unwrap();
+        
+        let result = parser.parse_line_with_source("billing|charged", "pipe.log");
+        assert!(result.success);
+        assert_eq!(result.event.format_type, FormatType::Custom("pipe".into()));
+        assert_eq!(result.event.message, "charged");
+        assert_eq!(result.event.fields.get("service"), Some(&serde_json::json!("billing")));
+        
+        let json = parser.parse_line_with_source(r#"{"msg":"hello"}"#, "app.log");
+        assert_eq!(json.event.format_type, FormatType::Json);
+        
+        let stats = parser.get_statistics().unwrap();
+        assert_eq!(stats.format_distribution.get(&FormatType::Custom("pipe".into())), Some(&1));
+        assert!(parser.register_parser(RegisteredParser::new("pipe", 1, Box::new(PipeParser))).is_err());
+    }
+    
+    #[test]
+    fn test_forced_format_reports_mismatches() {
+        let mut config = TangoConfig::default();
+        config.format = Some("logfmt".to_string());
+        let mut parser = TangoParser::with_config(config);
+        
+        let logfmt = parser.parse_line_with_source("level=info msg=started port=80", "app.log");
+        assert!(logfmt.success);
+        assert_eq!(logfmt.event.format_type, FormatType::Logfmt);
+        // A forced format needs only its grammar, not the pairs detection wants
+        let short = parser.parse_line_with_source("level=info msg=hi", "app.log");
+        assert!(short.success);
+        assert_eq!((short.event.level, short.event.message.as_str()), (Some(LogLevel::Info), "hi"));
+        assert!(!parser.parse_line_with_source("no pairs here", "app.log").success);
+        
+        // No fallback to JSON or plain text
+        let json = parser.parse_line_with_source(r#"{"msg":"hello"}"#, "app.log");
+        assert!(!json.success);
+        assert!(matches!(json.error, Some(ParseError::PatternMatchError { .. })));
+        
+        let mut config = TangoConfig::default();
+        config.format = Some("syslog".to_string());
+        let mut parser = TangoParser::with_config(config);
+        assert!(!parser.parse_line_with_source("level=info msg=started", "app.log").success);
+        
+        let mut config = TangoConfig::default();
+        config.format = Some("bogus".to_string());
+        assert!(TangoParser::validate_forced_format(&config).is_err());
+        config.format = None;
+        config.profile = Some("missing".to_string());
+        assert!(TangoParser::with_config(config).validate_config().is_err());
+    }
+    
+    #[test]
+    fn test_source_field_mappings_read_nested_paths() {
+        let mut config = TangoConfig::default();
+        config.field_mappings.message.insert(0, "event.summary".to_string());
+        config.source_field_mappings.push(SourceFieldMappings {
+            pattern: "*/billing/*".to_string(),
+            timestamp: Some(vec!["eventTime".to_string(), "event.created".to_string()]),
+            level: Some(vec!["sev".to_string(), "log.level".to_string()]),
+            message: None,
+            epoch_unit: None,
+        });
+        let mut parser = TangoParser::with_config(config);
+        
+        let line = r#"{"log":{"level":"warn"},"event":{"created":"2025-01-02T03:04:05Z","summary":"charged"},"amount":5}"#;
+        let billing = parser.parse_line_with_source(line, "/srv/billing/app.log");
+        assert_eq!(billing.event.level, Some(LogLevel::Warn));
+        assert_eq!(billing.event.message, "charged");
+        assert_eq!(billing.event.timestamp, Some(Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap()));
+        assert!(!billing.event.fields.contains_key("log.level"));
+        assert!(!billing.event.fields.contains_key("event.created"));
+        
+        // Other sources keep the global timestamp keys
+        let other = parser.parse_line_with_source(line, "/srv/api/app.log");
+        assert_eq!(other.event.level, Some(LogLevel::Warn));
+        assert_eq!(other.event.timestamp, None);
+        assert!(other.event.fields.contains_key("event.created"));
+        
+        // Logfmt reads the same mappings
+        let logfmt = parser.parse_line_with_source("sev=error eventTime=2025-01-02T03:04:05Z msg=declined", "/srv/billing/app.log");
+        assert_eq!(logfmt.event.format_type, FormatType::Logfmt);
+        assert_eq!(logfmt.event.level, Some(LogLevel::Error));
+        assert_eq!(logfmt.event.message, "declined");
+        assert!(logfmt.event.timestamp.is_some());
+        
+        let mut invalid = TangoConfig::default();
+        invalid.source_field_mappings.push(SourceFieldMappings {
+            pattern: "[".to_string(),
+            timestamp: None,
+            level: None,
+            message: None,
+            epoch_unit: None,
+        });
+        assert!(TangoParser::with_config(invalid).validate_config().is_err());
+    }
+    
+    #[test]
+    fn test_epoch_timestamps_read_in_their_unit() {
+        let mut config = TangoConfig::default();
+        config.source_field_mappings.push(SourceFieldMappings {
+            pattern: "*/legacy/*".to_string(),
+            timestamp: None,
+            level: None,
+            message: None,
+            epoch_unit: Some(EpochUnit::Millis),
+        });
+        let mut parser = TangoParser::with_config(config);
+        let expected = Some(Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap());
+        
+        for line in [
+            r#"{"ts":1700000000000,"level":"info","msg":"millis"}"#,
+            r#"{"ts":1700000000.0,"level":"info","msg":"float seconds"}"#,
+            r#"{"ts":"1700000000000000","level":"info","msg":"micros as text"}"#,
+            "ts=1700000000 level=info msg=logfmt",
+            "1700000000000000000 service started",
+        ] {
+            assert_eq!(parser.parse_line_with_source(line, "/srv/app.log").event.timestamp, expected, "{}", line);
+        }
+        
+        // The legacy source writes milliseconds that would read as seconds
+        let legacy = parser.parse_line_with_source(r#"{"ts":1700000000,"msg":"old"}"#, "/srv/legacy/app.log");
+        assert_eq!(legacy.event.timestamp, Some(Utc.with_ymd_and_hms(1970, 1, 20, 16, 13, 20).unwrap()));
+        let plain = parser.parse_line_with_source("1700000000000 service started", "/srv/legacy/app.log");
+        assert_eq!(plain.event.timestamp, expected);
+    }
+    
+    #[test]
+    fn test_timestamp_formats_recognized_and_learned_per_source() {
+        let mut parser = TangoParser::new();
+        let expected = Some(Utc.with_ymd_and_hms(2024, 1, 2, 15, 4, 5).unwrap());
+        
+        for (source, line, format) in [
+            ("app.log", "2024-01-02 15:04:05,000 INFO [main] Started", "log4j"),
+            ("events.log", "1/2/2024 3:04:05 PM Service entered the running state", "windows"),
+            ("go.log", "2024-01-02 16:04:05 +0100 CET worker started", "go"),
+            ("mail.log", "Tue, 02 Jan 2024 15:04:05 GMT delivered", "rfc2822"),
+        ] {
+            let result = parser.parse_line_with_source(line, source);
+            assert_eq!(result.event.timestamp, expected, "{}", line);
+            assert_eq!(result.timestamp_format.map(TimestampFormat::name), Some(format));
+            assert_eq!(parser.timestamp_format(source), Some(format));
+        }
+        
+        let klog = parser.parse_line_with_source("I0102 15:04:05.000000    1 main.go:42] ready", "kubelet.log");
+        assert_eq!(klog.event.year_inferred, Some(true));
+        assert_eq!(parser.timestamp_format("kubelet.log"), Some("klog"));
+        assert_eq!(parser.timestamp_format("other.log"), None);
+        
+        // A learned format does not cut short a more specific one
+        parser.parse_line_with_source("2024-01-02 15:04:05 INFO worker started", "mixed.log");
+        assert_eq!(parser.timestamp_format("mixed.log"), Some("datetime"));
+        let go = parser.parse_line_with_source("2024-01-02 16:04:05 +0100 CET worker started", "mixed.log");
+        assert_eq!(go.event.timestamp, expected);
+        assert_eq!(go.event.offset_assumed, Some(false));
+        assert_eq!(parser.timestamp_format("mixed.log"), Some("go"));
+    }
+    
+    #[test]
+    fn test_container_logs_unwrap_payload_and_path_metadata() {
+        let mut parser = TangoParser::new();
+        let pods = "/var/log/pods/prod_api-1_0b1c3f9e/server/0.log";
+        
+        let cri = parser.parse_line_with_source(r#"2024-01-01T00:00:00Z stderr F {"level":"error","msg":"db down","retry":3}"#, pods);
+        assert!(cri.success);
+        let event = cri.event;
+        assert_eq!(event.format_type, FormatType::Cri);
+        assert_eq!(event.level, Some(LogLevel::Error));
+        assert_eq!(event.message, "db down");
+        assert_eq!(event.fields["stream"], "stderr");
+        assert_eq!(event.fields["retry"], 3);
+        assert_eq!(event.fields["payload_format"], "Json");
+        assert_eq!(event.fields["namespace"], "prod");
+        assert_eq!(event.fields["pod"], "api-1");
+        assert_eq!(event.fields["container"], "server");
+        // The envelope's time wins over the payload's
+        assert_eq!(event.timestamp, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+        
+        let docker = parser.parse_line_with_source(r#"{"log":"level=warn msg=\"disk low\" component=storage\n","stream":"stdout","time":"2024-01-01T00:00:01Z"}"#, "json.log");
+        assert_eq!(docker.event.format_type, FormatType::DockerJson);
+        assert_eq!(docker.event.level, Some(LogLevel::Warn));
+        assert_eq!(docker.event.message, "disk low");
+        assert_eq!(docker.event.fields["component"], "storage");
+        assert_eq!(docker.event.fields["payload_format"], "Logfmt");
+        
+        let klog = parser.parse_line_with_source("W0102 15:04:05.000000    7 leader.go:12] lease lost", pods);
+        assert_eq!(klog.event.format_type, FormatType::Klog);
+        assert_eq!(klog.event.level, Some(LogLevel::Warn));
+        
+        // Partial lines are joined into one payload before it is parsed
+        let group = LineGroup {
+            lines: vec![
+                r#"2024-01-01T00:00:02Z stdout P {"level":"info","#.to_string(),
+                r#"2024-01-01T00:00:02Z stdout F "msg":"joined"}"#.to_string(),
+            ],
+            line_number: 1,
+            offset: Some(0),
+        };
+        let joined = parser.parse_group_with_source(&group, pods);
+        assert_eq!(joined.event.message, "joined");
+        assert_eq!(joined.event.level, Some(LogLevel::Info));
+        assert!(!joined.event.fields.contains_key("partial"));
+        assert_eq!(joined.event.raw, group.lines.join("\n"));
+    }
+    
     #[test]
     fn test_configuration_validation() {
         let parser = TangoParser::new();
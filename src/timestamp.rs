@@ -0,0 +1,292 @@
+use crate::models::LogTimestamp;
+use crate::year_inference::{assume_year, PLACEHOLDER_YEAR};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// How a catalog entry turns matched text into a timestamp
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    /// ISO 8601 with an offset or `Z`; a comma may separate the fraction
+    IsoOffset,
+    Rfc2822,
+    /// strftime with an offset
+    Offset(&'static str),
+    /// strftime ending in a literal `Z`
+    Utc(&'static str),
+    /// strftime without an offset: wall-clock time local to the source
+    Local(&'static str),
+    /// strftime of `PLACEHOLDER_YEAR` and a year-less timestamp; the year is inferred
+    NoYear(&'static str),
+    /// Go's `time.Time` default: `2006-01-02 15:04:05.999999999 -0700 MST`
+    Go,
+    /// klog header: severity letter, then `MMDD HH:MM:SS.uuuuuu`
+    Klog,
+}
+
+/// Timestamp formats recognized in log lines and fields, most specific first
+/// where one's text starts with another's
+const CATALOG: &[(&str, &str, Kind)] = &[
+    ("iso8601", r"\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:[Zz]|[+-]\d{2}:?\d{2})", Kind::IsoOffset),
+    ("go", r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)? [+-]\d{4} [A-Z]{1,5}(?: m=[+-]\d+\.\d+)?", Kind::Go),
+    ("iso8601_local", r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::Local("%Y-%m-%dT%H:%M:%S%.f")),
+    ("log4j", r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2},\d{3}", Kind::Local("%Y-%m-%d %H:%M:%S,%3f")),
+    ("datetime", r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::Local("%Y-%m-%d %H:%M:%S%.f")),
+    ("slashed", r"\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::Local("%Y/%m/%d %H:%M:%S%.f")),
+    ("compact_utc", r"\d{8}T\d{6}Z", Kind::Utc("%Y%m%dT%H%M%SZ")),
+    ("compact", r"\d{8}[ T]\d{6}", Kind::Local("%Y%m%d %H%M%S")),
+    ("clf", r"\d{2}/[A-Z][a-z]{2}/\d{4}:\d{2}:\d{2}:\d{2} [+-]\d{4}", Kind::Offset("%d/%b/%Y:%H:%M:%S %z")),
+    ("rfc2822", r"(?:[A-Z][a-z]{2}, )?\d{1,2} [A-Z][a-z]{2} \d{4} \d{2}:\d{2}(?::\d{2})? (?:[+-]\d{4}|UT|GMT|[ECMP][SD]T)", Kind::Rfc2822),
+    ("asctime", r"[A-Z][a-z]{2} [A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}(?:\.\d+)? \d{4}", Kind::Local("%a %b %e %H:%M:%S%.f %Y")),
+    ("windows", r"\d{1,2}/\d{1,2}/\d{4} \d{1,2}:\d{2}:\d{2} [AP]M", Kind::Local("%m/%d/%Y %I:%M:%S %p")),
+    ("java", r"[A-Z][a-z]{2} \d{1,2}, \d{4} \d{1,2}:\d{2}:\d{2} [AP]M", Kind::Local("%b %d, %Y %I:%M:%S %p")),
+    ("tomcat", r"\d{2}-[A-Z][a-z]{2}-\d{4} \d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::Local("%d-%b-%Y %H:%M:%S%.f")),
+    ("syslog_year", r"[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2} \d{4}", Kind::Local("%b %d %H:%M:%S %Y")),
+    ("syslog", r"[A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::NoYear("%Y %b %d %H:%M:%S%.f")),
+    ("logcat", r"\d{2}-\d{2} +\d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::NoYear("%Y %m-%d %H:%M:%S%.f")),
+    ("klog", r"[IWEF]\d{4} \d{2}:\d{2}:\d{2}(?:\.\d+)?", Kind::Klog),
+];
+
+/// Formats whose text, ended at a boundary, an earlier and more specific format
+/// can continue (Go's offset and zone, syslog's year); a learned format's match
+/// is checked against these alone
+const EXTENDED_BY: &[(&str, &[&str])] = &[
+    ("datetime", &["go"]),
+    ("syslog", &["syslog_year"]),
+];
+
+fn patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        CATALOG.iter()
+            .map(|(_, pattern, _)| Regex::new(&format!("^(?:{})", pattern)).unwrap())
+            .collect()
+    })
+}
+
+/// `EXTENDED_BY` for each format, by catalog index
+fn extensions() -> &'static [Vec<TimestampFormat>] {
+    static EXTENSIONS: OnceLock<Vec<Vec<TimestampFormat>>> = OnceLock::new();
+    EXTENSIONS.get_or_init(|| {
+        let index = |name: &str| TimestampFormat(format_names().position(|format| format == name).unwrap());
+        let mut extensions = vec![Vec::new(); CATALOG.len()];
+        for (name, extended_by) in EXTENDED_BY {
+            extensions[index(name).0] = extended_by.iter().map(|name| index(name)).collect();
+        }
+        extensions
+    })
+}
+
+/// A catalog format, as remembered for a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampFormat(usize);
+
+impl TimestampFormat {
+    pub fn name(self) -> &'static str {
+        CATALOG[self.0].0
+    }
+}
+
+/// A timestamp found by the recognizer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Recognized {
+    pub timestamp: LogTimestamp,
+    /// The text had no year; one was assumed and should be inferred per source
+    pub year_inferred: bool,
+    pub format: TimestampFormat,
+}
+
+/// Names of the catalog formats, in the order they are tried
+pub fn format_names() -> impl Iterator<Item = &'static str> {
+    CATALOG.iter().map(|(name, _, _)| *name)
+}
+
+/// The timestamp `text` consists of, trying `learned` (the format its source
+/// last matched) first
+pub fn recognize(text: &str, learned: Option<TimestampFormat>) -> Option<Recognized> {
+    let text = text.trim();
+    find(text, learned, |len| len == text.len()).map(|(recognized, _)| recognized)
+}
+
+/// The timestamp opening `line`, with the length of its text; it must end the
+/// line or be followed by whitespace or a closing `]`, `)`, `|` or `;`
+pub fn recognize_prefix(line: &str, learned: Option<TimestampFormat>) -> Option<(Recognized, usize)> {
+    find(line, learned, |len| {
+        line[len..].chars().next().is_none_or(|c| c.is_whitespace() || matches!(c, ']' | ')' | '|' | ';'))
+    })
+}
+
+/// First format in the catalog whose text opens `line` with an accepted length
+/// and parses. The learned format is tried first, and kept unless a format
+/// extending it accepts a longer text
+fn find(line: &str, learned: Option<TimestampFormat>, accept: impl Fn(usize) -> bool) -> Option<(Recognized, usize)> {
+    let try_format = |format: TimestampFormat| {
+        let len = patterns()[format.0].find(line)?.end();
+        if !accept(len) {
+            return None;
+        }
+        let (timestamp, year_inferred) = parse(CATALOG[format.0].2, &line[..len])?;
+        Some((Recognized { timestamp, year_inferred, format }, len))
+    };
+    let catalog = (0..CATALOG.len()).map(TimestampFormat);
+    match learned.and_then(try_format) {
+        Some((learned, len)) => extensions()[learned.format.0].iter()
+            .find_map(|format| try_format(*format).filter(|(_, longer)| *longer > len))
+            .or(Some((learned, len))),
+        None => catalog.filter_map(try_format).next(),
+    }
+}
+
+fn parse(kind: Kind, text: &str) -> Option<(LogTimestamp, bool)> {
+    let explicit = |dt: DateTime<chrono::FixedOffset>| (LogTimestamp::Explicit(dt.with_timezone(&Utc)), false);
+    match kind {
+        Kind::IsoOffset => {
+            let text = text.replacen(',', ".", 1);
+            DateTime::parse_from_rfc3339(&text)
+                .or_else(|_| DateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f%z"))
+                .or_else(|_| DateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f%z"))
+                .ok()
+                .map(explicit)
+        }
+        Kind::Rfc2822 => DateTime::parse_from_rfc2822(text).ok().map(explicit),
+        Kind::Offset(format) => DateTime::parse_from_str(text, format).ok().map(explicit),
+        Kind::Utc(format) => NaiveDateTime::parse_from_str(text, format).ok()
+            .map(|dt| (LogTimestamp::Explicit(dt.and_utc()), false)),
+        Kind::Local(format) => NaiveDateTime::parse_from_str(text, format).ok()
+            .map(|dt| (LogTimestamp::Naive(dt), false)),
+        Kind::NoYear(format) => NaiveDateTime::parse_from_str(&format!("{} {}", PLACEHOLDER_YEAR, text), format).ok()
+            .map(|dt| (LogTimestamp::Naive(assume_year(dt)), true)),
+        Kind::Go => {
+            // Drop the zone abbreviation and monotonic clock reading; the offset is authoritative
+            let mut parts = text.split(' ');
+            let without_zone = [parts.next()?, parts.next()?, parts.next()?].join(" ");
+            DateTime::parse_from_str(&without_zone, "%Y-%m-%d %H:%M:%S%.f %z").ok().map(explicit)
+        }
+        Kind::Klog => parse(Kind::NoYear("%Y %m%d %H:%M:%S%.f"), &text[1..]),
+    }
+}
+
+/// A time bound as given to `--since`/`--until` or compared against in a
+/// filter: RFC 3339, a UTC date or date and time, or a duration ago (`1h ago`)
+pub fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    // Try RFC3339 first
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    
+    // Try common date formats
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Some(DateTime::from_naive_utc_and_offset(dt, Utc));
+    }
+    if let Ok(dt) = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(DateTime::from_naive_utc_and_offset(dt.and_hms_opt(0, 0, 0)?, Utc));
+    }
+    
+    // Try humantime relative (e.g., "1 hour ago")
+    if let Ok(duration) = humantime::parse_duration(s.trim_end_matches(" ago")) {
+        let now = Utc::now();
+        return Some(now - chrono::Duration::from_std(duration).ok()?);
+    }
+    
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone, Timelike};
+    
+    fn naive(text: &str) -> LogTimestamp {
+        LogTimestamp::Naive(NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").unwrap())
+    }
+    
+    #[test]
+    fn test_catalog_formats() {
+        let utc = |h, m, s| LogTimestamp::Explicit(Utc.with_ymd_and_hms(2024, 1, 2, h, m, s).unwrap());
+        for (text, name, expected) in [
+            ("2024-01-02T03:04:05Z", "iso8601", utc(3, 4, 5)),
+            ("2024-01-02 04:04:05+0100", "iso8601", utc(3, 4, 5)),
+            ("2024-01-02 03:04:05 +0000 UTC", "go", utc(3, 4, 5)),
+            ("Tue, 02 Jan 2024 03:04:05 GMT", "rfc2822", utc(3, 4, 5)),
+            ("02/Jan/2024:04:04:05 +0100", "clf", utc(3, 4, 5)),
+            ("20240102T030405Z", "compact_utc", utc(3, 4, 5)),
+            ("2024-01-02 03:04:05,123", "log4j", naive("2024-01-02 03:04:05.123")),
+            ("20240102 030405", "compact", naive("2024-01-02 03:04:05")),
+            ("2024/01/02 03:04:05", "slashed", naive("2024-01-02 03:04:05")),
+            ("1/2/2024 3:04:05 PM", "windows", naive("2024-01-02 15:04:05")),
+            ("Jan 2, 2024 3:04:05 PM", "java", naive("2024-01-02 15:04:05")),
+            ("Tue Jan  2 03:04:05 2024", "asctime", naive("2024-01-02 03:04:05")),
+            ("02-Jan-2024 03:04:05.250", "tomcat", naive("2024-01-02 03:04:05.25")),
+        ] {
+            let recognized = recognize(text, None).unwrap_or_else(|| panic!("{}", text));
+            assert_eq!((recognized.format.name(), recognized.timestamp), (name, expected), "{}", text);
+            assert!(!recognized.year_inferred);
+        }
+        
+        let klog = recognize("I0102 03:04:05.123456", None).unwrap();
+        assert!(klog.year_inferred);
+        let LogTimestamp::Naive(time) = klog.timestamp else { panic!() };
+        assert_eq!((time.month(), time.day(), time.nanosecond()), (1, 2, 123_456_000));
+        assert!(recognize("2024-01-02 03:04:05 trailing", None).is_none());
+        assert!(recognize("404", None).is_none());
+    }
+    
+    #[test]
+    fn test_prefixes_stop_at_a_boundary_and_learned_formats_lead() {
+        let line = "2024-01-02 03:04:05,123 INFO [main] started";
+        let (recognized, len) = recognize_prefix(line, None).unwrap();
+        assert_eq!((recognized.format.name(), &line[..len]), ("log4j", "2024-01-02 03:04:05,123"));
+        assert!(recognize_prefix("2024-01-02 03:04:05x", None).is_none());
+        
+        let learned = recognize("Jan  4 10:00:00", None).map(|found| found.format);
+        assert_eq!(learned.map(TimestampFormat::name), Some("syslog"));
+        // A learned format yields to a more specific one matching more of the line
+        let found = recognize_prefix("Jan  4 10:00:00 2023 host", learned);
+        assert_eq!(found.map(|(found, _)| found.format.name()), Some("syslog_year"));
+        let found = recognize_prefix("Jan  5 10:00:00 host", learned);
+        assert_eq!(found.map(|(found, _)| found.format.name()), Some("syslog"));
+        
+        let learned = recognize("2024-01-02 03:04:05", None).map(|found| found.format);
+        assert_eq!(learned.map(TimestampFormat::name), Some("datetime"));
+        let found = recognize_prefix("2024-01-02 03:04:05 +0100 CET hello", learned);
+        let (found, len) = found.unwrap();
+        assert_eq!((found.format.name(), len), ("go", "2024-01-02 03:04:05 +0100 CET".len()));
+        assert_eq!(found.timestamp, LogTimestamp::Explicit(Utc.with_ymd_and_hms(2024, 1, 2, 2, 4, 5).unwrap()));
+    }
+    
+    #[test]
+    fn test_extensions_cover_every_format_a_later_one_cuts_short() {
+        for text in [
+            "2024-01-02 03:04:05.123 +0100 CET m=+0.001",
+            "Jan  4 10:00:00 2023",
+            "Tue Jan  2 03:04:05 2024",
+            "Tue, 02 Jan 2024 03:04:05 GMT",
+            "02/Jan/2024:04:04:05 +0100",
+            "2024-01-02 03:04:05,123",
+            "2024-01-02T03:04:05Z",
+            "20240102T030405Z",
+            "Jan 2, 2024 3:04:05 PM",
+            "1/2/2024 3:04:05 PM",
+        ] {
+            let (found, _) = recognize_prefix(text, None).unwrap();
+            for later in (found.format.0 + 1..CATALOG.len()).map(TimestampFormat) {
+                let Some(len) = patterns()[later.0].find(text).map(|m| m.end()) else { continue };
+                if len < text.len() && text[len..].starts_with(char::is_whitespace) {
+                    assert!(extensions()[later.0].contains(&found.format), "{} cuts {} short", later.name(), found.format.name());
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_parse_time_bounds() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(parse_time("2024-01-02T04:04:05+01:00"), Some(expected));
+        assert_eq!(parse_time("2024-01-02 03:04:05"), Some(expected));
+        assert_eq!(parse_time("2024-01-02"), Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).single());
+        
+        let hour_ago = parse_time("1h ago").unwrap();
+        assert!((Utc::now() - hour_ago - chrono::Duration::hours(1)).num_seconds().abs() < 60);
+        assert_eq!(parse_time("yesterday-ish"), None);
+    }
+}
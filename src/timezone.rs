@@ -0,0 +1,53 @@
+use crate::error::ParseError;
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Parse an IANA timezone name (e.g. "America/Chicago") from configuration
+pub fn parse_timezone(name: &str, parameter: &str) -> Result<Tz, ParseError> {
+    name.parse::<Tz>().map_err(|_| ParseError::ConfigurationError {
+        parameter: parameter.to_string(),
+        error_message: format!("Unknown timezone '{}' (expected an IANA name like 'Europe/Berlin')", name),
+    })
+}
+
+/// Interpret a wall-clock time in `tz`. Ambiguous times (DST fall-back) resolve to
+/// the earlier instant; times skipped by a DST jump are moved past the gap.
+pub fn localize(naive: NaiveDateTime, tz: Tz) -> DateTime<Utc> {
+    let local = match tz.from_local_datetime(&naive) {
+        LocalResult::Single(local) => Some(local),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => tz.from_local_datetime(&(naive + Duration::hours(1))).earliest(),
+    };
+    local
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    
+    fn naive(month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+    
+    #[test]
+    fn test_localize_standard_and_daylight_time() {
+        let chicago = parse_timezone("America/Chicago", "default_timezone").unwrap();
+        assert_eq!(localize(naive(1, 15, 9, 0), chicago), naive(1, 15, 15, 0).and_utc());
+        assert_eq!(localize(naive(7, 15, 9, 0), chicago), naive(7, 15, 14, 0).and_utc());
+        
+        // 01:30 happens twice on 2025-11-02; 02:30 never happens on 2025-03-09
+        assert_eq!(localize(naive(11, 2, 1, 30), chicago), naive(11, 2, 6, 30).and_utc());
+        assert_eq!(localize(naive(3, 9, 2, 30), chicago), naive(3, 9, 8, 30).and_utc());
+    }
+    
+    #[test]
+    fn test_unknown_timezone_is_configuration_error() {
+        match parse_timezone("Mars/Olympus_Mons", "default_timezone") {
+            Err(ParseError::ConfigurationError { parameter, .. }) => assert_eq!(parameter, "default_timezone"),
+            other => panic!("Expected configuration error, got {:?}", other),
+        }
+    }
+}
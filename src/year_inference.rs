@@ -0,0 +1,150 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use std::time::SystemTime;
+
+/// Leap year used when parsing year-less dates so that "Feb 29" is accepted;
+/// `assume_year` then fills in a year, which a source's `YearContext` may replace
+pub const PLACEHOLDER_YEAR: i32 = 2000;
+
+/// A jump of more than this many months between consecutive lines is taken as a year boundary
+const ROLLOVER_MONTHS: u32 = 6;
+
+/// Where a source's year comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum YearReference {
+    /// Year of the first line, given explicitly (`--year`)
+    Explicit(i32),
+    /// Date the source was last written (file mtime, or now for streams);
+    /// lines are assumed not to be newer than it
+    LastWritten(NaiveDate),
+}
+
+/// Per-source state for assigning years to timestamps that lack one (syslog, logcat)
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearContext {
+    reference: YearReference,
+    year: Option<i32>,
+    last_month: Option<u32>,
+}
+
+impl YearContext {
+    /// Context whose first line falls in `year`
+    pub fn explicit(year: i32) -> Self {
+        Self::with_reference(YearReference::Explicit(year))
+    }
+    
+    /// Context for a source last written at `modified` (typically the file mtime)
+    pub fn from_modified(modified: SystemTime) -> Self {
+        Self::last_written(DateTime::<Utc>::from(modified))
+    }
+    
+    /// Context for a source last written at `time`
+    pub fn last_written(time: DateTime<Utc>) -> Self {
+        Self::with_reference(YearReference::LastWritten(time.date_naive()))
+    }
+    
+    /// Context for a live source, relative to the current date
+    pub fn now() -> Self {
+        Self::last_written(Utc::now())
+    }
+    
+    fn with_reference(reference: YearReference) -> Self {
+        Self {
+            reference,
+            year: None,
+            last_month: None,
+        }
+    }
+    
+    /// Year for the next line, given its month; detects Dec→Jan rollover between lines
+    pub fn resolve_year(&mut self, month: u32) -> i32 {
+        let year = match (self.year, self.last_month) {
+            (Some(year), Some(last)) if last > month + ROLLOVER_MONTHS => year + 1,
+            // A straggler from before the rollover (e.g. Dec after Jan)
+            (Some(year), Some(last)) if month > last + ROLLOVER_MONTHS => year - 1,
+            (Some(year), _) => year,
+            (None, _) => match self.reference {
+                YearReference::Explicit(year) => year,
+                YearReference::LastWritten(date) if month > date.month() => date.year() - 1,
+                YearReference::LastWritten(date) => date.year(),
+            },
+        };
+        
+        self.year = Some(year);
+        self.last_month = Some(month);
+        year
+    }
+    
+    /// Replace the year of a timestamp parsed without one
+    pub fn apply(&mut self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let year = self.resolve_year(timestamp.month());
+        with_year(timestamp.naive_utc(), year).and_utc()
+    }
+}
+
+/// Fill in the year of a timestamp parsed with `PLACEHOLDER_YEAR`, as a parser used
+/// on its own reports it: the current year, unless that would put it in the future.
+/// A leap day keeps its date, in the latest leap year, so that a `YearContext`
+/// applied later still sees Feb 29
+pub fn assume_year(timestamp: NaiveDateTime) -> NaiveDateTime {
+    let year = YearContext::now().resolve_year(timestamp.month());
+    let year = match (timestamp.month(), timestamp.day()) {
+        (2, 29) => (year - 8..=year).rev().find(|year| NaiveDate::from_ymd_opt(*year, 2, 29).is_some()).unwrap_or(year),
+        _ => year,
+    };
+    with_year(timestamp, year)
+}
+
+/// Set the year, moving Feb 29 to Feb 28 in non-leap years
+fn with_year(timestamp: NaiveDateTime, year: i32) -> NaiveDateTime {
+    timestamp.with_year(year)
+        .or_else(|| (timestamp - Duration::days(1)).with_year(year))
+        .unwrap_or(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    fn at(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(PLACEHOLDER_YEAR, month, day, 12, 0, 0).unwrap()
+    }
+    
+    #[test]
+    fn test_last_written_never_dates_into_future() {
+        // Last December's log read in January
+        let mut context = YearContext::last_written(Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap());
+        assert_eq!(context.apply(at(12, 30)).year(), 2024);
+        assert_eq!(context.apply(at(12, 31)).year(), 2024);
+        assert_eq!(context.apply(at(1, 2)).year(), 2025);
+        
+        let mut context = YearContext::last_written(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap());
+        assert_eq!(context.apply(at(5, 1)).year(), 2025);
+    }
+    
+    #[test]
+    fn test_explicit_year_and_rollover() {
+        let mut context = YearContext::explicit(2023);
+        assert_eq!(context.resolve_year(11), 2023);
+        assert_eq!(context.resolve_year(12), 2023);
+        assert_eq!(context.resolve_year(1), 2024);
+        // Out-of-order line from before the boundary
+        assert_eq!(context.resolve_year(12), 2023);
+        assert_eq!(context.resolve_year(1), 2024);
+        assert_eq!(context.resolve_year(2), 2024);
+    }
+    
+    #[test]
+    fn test_leap_day_in_non_leap_year() {
+        let mut context = YearContext::explicit(2023);
+        let timestamp = context.apply(at(2, 29));
+        assert_eq!((timestamp.year(), timestamp.month(), timestamp.day()), (2023, 2, 28));
+        
+        let mut context = YearContext::explicit(2024);
+        assert_eq!(context.apply(at(2, 29)).day(), 29);
+        
+        let assumed = assume_year(at(2, 29).naive_utc());
+        assert_eq!((assumed.month(), assumed.day()), (2, 29));
+        assert!(assumed.year() <= Utc::now().year());
+    }
+}